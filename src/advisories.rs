@@ -0,0 +1,485 @@
+// advisories.rs - Offline vulnerability matching
+// Matches locked package versions against a mirrored RustSec advisory-db or an OSV export, without network access
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::lockfiles::DependencyReport;
+use crate::manifests::{normalize_python, Ecosystem};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Vulnerability {
+    pub id: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub package: String,
+    pub version: String,
+    pub ecosystem: Ecosystem,
+    pub lockfile: String,
+    pub title: String,
+    /// Affected or patched ranges as the advisory states them
+    pub affected: String,
+    /// CRITICAL/HIGH/MODERATE/LOW when the database says, or the RustSec informational kind
+    pub severity: Option<String>,
+}
+
+impl Vulnerability {
+    /// Points taken off the security score; unrated advisories count as moderate
+    pub fn penalty(&self) -> f32 {
+        match self.severity.as_deref().map(str::to_uppercase).as_deref() {
+            Some("CRITICAL") => 25.0,
+            Some("HIGH") => 15.0,
+            Some("LOW") => 3.0,
+            Some("UNMAINTAINED") | Some("UNSOUND") | Some("NOTICE") => 2.0,
+            _ => 8.0,
+        }
+    }
+}
+
+/// Advisories loaded from disk, indexed by ecosystem and package name
+pub struct AdvisoryDb {
+    advisories: HashMap<(Ecosystem, String), Vec<Advisory>>,
+}
+
+struct Advisory {
+    id: String,
+    aliases: Vec<String>,
+    title: String,
+    severity: Option<String>,
+    affects: Affects,
+}
+
+enum Affects {
+    /// RustSec: affected unless the version meets a patched or unaffected requirement
+    RustSec { safe: Vec<semver::VersionReq>, description: String },
+    /// OSV: explicit versions plus introduced/fixed/last_affected event ranges
+    Osv { versions: Vec<String>, ranges: Vec<Vec<(String, String)>> },
+}
+
+impl AdvisoryDb {
+    /// Loads a RustSec advisory-db checkout (`crates/<name>/RUSTSEC-*.md`) or any directory of OSV JSON files
+    pub fn open(path: &Path) -> Result<Self> {
+        if !path.is_dir() {
+            return Err(anyhow!("Advisory database {} is not a directory", path.display()));
+        }
+
+        let mut db = AdvisoryDb { advisories: HashMap::new() };
+        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()).filter(|e| e.file_type().is_file()) {
+            let file = entry.path();
+            let Ok(content) = fs::read_to_string(file) else { continue };
+            let parsed = match file.extension().and_then(|e| e.to_str()) {
+                Some("json") => parse_osv(&content),
+                Some("md") | Some("toml") if file.components().any(|c| c.as_os_str() == "crates") => {
+                    parse_rustsec(&content).into_iter().collect()
+                }
+                _ => continue,
+            };
+            for (key, advisory) in parsed {
+                db.advisories.entry(key).or_default().push(advisory);
+            }
+        }
+
+        if db.advisories.is_empty() {
+            return Err(anyhow!("No RustSec or OSV advisories found under {}", path.display()));
+        }
+        Ok(db)
+    }
+
+    pub fn len(&self) -> usize {
+        self.advisories.values().map(Vec::len).sum()
+    }
+
+    /// Every advisory affecting a locked package version
+    pub fn check(&self, reports: &[DependencyReport]) -> Vec<Vulnerability> {
+        let mut found = Vec::new();
+        for report in reports {
+            for package in &report.packages {
+                let key = (report.ecosystem, normalize(report.ecosystem, &package.name));
+                for advisory in self.advisories.get(&key).into_iter().flatten() {
+                    if let Some(affected) = advisory.affects.matches(&package.version) {
+                        found.push(Vulnerability {
+                            id: advisory.id.clone(),
+                            aliases: advisory.aliases.clone(),
+                            package: package.name.clone(),
+                            version: package.version.clone(),
+                            ecosystem: report.ecosystem,
+                            lockfile: report.lockfile.clone(),
+                            title: advisory.title.clone(),
+                            affected,
+                            severity: advisory.severity.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        found.sort_by(|a, b| b.penalty().total_cmp(&a.penalty()).then(a.id.cmp(&b.id)));
+        found
+    }
+}
+
+impl Affects {
+    // Returns the matching range description when `version` is affected
+    fn matches(&self, version: &str) -> Option<String> {
+        match self {
+            Affects::RustSec { safe, description } => {
+                let version = semver::Version::parse(version).ok()?;
+                (!safe.iter().any(|req| req.matches(&version))).then(|| description.clone())
+            }
+            Affects::Osv { versions, ranges } => {
+                if versions.iter().any(|v| v == version) {
+                    return Some(format!("version {} listed", version));
+                }
+                ranges.iter().find_map(|events| osv_range_matches(events, version))
+            }
+        }
+    }
+}
+
+// Events are applied in version order: `introduced` opens a range, `fixed`/`last_affected` close it
+fn osv_range_matches(events: &[(String, String)], version: &str) -> Option<String> {
+    let mut sorted: Vec<&(String, String)> = events.iter().collect();
+    sorted.sort_by(|a, b| compare_versions(&a.1, &b.1));
+
+    let mut open: Option<&str> = None;
+    for (kind, at) in sorted {
+        match kind.as_str() {
+            "introduced" if compare_versions(version, at) != Ordering::Less => open = Some(at),
+            "fixed" if compare_versions(version, at) == Ordering::Less => {
+                return open.map(|from| format!(">= {}, < {}", from, at));
+            }
+            "last_affected" if compare_versions(version, at) != Ordering::Greater => {
+                return open.map(|from| format!(">= {}, <= {}", from, at));
+            }
+            "fixed" | "last_affected" => open = None,
+            _ => {}
+        }
+    }
+    open.map(|from| format!(">= {}", from))
+}
+
+/// Orders dotted versions segment by segment, numbers numerically; a pre-release sorts before its release,
+/// whether it follows a dash (`1.0-rc.1`) or not (PEP 440 `1.0rc1`, `1.0.dev1`, RubyGems `1.0.0.rc1`)
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    if a == "0" || b == "0" {
+        // OSV uses "0" for "every version"
+        return if a == b { Ordering::Equal } else if a == "0" { Ordering::Less } else { Ordering::Greater };
+    }
+    let split = |v: &str| -> (Vec<String>, Option<String>) {
+        let v = v.trim_start_matches('v');
+        let (release, pre) = match v.find(['-', '+']) {
+            Some(i) if &v[i..i + 1] == "-" => (&v[..i], Some(v[i + 1..].to_string())),
+            Some(i) => (&v[..i], None),
+            None => (v, None),
+        };
+        let mut segments: Vec<String> = release.split(['.', '_']).map(str::to_string).collect();
+        if pre.is_some() {
+            return (segments, pre);
+        }
+        // The first segment with letters starts the pre-release; post-releases sort after and stay segments
+        let Some(i) = segments.iter().position(|s| !s.chars().all(|c| c.is_ascii_digit())) else {
+            return (segments, None);
+        };
+        let digits = segments[i].chars().take_while(char::is_ascii_digit).count();
+        let mut rest = vec![&segments[i][digits..]];
+        rest.extend(segments[i + 1..].iter().map(String::as_str));
+        let suffix = rest.join(".").trim_start_matches(['.', '-']).to_string();
+        if suffix.starts_with("post") {
+            return (segments, None);
+        }
+        segments[i].truncate(digits);
+        segments.truncate(if digits == 0 { i } else { i + 1 });
+        (segments, Some(suffix))
+    };
+    let (ra, pa) = split(a);
+    let (rb, pb) = split(b);
+
+    for i in 0..ra.len().max(rb.len()) {
+        let x = ra.get(i).map_or("0", String::as_str);
+        let y = rb.get(i).map_or("0", String::as_str);
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            _ => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    match (pa, pb) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(x), Some(y)) => x.cmp(&y),
+    }
+}
+
+fn normalize(ecosystem: Ecosystem, name: &str) -> String {
+    match ecosystem {
+        Ecosystem::PyPI => normalize_python(name),
+        Ecosystem::NuGet => name.to_lowercase(),
+        _ => name.to_string(),
+    }
+}
+
+// ----- RustSec -----
+
+// Advisories are Markdown with a ```toml front matter block, or plain TOML in older checkouts
+fn parse_rustsec(content: &str) -> Option<((Ecosystem, String), Advisory)> {
+    let (front, body) = match content.trim_start().strip_prefix("```toml") {
+        Some(rest) => rest.split_once("```")?,
+        None => (content, ""),
+    };
+    let toml: toml::Value = toml::from_str(front).ok()?;
+    let advisory = toml.get("advisory")?;
+    if advisory.get("withdrawn").is_some() {
+        return None;
+    }
+    let versions = toml.get("versions");
+
+    let requirements = |key: &str| -> Vec<String> {
+        versions
+            .and_then(|v| v.get(key))
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|r| r.as_str().map(str::to_string))
+            .collect()
+    };
+    let patched = requirements("patched");
+    let unaffected = requirements("unaffected");
+    let safe = patched
+        .iter()
+        .chain(&unaffected)
+        .filter_map(|r| semver::VersionReq::parse(r).ok())
+        .collect();
+    let description = if patched.is_empty() {
+        "no patched version".to_string()
+    } else {
+        format!("patched: {}", patched.join(" or "))
+    };
+
+    let title = advisory
+        .get("title")
+        .and_then(|t| t.as_str())
+        .map(str::to_string)
+        .or_else(|| body.lines().find_map(|l| l.strip_prefix("# ")).map(|t| t.trim().to_string()))
+        .unwrap_or_default();
+    let severity = advisory
+        .get("informational")
+        .and_then(|i| i.as_str())
+        .map(str::to_uppercase)
+        .or_else(|| advisory.get("cvss").and_then(|c| c.as_str()).map(cvss_severity));
+
+    let package = advisory.get("package")?.as_str()?.to_string();
+    Some((
+        (Ecosystem::Cargo, package),
+        Advisory {
+            id: advisory.get("id")?.as_str()?.to_string(),
+            aliases: string_array(advisory.get("aliases")),
+            title,
+            severity,
+            affects: Affects::RustSec { safe, description },
+        },
+    ))
+}
+
+fn string_array(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect()
+}
+
+// Rough rating from a CVSS v3 vector's impact metrics, for databases that don't state a severity
+fn cvss_severity(vector: &str) -> String {
+    let high = ["C:H", "I:H", "A:H"].iter().filter(|m| vector.contains(*m)).count();
+    let network = vector.contains("AV:N");
+    match (high, network) {
+        (h, true) if h >= 2 => "CRITICAL",
+        (h, _) if h >= 1 => "HIGH",
+        _ if vector.contains(":L") => "MODERATE",
+        _ => "LOW",
+    }
+    .to_string()
+}
+
+// ----- OSV -----
+
+fn parse_osv(content: &str) -> Vec<((Ecosystem, String), Advisory)> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else { return Vec::new() };
+    let Some(id) = json.get("id").and_then(|i| i.as_str()) else { return Vec::new() };
+    if json.get("withdrawn").is_some() {
+        return Vec::new();
+    }
+
+    let aliases: Vec<String> = json
+        .get("aliases")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a.as_str().map(str::to_string))
+        .collect();
+    let title = json
+        .get("summary")
+        .or_else(|| json.get("details"))
+        .and_then(|s| s.as_str())
+        .map(|s| s.lines().next().unwrap_or_default().to_string())
+        .unwrap_or_default();
+    let severity = json
+        .pointer("/database_specific/severity")
+        .and_then(|s| s.as_str())
+        .map(str::to_uppercase)
+        .or_else(|| {
+            json.get("severity")
+                .and_then(|s| s.as_array())
+                .and_then(|s| s.iter().find_map(|entry| entry.get("score")?.as_str()))
+                .filter(|score| score.starts_with("CVSS:"))
+                .map(cvss_severity)
+        });
+
+    let mut advisories = Vec::new();
+    for affected in json.get("affected").and_then(|a| a.as_array()).into_iter().flatten() {
+        let Some(package) = affected.get("package") else { continue };
+        let (Some(ecosystem), Some(name)) = (
+            package.get("ecosystem").and_then(|e| e.as_str()).and_then(osv_ecosystem),
+            package.get("name").and_then(|n| n.as_str()),
+        ) else {
+            continue;
+        };
+
+        let versions = affected
+            .get("versions")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect();
+        // Git ranges name commits, which lockfile versions can't be compared with
+        let ranges = affected
+            .get("ranges")
+            .and_then(|r| r.as_array())
+            .into_iter()
+            .flatten()
+            .filter(|r| r.get("type").and_then(|t| t.as_str()) != Some("GIT"))
+            .map(|r| {
+                r.get("events")
+                    .and_then(|e| e.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|e| {
+                        let (kind, at) = e.as_object()?.iter().next()?;
+                        Some((kind.clone(), at.as_str()?.to_string()))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        advisories.push((
+            (ecosystem, normalize(ecosystem, name)),
+            Advisory {
+                id: id.to_string(),
+                aliases: aliases.clone(),
+                title: title.clone(),
+                severity: severity.clone(),
+                affects: Affects::Osv { versions, ranges },
+            },
+        ));
+    }
+    advisories
+}
+
+fn osv_ecosystem(name: &str) -> Option<Ecosystem> {
+    // Ecosystems may carry a suffix, e.g. "Debian:11"
+    match name.split(':').next()? {
+        "crates.io" => Some(Ecosystem::Cargo),
+        "npm" => Some(Ecosystem::Npm),
+        "PyPI" => Some(Ecosystem::PyPI),
+        "Go" => Some(Ecosystem::Go),
+        "Maven" => Some(Ecosystem::Maven),
+        "Packagist" => Some(Ecosystem::Packagist),
+        "RubyGems" => Some(Ecosystem::RubyGems),
+        "NuGet" => Some(Ecosystem::NuGet),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(list: &[(&str, &str)]) -> Vec<(String, String)> {
+        list.iter().map(|(kind, at)| (kind.to_string(), at.to_string())).collect()
+    }
+
+    #[test]
+    fn compares_numeric_segments_numerically() {
+        assert_eq!(compare_versions("1.10.0", "1.9.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("v2.1.3", "2.1.3"), Ordering::Equal);
+        assert_eq!(compare_versions("0", "0.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn pre_releases_sort_before_their_release() {
+        assert_eq!(compare_versions("1.0.0-rc.1", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0a1", "1.0b1"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.dev1", "2.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0.rc1", "1.0.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0rc1", "0.9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn post_releases_sort_after_their_release() {
+        assert_eq!(compare_versions("1.0.post1", "1.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0post1", "1.0"), Ordering::Greater);
+    }
+
+    #[test]
+    fn matches_introduced_fixed_range() {
+        let range = events(&[("introduced", "1.0"), ("fixed", "1.4.2")]);
+        assert_eq!(osv_range_matches(&range, "1.2.0").as_deref(), Some(">= 1.0, < 1.4.2"));
+        assert_eq!(osv_range_matches(&range, "1.4.2"), None);
+        assert_eq!(osv_range_matches(&range, "0.9"), None);
+    }
+
+    #[test]
+    fn pre_release_of_fix_is_still_affected() {
+        let range = events(&[("introduced", "0"), ("fixed", "1.0")]);
+        assert!(osv_range_matches(&range, "1.0rc1").is_some());
+        assert!(osv_range_matches(&range, "1.0.0-beta.2").is_some());
+        assert_eq!(osv_range_matches(&range, "1.0"), None);
+    }
+
+    #[test]
+    fn matches_last_affected_and_open_ranges() {
+        let closed = events(&[("introduced", "2.0"), ("last_affected", "2.3")]);
+        assert!(osv_range_matches(&closed, "2.3").is_some());
+        assert_eq!(osv_range_matches(&closed, "2.3.1"), None);
+
+        let open = events(&[("introduced", "3.1")]);
+        assert_eq!(osv_range_matches(&open, "9.0").as_deref(), Some(">= 3.1"));
+        assert_eq!(osv_range_matches(&open, "3.0"), None);
+    }
+
+    #[test]
+    fn handles_reintroduced_ranges() {
+        let range = events(&[("introduced", "1.0"), ("fixed", "1.2"), ("introduced", "2.0"), ("fixed", "2.1")]);
+        assert!(osv_range_matches(&range, "1.1").is_some());
+        assert_eq!(osv_range_matches(&range, "1.5"), None);
+        assert_eq!(osv_range_matches(&range, "2.0.5").as_deref(), Some(">= 2.0, < 2.1"));
+    }
+
+    #[test]
+    fn skips_withdrawn_rustsec_advisories() {
+        let advisory = "```toml\n[advisory]\nid = \"RUSTSEC-2020-0001\"\npackage = \"demo\"\nwithdrawn = \"2020-02-01\"\n\n[versions]\npatched = [\">= 1.0.0\"]\n```\n# Demo\n";
+        assert!(parse_rustsec(advisory).is_none());
+        assert!(parse_rustsec(&advisory.replace("withdrawn = \"2020-02-01\"\n", "")).is_some());
+    }
+}
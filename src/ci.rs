@@ -0,0 +1,527 @@
+// ci.rs - CI/CD pipeline detection
+// Reads GitHub Actions, GitLab CI, Jenkins, CircleCI, Azure Pipelines and Buildkite configs into jobs, triggers and commands
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value as Yaml;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    Setup,
+    Build,
+    Test,
+    Lint,
+    Publish,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Step {
+    /// Shell command, or `uses <action>` / `task <name>` for prebuilt steps
+    pub command: String,
+    pub purpose: Purpose,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Job {
+    pub name: String,
+    /// Runner label, container image or agent the job runs on
+    pub runs_on: Option<String>,
+    pub steps: Vec<Step>,
+    /// Artifacts, packages and images the job publishes
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pipeline {
+    /// GitHub Actions, GitLab CI, Jenkins, CircleCI, Azure Pipelines or Buildkite
+    pub provider: String,
+    pub path: String,
+    pub triggers: Vec<String>,
+    pub jobs: Vec<Job>,
+}
+
+impl Pipeline {
+    fn new(provider: &str, path: &Path) -> Self {
+        Pipeline {
+            provider: provider.to_string(),
+            path: path.to_string_lossy().replace('\\', "/"),
+            triggers: Vec::new(),
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Distinct commands of one purpose across all jobs, in job order
+    pub fn commands(&self, purpose: Purpose) -> Vec<&str> {
+        let mut commands: Vec<&str> = Vec::new();
+        for step in self.jobs.iter().flat_map(|j| &j.steps).filter(|s| s.purpose == purpose) {
+            if !commands.contains(&step.command.as_str()) {
+                commands.push(&step.command);
+            }
+        }
+        commands
+    }
+
+    pub fn artifacts(&self) -> Vec<&str> {
+        self.jobs.iter().flat_map(|j| j.artifacts.iter().map(String::as_str)).collect()
+    }
+}
+
+impl Job {
+    fn new(name: &str, runs_on: Option<String>) -> Self {
+        Job {
+            name: name.to_string(),
+            runs_on,
+            steps: Vec::new(),
+            artifacts: Vec::new(),
+        }
+    }
+
+    // Multi-line scripts become one step per line
+    fn run(&mut self, script: &str) {
+        for line in script.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let purpose = purpose(line);
+            self.steps.push(Step { command: line.to_string(), purpose });
+        }
+    }
+
+    fn uses(&mut self, command: String, purpose: Purpose) {
+        self.steps.push(Step { command, purpose });
+    }
+}
+
+/// Every CI config among `paths`, by provider convention
+pub fn detect(source: &dyn FileSource, paths: &[PathBuf]) -> Vec<Pipeline> {
+    let mut pipelines: Vec<Pipeline> = paths
+        .iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let parent = path.parent().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+            let yaml = matches!(path.extension().and_then(|e| e.to_str()), Some("yml") | Some("yaml"));
+            let content = || source.read_to_string(path);
+
+            match name {
+                _ if yaml && parent.ends_with(".github/workflows") => parse_github(path, &content()?),
+                ".gitlab-ci.yml" | ".gitlab-ci.yaml" => parse_gitlab(path, &content()?),
+                "Jenkinsfile" => Some(parse_jenkinsfile(path, &content()?)),
+                _ if yaml && parent.ends_with(".circleci") && name.starts_with("config") => parse_circleci(path, &content()?),
+                _ if yaml && (name.starts_with("azure-pipelines") || parent.ends_with(".azure-pipelines")) => {
+                    parse_azure(path, &content()?)
+                }
+                _ if yaml && (parent.ends_with(".buildkite") && name.starts_with("pipeline") || name.starts_with("buildkite.")) => {
+                    parse_buildkite(path, &content()?)
+                }
+                _ => None,
+            }
+        })
+        .collect();
+
+    pipelines.sort_by(|a, b| a.path.cmp(&b.path));
+    pipelines
+}
+
+// Checked in order: a step that builds and pushes an image counts as publishing
+fn purpose(command: &str) -> Purpose {
+    let c = command.to_lowercase();
+    let any = |words: &[&str]| words.iter().any(|w| c.contains(w));
+    // `test` as a whole word, so `cargo test` and `npm run test:unit` count but `node:latest` doesn't
+    let test_word = c.split(|ch: char| !ch.is_ascii_alphanumeric()).any(|w| w == "test");
+
+    if any(&[
+        "publish", "docker push", "twine upload", "gh release", "deploy", "helm push", "upload-artifact",
+        "release-action", "action-gh-release", "goreleaser",
+    ]) {
+        Purpose::Publish
+    } else if any(&[
+        "pytest", "jest", "vitest", "nextest", "mvn verify", "gradlew check", "gradle check",
+        "rspec", "phpunit", "tox", "nox ", "ctest", "make check", "coverage", "cypress", "playwright",
+    ]) || test_word
+    {
+        Purpose::Test
+    } else if any(&["clippy", "lint", "fmt", "ruff", "flake8", "black --check", "prettier --check", "mypy", "rubocop", "vet"]) {
+        Purpose::Lint
+    } else if any(&["build", "compile", " package", " install -", "tsc", "webpack", "make", "bundle exec rake"]) {
+        Purpose::Build
+    } else if any(&[
+        "install", "npm ci", "yarn --frozen", "checkout", "setup-", "cache", "rustup", "toolchain", "download",
+        "apt-get", "apk add", "brew ", "restore",
+    ]) {
+        Purpose::Setup
+    } else {
+        Purpose::Other
+    }
+}
+
+fn str_of(value: Option<&Yaml>) -> Option<String> {
+    match value? {
+        Yaml::String(s) => Some(s.clone()),
+        Yaml::Number(n) => Some(n.to_string()),
+        Yaml::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+// A string or a list of strings
+fn strings(value: Option<&Yaml>) -> Vec<String> {
+    match value {
+        Some(Yaml::Sequence(items)) => items.iter().filter_map(|i| str_of(Some(i))).collect(),
+        Some(other) => str_of(Some(other)).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+fn mapping(value: Option<&Yaml>) -> impl Iterator<Item = (String, &Yaml)> {
+    value
+        .and_then(|v| v.as_mapping())
+        .into_iter()
+        .flatten()
+        .filter_map(|(k, v)| Some((str_of(Some(k))?, v)))
+}
+
+// `push (main, release/*)` style trigger from a name and its branch or tag filters
+fn trigger(name: &str, filters: Vec<String>) -> String {
+    if filters.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} ({})", name, filters.join(", "))
+    }
+}
+
+// ----- GitHub Actions -----
+
+fn parse_github(path: &Path, content: &str) -> Option<Pipeline> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut pipeline = Pipeline::new("GitHub Actions", path);
+
+    match yaml.get("on") {
+        Some(Yaml::Mapping(events)) => {
+            for (event, config) in events {
+                let Some(event) = str_of(Some(event)) else { continue };
+                let mut filters = strings(config.get("branches"));
+                filters.extend(strings(config.get("tags")).into_iter().map(|t| format!("tag {}", t)));
+                if event == "schedule" {
+                    filters = config
+                        .as_sequence()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|s| str_of(s.get("cron")))
+                        .collect();
+                }
+                pipeline.triggers.push(trigger(&event, filters));
+            }
+        }
+        other => pipeline.triggers.extend(strings(other)),
+    }
+
+    for (id, spec) in mapping(yaml.get("jobs")) {
+        let name = str_of(spec.get("name")).unwrap_or(id);
+        let runs_on = strings(spec.get("runs-on")).first().cloned();
+        let mut job = Job::new(&name, runs_on);
+        // Reusable workflow calls have no steps of their own
+        if let Some(workflow) = str_of(spec.get("uses")) {
+            job.uses(format!("uses {}", workflow), Purpose::Other);
+        }
+        for step in spec.get("steps").and_then(|s| s.as_sequence()).into_iter().flatten() {
+            if let Some(script) = str_of(step.get("run")) {
+                job.run(&script);
+            } else if let Some(action) = str_of(step.get("uses")) {
+                let with = step.get("with");
+                let action_name = action.split('@').next().unwrap_or_default();
+                let purpose = match action_name {
+                    "docker/build-push-action" if str_of(with.and_then(|w| w.get("push"))).as_deref() == Some("true") => {
+                        Purpose::Publish
+                    }
+                    _ => purpose(action_name),
+                };
+                if action_name == "actions/upload-artifact" {
+                    job.artifacts.extend(str_of(with.and_then(|w| w.get("name"))).or_else(|| str_of(with.and_then(|w| w.get("path")))));
+                } else if purpose == Purpose::Publish {
+                    job.artifacts.extend(str_of(with.and_then(|w| w.get("tags"))).or_else(|| str_of(with.and_then(|w| w.get("files")))));
+                }
+                job.uses(format!("uses {}", action), purpose);
+            }
+        }
+        pipeline.jobs.push(job);
+    }
+    Some(pipeline)
+}
+
+// ----- GitLab CI -----
+
+// Top-level keys that configure the pipeline rather than name a job
+const GITLAB_KEYWORDS: &[&str] = &[
+    "stages", "variables", "default", "include", "workflow", "image", "services", "before_script", "after_script",
+    "cache",
+];
+
+fn parse_gitlab(path: &Path, content: &str) -> Option<Pipeline> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut pipeline = Pipeline::new("GitLab CI", path);
+    let default_image = str_of(yaml.get("image")).or_else(|| str_of(yaml.get("default").and_then(|d| d.get("image"))));
+
+    for rule in yaml.get("workflow").and_then(|w| w.get("rules")).and_then(|r| r.as_sequence()).into_iter().flatten() {
+        pipeline.triggers.extend(str_of(rule.get("if")));
+    }
+
+    for (name, spec) in mapping(Some(&yaml)) {
+        // `.hidden` jobs are templates for `extends`
+        if name.starts_with('.') || GITLAB_KEYWORDS.contains(&name.as_str()) || spec.get("script").is_none() && spec.get("trigger").is_none() {
+            continue;
+        }
+        let image = str_of(spec.get("image")).or_else(|| str_of(spec.get("image").and_then(|i| i.get("name"))));
+        let mut job = Job::new(&name, image.or_else(|| default_image.clone()));
+        for key in ["before_script", "script"] {
+            for line in strings(spec.get(key)) {
+                job.run(&line);
+            }
+        }
+        job.artifacts.extend(strings(spec.get("artifacts").and_then(|a| a.get("paths"))));
+        if spec.get("environment").is_some() {
+            let environment = str_of(spec.get("environment")).or_else(|| str_of(spec.get("environment").and_then(|e| e.get("name"))));
+            job.artifacts.extend(environment.map(|e| format!("deploys to {}", e)));
+        }
+
+        for rule in spec.get("rules").and_then(|r| r.as_sequence()).into_iter().flatten() {
+            if let Some(condition) = str_of(rule.get("if")) {
+                if !pipeline.triggers.contains(&condition) {
+                    pipeline.triggers.push(condition);
+                }
+            }
+        }
+        for only in strings(spec.get("only")) {
+            if !pipeline.triggers.contains(&only) {
+                pipeline.triggers.push(only);
+            }
+        }
+        pipeline.jobs.push(job);
+    }
+    Some(pipeline)
+}
+
+// ----- Jenkins -----
+
+static JENKINS_STAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"stage\s*\(\s*['"]([^'"]+)['"]"#).unwrap());
+static JENKINS_SH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)\b(?:sh|bat|pwsh|powershell)\s*\(?\s*(?:script:\s*)?(?:'''(.*?)'''|"""(.*?)"""|'([^'\n]*)'|"([^"\n]*)")"#).unwrap()
+});
+static JENKINS_ARCHIVE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"archiveArtifacts\s*\(?\s*(?:artifacts:\s*)?['"]([^'"]+)['"]"#).unwrap());
+static JENKINS_TRIGGER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\b(cron|pollSCM|upstream|githubPush)\s*\(\s*(?:['"]([^'"]*)['"])?"#).unwrap());
+static JENKINS_AGENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?s)agent\s*\{\s*(?:docker\s*\{?\s*(?:image\s*)?|label\s*)['"]([^'"]+)['"]"#).unwrap());
+
+// Declarative or scripted Groovy; each `sh` belongs to the last `stage` opened before it
+fn parse_jenkinsfile(path: &Path, content: &str) -> Pipeline {
+    let mut pipeline = Pipeline::new("Jenkins", path);
+    let agent = JENKINS_AGENT.captures(content).map(|c| c[1].to_string());
+
+    let stages: Vec<(usize, String)> = JENKINS_STAGE.captures_iter(content).map(|c| (c.get(0).map_or(0, |m| m.start()), c[1].to_string())).collect();
+    let mut jobs: Vec<Job> = stages.iter().map(|(_, name)| Job::new(name, agent.clone())).collect();
+    let stage_at = |offset: usize| stages.iter().rposition(|(start, _)| *start < offset);
+
+    for caps in JENKINS_SH.captures_iter(content) {
+        let script = (1..=4).find_map(|i| caps.get(i)).map_or("", |m| m.as_str());
+        let offset = caps.get(0).map_or(0, |m| m.start());
+        match stage_at(offset) {
+            Some(i) => jobs[i].run(script),
+            None => {
+                if jobs.first().is_none_or(|j| !j.name.is_empty()) {
+                    jobs.insert(0, Job::new("", agent.clone()));
+                }
+                jobs[0].run(script);
+            }
+        }
+    }
+    for caps in JENKINS_ARCHIVE.captures_iter(content) {
+        let offset = caps.get(0).map_or(0, |m| m.start());
+        let i = stage_at(offset).unwrap_or(0);
+        if let Some(job) = jobs.get_mut(i) {
+            job.artifacts.push(caps[1].to_string());
+        }
+    }
+
+    for caps in JENKINS_TRIGGER.captures_iter(content) {
+        pipeline.triggers.push(trigger(&caps[1], caps.get(2).map(|m| m.as_str().to_string()).into_iter().collect()));
+    }
+    pipeline.jobs = jobs;
+    pipeline
+}
+
+// ----- CircleCI -----
+
+fn parse_circleci(path: &Path, content: &str) -> Option<Pipeline> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut pipeline = Pipeline::new("CircleCI", path);
+
+    for (name, spec) in mapping(yaml.get("jobs")) {
+        let image = spec
+            .get("docker")
+            .and_then(|d| d.as_sequence())
+            .and_then(|d| d.first())
+            .and_then(|d| str_of(d.get("image")))
+            .or_else(|| str_of(spec.get("machine").and_then(|m| m.get("image"))))
+            .or_else(|| str_of(spec.get("executor")));
+        let mut job = Job::new(&name, image);
+        for step in spec.get("steps").and_then(|s| s.as_sequence()).into_iter().flatten() {
+            match step {
+                Yaml::String(builtin) => job.uses(builtin.clone(), purpose(builtin)),
+                _ => {
+                    for (kind, config) in mapping(Some(step)) {
+                        match kind.as_str() {
+                            "run" => {
+                                let command = str_of(Some(config)).or_else(|| str_of(config.get("command")));
+                                job.run(&command.unwrap_or_default());
+                            }
+                            "store_artifacts" => {
+                                job.artifacts.extend(str_of(config.get("path")));
+                                job.uses(kind, Purpose::Publish);
+                            }
+                            // Orb commands such as `node/install-packages`
+                            _ => {
+                                let purpose = purpose(&kind);
+                                job.uses(kind, purpose);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        pipeline.jobs.push(job);
+    }
+
+    for (_, workflow) in mapping(yaml.get("workflows")) {
+        for trigger_spec in workflow.get("triggers").and_then(|t| t.as_sequence()).into_iter().flatten() {
+            let schedule = trigger_spec.get("schedule");
+            pipeline.triggers.push(trigger("schedule", str_of(schedule.and_then(|s| s.get("cron"))).into_iter().collect()));
+        }
+        for job in workflow.get("jobs").and_then(|j| j.as_sequence()).into_iter().flatten() {
+            for (_, config) in mapping(Some(job)) {
+                let filters = config.get("filters");
+                let branches = strings(filters.and_then(|f| f.get("branches")).and_then(|b| b.get("only")));
+                let tags = strings(filters.and_then(|f| f.get("tags")).and_then(|t| t.get("only")));
+                let mut filter: Vec<String> = branches;
+                filter.extend(tags.into_iter().map(|t| format!("tag {}", t)));
+                let trigger = trigger("push", filter);
+                if !pipeline.triggers.contains(&trigger) {
+                    pipeline.triggers.push(trigger);
+                }
+            }
+        }
+    }
+    if pipeline.triggers.is_empty() {
+        pipeline.triggers.push("push".to_string());
+    }
+    Some(pipeline)
+}
+
+// ----- Azure Pipelines -----
+
+fn parse_azure(path: &Path, content: &str) -> Option<Pipeline> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut pipeline = Pipeline::new("Azure Pipelines", path);
+
+    // `trigger: [main]`, `trigger: { branches: { include: [...] } }` or `trigger: none`
+    for (name, key) in [("push", "trigger"), ("pull request", "pr")] {
+        let Some(config) = yaml.get(key) else { continue };
+        if str_of(Some(config)).as_deref() == Some("none") {
+            continue;
+        }
+        let mut filters = strings(config.get("branches").and_then(|b| b.get("include")));
+        if filters.is_empty() {
+            filters = strings(Some(config));
+        }
+        pipeline.triggers.push(trigger(name, filters));
+    }
+    for schedule in yaml.get("schedules").and_then(|s| s.as_sequence()).into_iter().flatten() {
+        pipeline.triggers.push(trigger("schedule", str_of(schedule.get("cron")).into_iter().collect()));
+    }
+
+    // stages -> jobs -> steps, with either level optional
+    let mut jobs: Vec<(String, &Yaml)> = Vec::new();
+    let stages = yaml.get("stages").and_then(|s| s.as_sequence());
+    let job_lists: Vec<&Yaml> = match stages {
+        Some(stages) => stages.iter().filter_map(|s| s.get("jobs")).collect(),
+        None => yaml.get("jobs").into_iter().collect(),
+    };
+    for list in job_lists {
+        for job in list.as_sequence().into_iter().flatten() {
+            let name = str_of(job.get("job").or_else(|| job.get("deployment"))).unwrap_or_else(|| "job".to_string());
+            jobs.push((name, job));
+        }
+    }
+    if jobs.is_empty() && yaml.get("steps").is_some() {
+        jobs.push(("build".to_string(), &yaml));
+    }
+
+    let default_pool = str_of(yaml.get("pool").and_then(|p| p.get("vmImage"))).or_else(|| str_of(yaml.get("pool")));
+    for (name, spec) in jobs {
+        let pool = str_of(spec.get("pool").and_then(|p| p.get("vmImage"))).or_else(|| default_pool.clone());
+        let mut job = Job::new(&name, pool);
+        let steps = spec
+            .get("steps")
+            .or_else(|| spec.get("strategy").and_then(|s| s.get("runOnce")).and_then(|r| r.get("deploy")).and_then(|d| d.get("steps")));
+        for step in steps.and_then(|s| s.as_sequence()).into_iter().flatten() {
+            if let Some(script) = ["script", "bash", "powershell", "pwsh"].iter().find_map(|k| str_of(step.get(*k))) {
+                job.run(&script);
+            } else if let Some(task) = str_of(step.get("task")) {
+                let inputs = step.get("inputs");
+                let input = |keys: &[&str]| keys.iter().find_map(|k| str_of(inputs.and_then(|i| i.get(*k))));
+                let purpose = if task.starts_with("Publish") || input(&["command"]).as_deref() == Some("push") {
+                    job.artifacts.extend(input(&["artifactName", "ArtifactName", "targetPath", "PathtoPublish", "repository"]));
+                    Purpose::Publish
+                } else {
+                    purpose(&format!("{} {}", task, input(&["command"]).unwrap_or_default()))
+                };
+                job.uses(format!("task {}", task), purpose);
+            } else if let Some(published) = str_of(step.get("publish")) {
+                job.artifacts.push(str_of(step.get("artifact")).unwrap_or(published.clone()));
+                job.uses(format!("publish {}", published), Purpose::Publish);
+            } else if let Some(checkout) = str_of(step.get("checkout")) {
+                job.uses(format!("checkout {}", checkout), Purpose::Setup);
+            }
+        }
+        pipeline.jobs.push(job);
+    }
+    Some(pipeline)
+}
+
+// ----- Buildkite -----
+
+fn parse_buildkite(path: &Path, content: &str) -> Option<Pipeline> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut pipeline = Pipeline::new("Buildkite", path);
+    let steps = yaml.get("steps").or(Some(&yaml)).and_then(|s| s.as_sequence())?;
+
+    for (i, step) in steps.iter().enumerate() {
+        // `wait` and `block` steps only gate the ones after them
+        if step.as_str().is_some() || step.get("wait").is_some() || step.get("block").is_some() {
+            continue;
+        }
+        let name = str_of(step.get("label"))
+            .or_else(|| str_of(step.get("key")))
+            .unwrap_or_else(|| format!("step {}", i + 1));
+        let agent = step.get("agents").and_then(|a| str_of(a.get("queue")));
+        let mut job = Job::new(&name, agent);
+        for command in strings(step.get("command").or_else(|| step.get("commands"))) {
+            job.run(&command);
+        }
+        for plugin in step.get("plugins").and_then(|p| p.as_sequence()).into_iter().flatten() {
+            for (plugin, _) in mapping(Some(plugin)) {
+                let purpose = purpose(&plugin);
+                job.uses(format!("plugin {}", plugin), purpose);
+            }
+        }
+        if let Some(trigger_pipeline) = str_of(step.get("trigger")) {
+            job.uses(format!("trigger {}", trigger_pipeline), Purpose::Other);
+        }
+        job.artifacts.extend(strings(step.get("artifact_paths")));
+        pipeline.jobs.push(job);
+    }
+    Some(pipeline)
+}
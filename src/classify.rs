@@ -0,0 +1,196 @@
+// classify.rs - Generated and vendored code classification
+// Keeps protobuf output, minified bundles, lockfiles and third-party trees out of the metrics
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::languages;
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExclusionKind {
+    Generated,
+    Vendored,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Exclusion {
+    pub kind: ExclusionKind,
+    pub reason: String,
+}
+
+// Markers tools put in the header comment of files they write
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "DO NOT EDIT",
+    "Code generated by",
+    "Generated by the protocol buffer compiler",
+    "This file is automatically generated",
+    "This file was automatically generated",
+    "auto-generated",
+    "autogenerated",
+];
+
+// Line starts that open a comment in the languages generators emit
+const COMMENT_PREFIXES: &[&str] = &["//", "#", "/*", "*", "<!--", "--", ";"];
+
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go", ".pb.cc", ".pb.h", "_pb2.py", "_pb2_grpc.py", ".pb.rs", "_pb.js", "_pb.d.ts",
+    ".g.dart", ".freezed.dart", ".designer.cs", ".g.cs", ".generated.ts", ".generated.js",
+    ".min.js", ".min.css", ".bundle.js", ".map", ".snap",
+];
+
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock", "package-lock.json", "npm-shrinkwrap.json", "yarn.lock", "pnpm-lock.yaml",
+    "poetry.lock", "Pipfile.lock", "Gemfile.lock", "composer.lock", "go.sum", "flake.lock",
+    "packages.lock.json", "mix.lock", "pubspec.lock",
+];
+
+const GENERATED_DIRS: &[&str] = &["__snapshots__", "__generated__"];
+
+const VENDORED_DIRS: &[&str] = &[
+    "vendor", "vendors", "third_party", "third-party", "thirdparty",
+    "bower_components", "jspm_packages", "Godeps", "Pods", "Carthage",
+];
+
+// Lines this long only come out of bundlers and minifiers
+const MINIFIABLE_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "css"];
+const MINIFIED_AVG_LINE_LENGTH: usize = 110;
+const MINIFIED_MAX_LINE_LENGTH: usize = 1000;
+
+pub struct Classifier {
+    linguist_generated: Gitignore,
+    linguist_vendored: Gitignore,
+    include: Gitignore,
+    enabled: bool,
+}
+
+impl Classifier {
+    /// Builds a classifier from the source's root `.gitattributes` and the configured
+    /// `include` globs, which always count as hand-written source.
+    pub fn new(source: &dyn FileSource, include: &[String], enabled: bool) -> Self {
+        let mut generated = GitignoreBuilder::new("");
+        let mut vendored = GitignoreBuilder::new("");
+
+        if let Some(attributes) = source.read_to_string(Path::new(".gitattributes")) {
+            for line in attributes.lines().map(str::trim) {
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let mut parts = line.split_whitespace();
+                let Some(pattern) = parts.next() else { continue };
+                for attr in parts {
+                    let unset = attr.starts_with(['-', '!']);
+                    let attr = attr.trim_start_matches(['-', '!']);
+                    let (name, value) = attr.split_once('=').unwrap_or((attr, "true"));
+                    let builder = match name {
+                        "linguist-generated" => &mut generated,
+                        "linguist-vendored" => &mut vendored,
+                        _ => continue,
+                    };
+                    // A negated pattern lets `-linguist-generated` override the path heuristics
+                    let line = if unset || value == "false" { format!("!{}", pattern) } else { pattern.to_string() };
+                    let _ = builder.add_line(None, &line);
+                }
+            }
+        }
+
+        let mut include_builder = GitignoreBuilder::new("");
+        for pattern in include {
+            let _ = include_builder.add_line(None, pattern);
+        }
+
+        Classifier {
+            linguist_generated: generated.build().unwrap_or_else(|_| Gitignore::empty()),
+            linguist_vendored: vendored.build().unwrap_or_else(|_| Gitignore::empty()),
+            include: include_builder.build().unwrap_or_else(|_| Gitignore::empty()),
+            enabled,
+        }
+    }
+
+    /// Returns why a file should be left out of the metrics, or `None` for hand-written source.
+    pub fn classify(&self, path: &Path, content: &str) -> Option<Exclusion> {
+        if !self.enabled || self.include.matched_path_or_any_parents(path, false).is_ignore() {
+            return None;
+        }
+
+        // .gitattributes wins over every heuristic, including explicit `-linguist-*` overrides
+        match self.linguist_generated.matched_path_or_any_parents(path, false) {
+            m if m.is_ignore() => return Some(exclusion(ExclusionKind::Generated, "linguist-generated in .gitattributes")),
+            m if m.is_whitelist() => return None,
+            _ => {}
+        }
+        match self.linguist_vendored.matched_path_or_any_parents(path, false) {
+            m if m.is_ignore() => return Some(exclusion(ExclusionKind::Vendored, "linguist-vendored in .gitattributes")),
+            m if m.is_whitelist() => return None,
+            _ => {}
+        }
+
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let dirs: Vec<String> = path
+            .parent()
+            .map(|p| p.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect())
+            .unwrap_or_default();
+
+        if let Some(dir) = dirs.iter().find(|d| VENDORED_DIRS.contains(&d.as_str())) {
+            return Some(exclusion(ExclusionKind::Vendored, &format!("inside {}/", dir)));
+        }
+        if LOCKFILES.contains(&file_name.as_ref()) || path.extension().is_some_and(|e| e == "lock") {
+            return Some(exclusion(ExclusionKind::Generated, "dependency lockfile"));
+        }
+        if let Some(suffix) = GENERATED_SUFFIXES.iter().find(|s| file_name.ends_with(*s)) {
+            return Some(exclusion(ExclusionKind::Generated, &format!("{} file", suffix)));
+        }
+        if let Some(dir) = dirs.iter().find(|d| GENERATED_DIRS.contains(&d.as_str())) {
+            return Some(exclusion(ExclusionKind::Generated, &format!("inside {}/", dir)));
+        }
+        if let Some(marker) = generated_marker(path, content) {
+            return Some(exclusion(ExclusionKind::Generated, &format!("\"{}\" header", marker)));
+        }
+        if is_minified(path, content) {
+            return Some(exclusion(ExclusionKind::Generated, "minified (very long lines)"));
+        }
+
+        None
+    }
+}
+
+fn exclusion(kind: ExclusionKind, reason: &str) -> Exclusion {
+    Exclusion {
+        kind,
+        reason: reason.to_string(),
+    }
+}
+
+// Looks for a marker in the comments among the first lines; prose (Markdown, plain text) only
+// counts inside an HTML comment, and files with no comment syntax only on their first line
+fn generated_marker(path: &Path, content: &str) -> Option<&'static str> {
+    let prefixes: &[&str] = match languages::detect(path, content) {
+        Some(lang) if lang.name == "JSON" || lang.name == "CSV" => &[],
+        Some(lang) if matches!(lang.name, "Markdown" | "reStructuredText" | "AsciiDoc" | "HTML" | "XML") => &["<!--"],
+        Some(_) => COMMENT_PREFIXES,
+        None => &[],
+    };
+    let lines = content.lines().take(if prefixes.is_empty() { 1 } else { 10 });
+    lines
+        .filter(|line| {
+            let line = line.trim_start();
+            prefixes.is_empty() || prefixes.iter().any(|p| line.starts_with(p))
+        })
+        .find_map(|line| GENERATED_MARKERS.iter().copied().find(|m| line.contains(m)))
+}
+
+fn is_minified(path: &Path, content: &str) -> bool {
+    let ext = path.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+    if !MINIFIABLE_EXTENSIONS.contains(&ext.as_str()) {
+        return false;
+    }
+
+    let lines: Vec<usize> = content.lines().map(str::len).collect();
+    if lines.is_empty() {
+        return false;
+    }
+    let average = lines.iter().sum::<usize>() / lines.len();
+    average > MINIFIED_AVG_LINE_LENGTH || lines.iter().any(|&len| len > MINIFIED_MAX_LINE_LENGTH)
+}
@@ -0,0 +1,137 @@
+// cycles.rs - Dependency cycle detection
+// Finds strongly connected components in the import graph and a small set of imports that breaks them
+
+use petgraph::algo::{greedy_feedback_arc_set, tarjan_scc};
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::graph::{Dependency, DependencyGraph};
+use crate::parser::ImportKind;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Medium,
+    High,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DependencyCycle {
+    /// High when the cycle crosses package (crate, npm, Go module...) boundaries
+    pub severity: Severity,
+    /// Files of the strongly connected component
+    pub files: Vec<String>,
+    pub packages: Vec<String>,
+    /// One loop per suggested cut, as the chain of imports that closes it
+    pub cycles: Vec<Vec<Dependency>>,
+    /// A small set of imports whose removal leaves the component acyclic; found greedily, so not always the fewest
+    pub suggested_cuts: Vec<Dependency>,
+}
+
+impl DependencyCycle {
+    pub fn is_cross_package(&self) -> bool {
+        self.packages.len() > 1
+    }
+}
+
+/// Reports every strongly connected component of the file graph, most severe and largest first
+pub fn find_cycles(graph: &DependencyGraph) -> Vec<DependencyCycle> {
+    let mut file_graph: DiGraph<&str, usize> = DiGraph::new();
+    let nodes: HashMap<&str, NodeIndex> = graph
+        .files
+        .iter()
+        .map(|f| (f.as_str(), file_graph.add_node(f.as_str())))
+        .collect();
+    // `mod` declarations only say where a module lives; a parent declaring its child isn't a dependency
+    for (i, edge) in graph.edges.iter().enumerate().filter(|(_, e)| e.kind == ImportKind::Use) {
+        if let (Some(&from), Some(&to)) = (nodes.get(edge.from.as_str()), nodes.get(edge.to.as_str())) {
+            file_graph.add_edge(from, to, i);
+        }
+    }
+
+    let mut cycles: Vec<DependencyCycle> = tarjan_scc(&file_graph)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .map(|component| component_cycle(graph, &file_graph, &component))
+        .collect();
+
+    cycles.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then(b.files.len().cmp(&a.files.len()))
+            .then(a.files.cmp(&b.files))
+    });
+    cycles
+}
+
+fn component_cycle(graph: &DependencyGraph, file_graph: &DiGraph<&str, usize>, component: &[NodeIndex]) -> DependencyCycle {
+    // Copy the component into its own graph so the feedback arc set only sees its edges
+    let mut sub: DiGraph<&str, usize> = DiGraph::new();
+    let members: HashMap<NodeIndex, NodeIndex> = component
+        .iter()
+        .map(|&n| (n, sub.add_node(file_graph[n])))
+        .collect();
+    for &n in component {
+        for edge in file_graph.edges(n) {
+            if let Some(&to) = members.get(&edge.target()) {
+                sub.add_edge(members[&n], to, *edge.weight());
+            }
+        }
+    }
+
+    let mut cuts: Vec<usize> = greedy_feedback_arc_set(&sub).map(|e| *e.weight()).collect();
+    cuts.sort_by(|&a, &b| (&graph.edges[a].from, &graph.edges[a].to).cmp(&(&graph.edges[b].from, &graph.edges[b].to)));
+
+    let cycles = cuts
+        .iter()
+        .filter_map(|&cut| {
+            let edge = &graph.edges[cut];
+            let path = shortest_path(graph, &sub, &edge.to, &edge.from)?;
+            Some(std::iter::once(edge.clone()).chain(path).collect())
+        })
+        .collect();
+
+    let mut files: Vec<String> = component.iter().map(|&n| file_graph[n].to_string()).collect();
+    files.sort();
+    let packages: BTreeSet<String> = files.iter().map(|f| graph.package_of(f).to_string()).collect();
+
+    DependencyCycle {
+        severity: if packages.len() > 1 { Severity::High } else { Severity::Medium },
+        files,
+        packages: packages.into_iter().collect(),
+        cycles,
+        suggested_cuts: cuts.into_iter().map(|i| graph.edges[i].clone()).collect(),
+    }
+}
+
+// Breadth-first search for the fewest imports leading from `from` to `to` inside the component
+fn shortest_path(graph: &DependencyGraph, sub: &DiGraph<&str, usize>, from: &str, to: &str) -> Option<Vec<Dependency>> {
+    let start = sub.node_indices().find(|&n| sub[n] == from)?;
+    let goal = sub.node_indices().find(|&n| sub[n] == to)?;
+
+    let mut via: HashMap<NodeIndex, (NodeIndex, usize)> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        if node == goal {
+            break;
+        }
+        for edge in sub.edges(node) {
+            let next = edge.target();
+            if next != start && !via.contains_key(&next) {
+                via.insert(next, (node, *edge.weight()));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let mut path = Vec::new();
+    let mut node = goal;
+    while node != start {
+        let &(previous, edge) = via.get(&node)?;
+        path.push(graph.edges[edge].clone());
+        node = previous;
+    }
+    path.reverse();
+    Some(path)
+}
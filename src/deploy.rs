@@ -0,0 +1,562 @@
+// deploy.rs - Deployment and infrastructure detection
+// Parses container, orchestration, infrastructure-as-code and platform configs to describe how the system runs
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value as Yaml;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Service {
+    pub name: String,
+    /// Container image, or the base image of a Dockerfile's final stage
+    pub image: Option<String>,
+    #[serde(default)]
+    pub ports: Vec<String>,
+    /// Environment variable names; values are left out as they often hold secrets
+    #[serde(default)]
+    pub env: Vec<String>,
+    pub command: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Deployment {
+    /// Docker, Docker Compose, Kubernetes, Helm, Terraform, ...
+    pub kind: String,
+    pub path: String,
+    #[serde(default)]
+    pub services: Vec<Service>,
+    /// Anything else worth knowing: providers, resource counts, chart versions
+    #[serde(default)]
+    pub details: Vec<String>,
+}
+
+impl Deployment {
+    fn new(kind: &str, path: &Path) -> Self {
+        Deployment {
+            kind: kind.to_string(),
+            path: path.to_string_lossy().replace('\\', "/"),
+            services: Vec::new(),
+            details: Vec::new(),
+        }
+    }
+}
+
+/// Every deployment config among `paths`, in path order
+pub fn detect(source: &dyn FileSource, paths: &[PathBuf]) -> Vec<Deployment> {
+    let mut deployments = Vec::new();
+    let mut terraform: BTreeMap<PathBuf, Vec<&PathBuf>> = BTreeMap::new();
+
+    for path in paths {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        if extension == "tf" || extension == "tofu" {
+            terraform.entry(path.parent().unwrap_or(Path::new("")).to_path_buf()).or_default().push(path);
+            continue;
+        }
+        let Some(content) = source.read_to_string(path) else { continue };
+
+        let found = match name {
+            n if n == "Dockerfile" || n.starts_with("Dockerfile.") || n.ends_with(".dockerfile") => {
+                Some(parse_dockerfile(path, &content))
+            }
+            n if n.starts_with("docker-compose") || n.starts_with("compose.") => parse_compose(path, &content),
+            "Chart.yaml" => parse_helm(source, path, &content),
+            "kustomization.yaml" | "kustomization.yml" | "Kustomization" => parse_kustomize(path, &content),
+            "Pulumi.yaml" | "Pulumi.yml" => parse_pulumi(path, &content),
+            "serverless.yml" | "serverless.yaml" => parse_serverless(path, &content),
+            "fly.toml" => parse_fly(path, &content),
+            "Procfile" => Some(parse_procfile(path, &content)),
+            "flake.nix" => Some(parse_flake(path, &content)),
+            _ if extension == "service" => parse_systemd(path, &content),
+            // Helm templates aren't YAML until rendered
+            _ if matches!(extension, "yaml" | "yml") && !path.components().any(|c| c.as_os_str() == "templates") => {
+                parse_kubernetes(path, &content)
+            }
+            _ => None,
+        };
+        deployments.extend(found);
+    }
+
+    for (dir, files) in terraform {
+        deployments.extend(parse_terraform(source, &dir, &files));
+    }
+
+    deployments.sort_by(|a, b| a.path.cmp(&b.path));
+    deployments
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+fn dir_name(path: &Path) -> String {
+    path.parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "app".to_string())
+}
+
+// ----- Containers -----
+
+fn parse_dockerfile(path: &Path, content: &str) -> Deployment {
+    let mut deployment = Deployment::new("Docker", path);
+    let mut service = Service {
+        name: dir_name(path),
+        ..Service::default()
+    };
+    let mut stages: BTreeMap<String, String> = BTreeMap::new();
+    let mut images = Vec::new();
+
+    // Join `\` continuations so each instruction is one line
+    let joined = content.replace("\\\r\n", " ").replace("\\\n", " ");
+    for line in joined.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let (instruction, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let args = args.trim();
+        match instruction.to_uppercase().as_str() {
+            "FROM" => {
+                let words: Vec<&str> = args.split_whitespace().filter(|w| !w.starts_with("--")).collect();
+                let Some(image) = words.first() else { continue };
+                // `FROM builder` continues an earlier stage rather than pulling an image
+                let image = match stages.get(&image.to_lowercase()) {
+                    Some(base) => base.clone(),
+                    None => {
+                        push_unique(&mut images, image.to_string());
+                        image.to_string()
+                    }
+                };
+                if let Some(i) = words.iter().position(|w| w.eq_ignore_ascii_case("AS")) {
+                    if let Some(stage) = words.get(i + 1) {
+                        stages.insert(stage.to_lowercase(), image.clone());
+                    }
+                }
+                service.image = Some(image);
+            }
+            "EXPOSE" => {
+                for port in args.split_whitespace() {
+                    push_unique(&mut service.ports, port.to_string());
+                }
+            }
+            "ENV" => {
+                // `ENV KEY=value OTHER=value` or the legacy `ENV KEY value`
+                if args.contains('=') {
+                    for pair in args.split_whitespace().filter(|p| p.contains('=')) {
+                        push_unique(&mut service.env, pair.split('=').next().unwrap_or_default().to_string());
+                    }
+                } else if let Some(key) = args.split_whitespace().next() {
+                    push_unique(&mut service.env, key.to_string());
+                }
+            }
+            "CMD" | "ENTRYPOINT" => service.command = Some(exec_form(args)),
+            _ => {}
+        }
+    }
+
+    if images.len() > 1 {
+        deployment.details.push(format!("multi-stage build from {}", images.join(", ")));
+    }
+    deployment.services.push(service);
+    deployment
+}
+
+// `["npm", "start"]` reads better as `npm start`
+fn exec_form(args: &str) -> String {
+    match serde_json::from_str::<Vec<String>>(args) {
+        Ok(parts) => parts.join(" "),
+        Err(_) => args.to_string(),
+    }
+}
+
+fn parse_compose(path: &Path, content: &str) -> Option<Deployment> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let services = yaml.get("services")?.as_mapping()?;
+    let mut deployment = Deployment::new("Docker Compose", path);
+
+    for (name, spec) in services {
+        let build = spec.get("build").map(|b| {
+            let context = b.as_str().or_else(|| b.get("context").and_then(|c| c.as_str())).unwrap_or(".");
+            format!("build {}", context)
+        });
+        deployment.services.push(Service {
+            name: name.as_str().unwrap_or_default().to_string(),
+            image: spec.get("image").and_then(|i| i.as_str()).map(str::to_string).or(build),
+            ports: sequence(spec.get("ports"))
+                .iter()
+                .map(|port| match port {
+                    Yaml::Mapping(_) => {
+                        let target = port.get("target").map(scalar).unwrap_or_default();
+                        match port.get("published") {
+                            Some(published) => format!("{}:{}", scalar(published), target),
+                            None => target,
+                        }
+                    }
+                    other => scalar(other),
+                })
+                .collect(),
+            env: env_names(spec.get("environment")),
+            command: spec.get("command").map(|c| match c {
+                Yaml::Sequence(parts) => parts.iter().map(scalar).collect::<Vec<_>>().join(" "),
+                other => scalar(other),
+            }),
+        });
+    }
+
+    let volumes = yaml.get("volumes").and_then(|v| v.as_mapping()).map_or(0, |v| v.len());
+    if volumes > 0 {
+        deployment.details.push(format!("{} named volumes", volumes));
+    }
+    Some(deployment)
+}
+
+fn sequence(value: Option<&Yaml>) -> Vec<Yaml> {
+    value.and_then(|v| v.as_sequence()).cloned().unwrap_or_default()
+}
+
+fn scalar(value: &Yaml) -> String {
+    match value {
+        Yaml::String(s) => s.clone(),
+        Yaml::Number(n) => n.to_string(),
+        Yaml::Bool(b) => b.to_string(),
+        _ => String::new(),
+    }
+}
+
+// Compose accepts `environment` as a map or as a list of `KEY=value`
+fn env_names(value: Option<&Yaml>) -> Vec<String> {
+    match value {
+        Some(Yaml::Mapping(map)) => map.keys().map(scalar).collect(),
+        Some(Yaml::Sequence(list)) => list
+            .iter()
+            .map(|entry| scalar(entry).split('=').next().unwrap_or_default().to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// ----- Kubernetes, Helm and Kustomize -----
+
+fn parse_kubernetes(path: &Path, content: &str) -> Option<Deployment> {
+    let mut deployment = Deployment::new("Kubernetes", path);
+    let mut kinds: BTreeMap<String, usize> = BTreeMap::new();
+
+    // A manifest may hold several `---` separated documents
+    for document in serde_yaml::Deserializer::from_str(content) {
+        let Ok(yaml) = Yaml::deserialize(document) else { continue };
+        let (Some(_), Some(kind)) = (yaml.get("apiVersion"), yaml.get("kind").and_then(|k| k.as_str())) else {
+            continue;
+        };
+        *kinds.entry(kind.to_string()).or_insert(0) += 1;
+        let name = yaml.get("metadata").and_then(|m| m.get("name")).map(scalar).unwrap_or_default();
+
+        // Pod specs sit at different depths: Pod, workload template, CronJob job template
+        let pod = match kind {
+            "Pod" => yaml.get("spec"),
+            "CronJob" => yaml
+                .get("spec")
+                .and_then(|s| s.get("jobTemplate"))
+                .and_then(|j| j.get("spec"))
+                .and_then(|s| s.get("template"))
+                .and_then(|t| t.get("spec")),
+            _ => yaml.get("spec").and_then(|s| s.get("template")).and_then(|t| t.get("spec")),
+        };
+        for container in pod.map(|p| sequence(p.get("containers"))).unwrap_or_default() {
+            deployment.services.push(Service {
+                name: format!("{}/{}", name, container.get("name").map(scalar).unwrap_or_default()),
+                image: container.get("image").map(scalar),
+                ports: sequence(container.get("ports"))
+                    .iter()
+                    .filter_map(|p| p.get("containerPort").map(scalar))
+                    .collect(),
+                env: sequence(container.get("env")).iter().filter_map(|e| e.get("name").map(scalar)).collect(),
+                command: container.get("command").map(|c| sequence(Some(c)).iter().map(scalar).collect::<Vec<_>>().join(" ")),
+            });
+        }
+        if kind == "Service" {
+            let ports: Vec<String> = yaml
+                .get("spec")
+                .map(|s| sequence(s.get("ports")))
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|p| p.get("port").map(scalar))
+                .collect();
+            if !ports.is_empty() {
+                deployment.details.push(format!("Service {} on port {}", name, ports.join(", ")));
+            }
+        }
+    }
+
+    if kinds.is_empty() {
+        return None;
+    }
+    let summary: Vec<String> = kinds.iter().map(|(kind, n)| format!("{} {}", n, kind)).collect();
+    deployment.details.insert(0, summary.join(", "));
+    Some(deployment)
+}
+
+fn parse_helm(source: &dyn FileSource, path: &Path, content: &str) -> Option<Deployment> {
+    let chart: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut deployment = Deployment::new("Helm", path);
+    let name = chart.get("name").map(scalar).unwrap_or_else(|| dir_name(path));
+    deployment.details.push(format!(
+        "chart {} {}{}",
+        name,
+        chart.get("version").map(scalar).unwrap_or_default(),
+        chart.get("appVersion").map(|v| format!(" (app {})", scalar(v))).unwrap_or_default()
+    ));
+    let dependencies: Vec<String> = sequence(chart.get("dependencies")).iter().filter_map(|d| d.get("name").map(scalar)).collect();
+    if !dependencies.is_empty() {
+        deployment.details.push(format!("depends on charts {}", dependencies.join(", ")));
+    }
+
+    // Conventional values.yaml keys for the image and service port
+    let values: Option<Yaml> = source
+        .read_to_string(&path.with_file_name("values.yaml"))
+        .and_then(|v| serde_yaml::from_str(&v).ok());
+    if let Some(values) = values {
+        let image = values.get("image").and_then(|i| {
+            let repository = i.get("repository").map(scalar)?;
+            Some(match i.get("tag").map(scalar).filter(|t| !t.is_empty()) {
+                Some(tag) => format!("{}:{}", repository, tag),
+                None => repository,
+            })
+        });
+        let port = values.get("service").and_then(|s| s.get("port")).map(scalar);
+        deployment.services.push(Service {
+            name,
+            image,
+            ports: port.into_iter().collect(),
+            ..Service::default()
+        });
+    }
+    Some(deployment)
+}
+
+fn parse_kustomize(path: &Path, content: &str) -> Option<Deployment> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut deployment = Deployment::new("Kustomize", path);
+    for key in ["resources", "bases", "components"] {
+        let entries: Vec<String> = sequence(yaml.get(key)).iter().map(scalar).collect();
+        if !entries.is_empty() {
+            deployment.details.push(format!("{}: {}", key, entries.join(", ")));
+        }
+    }
+    for image in sequence(yaml.get("images")) {
+        let name = image.get("newName").or_else(|| image.get("name")).map(scalar).unwrap_or_default();
+        let tag = image.get("newTag").map(|t| format!(":{}", scalar(t))).unwrap_or_default();
+        deployment.details.push(format!("image {}{}", name, tag));
+    }
+    Some(deployment)
+}
+
+// ----- Infrastructure as code -----
+
+static TF_BLOCK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^\s*(resource|module|provider|data)\s+"([^"]+)""#).unwrap());
+static TF_REQUIRED_PROVIDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^\s*source\s*=\s*"(?:[^"/]+/)?([^"/]+/[^"/]+)""#).unwrap());
+
+// A directory of .tf files is one root module; `.tofu` files mean OpenTofu
+fn parse_terraform(source: &dyn FileSource, dir: &Path, files: &[&PathBuf]) -> Option<Deployment> {
+    let tofu = files.iter().any(|f| f.extension().is_some_and(|e| e == "tofu"));
+    let mut deployment = Deployment::new(if tofu { "OpenTofu" } else { "Terraform" }, dir);
+    let mut providers = BTreeSet::new();
+    let mut resources: BTreeMap<String, usize> = BTreeMap::new();
+    let mut modules = 0;
+
+    for file in files {
+        let Some(content) = source.read_to_string(file) else { continue };
+        for caps in TF_BLOCK.captures_iter(&content) {
+            match &caps[1] {
+                "resource" => *resources.entry(caps[2].to_string()).or_insert(0) += 1,
+                "module" => modules += 1,
+                "provider" => {
+                    providers.insert(caps[2].to_string());
+                }
+                _ => {}
+            }
+        }
+        for caps in TF_REQUIRED_PROVIDER.captures_iter(&content) {
+            // hashicorp/aws -> aws
+            providers.extend(caps[1].rsplit('/').next().map(str::to_string));
+        }
+    }
+
+    if providers.is_empty() && resources.is_empty() && modules == 0 {
+        return None;
+    }
+    if !providers.is_empty() {
+        deployment.details.push(format!("providers: {}", providers.into_iter().collect::<Vec<_>>().join(", ")));
+    }
+    if !resources.is_empty() {
+        let mut by_count: Vec<(String, usize)> = resources.into_iter().collect();
+        by_count.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let total: usize = by_count.iter().map(|(_, n)| n).sum();
+        let top: Vec<String> = by_count.iter().take(5).map(|(kind, n)| format!("{} {}", n, kind)).collect();
+        deployment.details.push(format!("{} resources ({})", total, top.join(", ")));
+    }
+    if modules > 0 {
+        deployment.details.push(format!("{} modules", modules));
+    }
+    Some(deployment)
+}
+
+fn parse_pulumi(path: &Path, content: &str) -> Option<Deployment> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut deployment = Deployment::new("Pulumi", path);
+    let runtime = yaml.get("runtime").map(|r| r.get("name").map(scalar).unwrap_or_else(|| scalar(r)));
+    deployment.details.push(format!(
+        "project {}{}",
+        yaml.get("name").map(scalar).unwrap_or_default(),
+        runtime.map(|r| format!(" ({} runtime)", r)).unwrap_or_default()
+    ));
+    Some(deployment)
+}
+
+// ----- Platforms -----
+
+fn parse_serverless(path: &Path, content: &str) -> Option<Deployment> {
+    let yaml: Yaml = serde_yaml::from_str(content).ok()?;
+    let mut deployment = Deployment::new("Serverless Framework", path);
+    let provider = yaml.get("provider");
+    let provider_env = env_names(provider.and_then(|p| p.get("environment")));
+    deployment.details.push(format!(
+        "service {} on {} {}",
+        yaml.get("service").map(|s| s.get("name").map(scalar).unwrap_or_else(|| scalar(s))).unwrap_or_default(),
+        provider.and_then(|p| p.get("name")).map(scalar).unwrap_or_default(),
+        provider.and_then(|p| p.get("runtime")).map(scalar).unwrap_or_default()
+    ).trim_end().to_string());
+
+    for (name, function) in yaml.get("functions").and_then(|f| f.as_mapping()).into_iter().flatten() {
+        let routes: Vec<String> = sequence(function.get("events"))
+            .iter()
+            .filter_map(|event| {
+                let http = event.get("http").or_else(|| event.get("httpApi"))?;
+                Some(match http {
+                    Yaml::String(route) => route.clone(),
+                    _ => format!(
+                        "{} {}",
+                        http.get("method").map(scalar).unwrap_or_default().to_uppercase(),
+                        http.get("path").map(scalar).unwrap_or_default()
+                    )
+                    .trim()
+                    .to_string(),
+                })
+            })
+            .collect();
+        let mut env = provider_env.clone();
+        for key in env_names(function.get("environment")) {
+            push_unique(&mut env, key);
+        }
+        if !routes.is_empty() {
+            deployment.details.push(format!("{} serves {}", scalar(name), routes.join(", ")));
+        }
+        deployment.services.push(Service {
+            name: scalar(name),
+            image: None,
+            ports: Vec::new(),
+            env,
+            command: function.get("handler").map(scalar),
+        });
+    }
+    Some(deployment)
+}
+
+fn parse_fly(path: &Path, content: &str) -> Option<Deployment> {
+    let toml: toml::Value = toml::from_str(content).ok()?;
+    let mut deployment = Deployment::new("Fly.io", path);
+    let str_at = |value: Option<&toml::Value>| value.and_then(|v| v.as_str()).map(str::to_string);
+
+    let build = toml.get("build");
+    let mut ports: Vec<String> = Vec::new();
+    ports.extend(toml.get("http_service").and_then(|h| h.get("internal_port")).map(|p| p.to_string()));
+    for service in toml.get("services").and_then(|s| s.as_array()).into_iter().flatten() {
+        ports.extend(service.get("internal_port").map(|p| p.to_string()));
+    }
+    deployment.services.push(Service {
+        name: str_at(toml.get("app")).unwrap_or_else(|| dir_name(path)),
+        image: str_at(build.and_then(|b| b.get("image")))
+            .or_else(|| str_at(build.and_then(|b| b.get("dockerfile"))).map(|d| format!("build {}", d))),
+        ports,
+        env: toml.get("env").and_then(|e| e.as_table()).map(|e| e.keys().cloned().collect()).unwrap_or_default(),
+        command: None,
+    });
+    if let Some(region) = str_at(toml.get("primary_region")) {
+        deployment.details.push(format!("primary region {}", region));
+    }
+    Some(deployment)
+}
+
+fn parse_procfile(path: &Path, content: &str) -> Deployment {
+    let mut deployment = Deployment::new("Procfile", path);
+    for line in content.lines().map(str::trim).filter(|l| !l.starts_with('#')) {
+        if let Some((process, command)) = line.split_once(':') {
+            deployment.services.push(Service {
+                name: process.trim().to_string(),
+                command: Some(command.trim().to_string()),
+                ..Service::default()
+            });
+        }
+    }
+    deployment
+}
+
+static FLAKE_OUTPUT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(packages|devShells?|nixosConfigurations|nixosModules|apps|checks|overlays|dockerImage)\b").unwrap()
+});
+static FLAKE_INPUT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\s*(?:inputs\.)?([\w-]+)\.url\s*=").unwrap());
+
+fn parse_flake(path: &Path, content: &str) -> Deployment {
+    let mut deployment = Deployment::new("Nix", path);
+    let outputs: BTreeSet<&str> = FLAKE_OUTPUT.find_iter(content).map(|m| m.as_str()).collect();
+    if !outputs.is_empty() {
+        deployment.details.push(format!("outputs: {}", outputs.into_iter().collect::<Vec<_>>().join(", ")));
+    }
+    let inputs: Vec<&str> = FLAKE_INPUT.captures_iter(content).filter_map(|c| c.get(1)).map(|m| m.as_str()).collect();
+    if !inputs.is_empty() {
+        deployment.details.push(format!("inputs: {}", inputs.join(", ")));
+    }
+    deployment
+}
+
+fn parse_systemd(path: &Path, content: &str) -> Option<Deployment> {
+    let mut section = "";
+    let mut service = Service {
+        name: path.file_stem()?.to_string_lossy().to_string(),
+        ..Service::default()
+    };
+    let mut description = None;
+    let mut is_unit = false;
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line;
+            is_unit |= line == "[Service]";
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        match (section, key.trim()) {
+            ("[Unit]", "Description") => description = Some(value.trim().to_string()),
+            ("[Service]", "ExecStart") => service.command = Some(value.trim().trim_start_matches(['-', '@', '+', '!']).to_string()),
+            ("[Service]", "Environment") => {
+                for pair in value.split_whitespace() {
+                    push_unique(&mut service.env, pair.trim_matches('"').split('=').next().unwrap_or_default().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !is_unit {
+        return None;
+    }
+    let mut deployment = Deployment::new("systemd", path);
+    deployment.details.extend(description);
+    deployment.services.push(service);
+    Some(deployment)
+}
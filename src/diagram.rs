@@ -0,0 +1,206 @@
+// diagram.rs - Dependency graph rendering
+// Collapses the file graph to the requested level and writes it as Graphviz DOT, Mermaid or JSON
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::graph::{module_of, DependencyGraph};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    File,
+    Module,
+    Package,
+}
+
+impl Level {
+    pub fn parse(level: &str) -> Result<Self> {
+        match level {
+            "file" => Ok(Level::File),
+            "module" => Ok(Level::Module),
+            "package" => Ok(Level::Package),
+            _ => Err(anyhow!("Unknown graph level '{}' (expected file, module or package)", level)),
+        }
+    }
+}
+
+pub struct DiagramOptions {
+    pub level: Level,
+    /// Group nodes into subgraphs by their directory
+    pub cluster: bool,
+    /// Only keep files under this path prefix
+    pub filter: Option<String>,
+    /// Collapse node paths to at most this many components
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiagramEdge {
+    pub from: String,
+    pub to: String,
+    /// Number of file-level imports behind this edge
+    pub imports: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Diagram {
+    pub level: Level,
+    pub nodes: Vec<String>,
+    pub edges: Vec<DiagramEdge>,
+    #[serde(skip)]
+    cluster: bool,
+}
+
+impl Diagram {
+    pub fn build(graph: &DependencyGraph, options: &DiagramOptions) -> Self {
+        let node_of = |file: &str| -> Option<String> {
+            if let Some(prefix) = &options.filter {
+                if !Path::new(file).starts_with(prefix.trim_end_matches('/')) {
+                    return None;
+                }
+            }
+            let node = match options.level {
+                Level::File => file.to_string(),
+                Level::Module => module_of(file),
+                Level::Package => graph.package_of(file).to_string(),
+            };
+            Some(match options.max_depth {
+                Some(depth) => truncate(&node, depth),
+                None => node,
+            })
+        };
+
+        let nodes: BTreeSet<String> = graph.files.iter().filter_map(|f| node_of(f)).collect();
+        let mut edges: BTreeMap<(String, String), usize> = BTreeMap::new();
+        for edge in &graph.edges {
+            if let (Some(from), Some(to)) = (node_of(&edge.from), node_of(&edge.to)) {
+                if from != to {
+                    *edges.entry((from, to)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        Diagram {
+            level: options.level,
+            nodes: nodes.into_iter().collect(),
+            edges: edges
+                .into_iter()
+                .map(|((from, to), imports)| DiagramEdge { from, to, imports })
+                .collect(),
+            cluster: options.cluster,
+        }
+    }
+
+    pub fn render(&self, format: &str) -> Result<String> {
+        match format {
+            "dot" => Ok(self.to_dot()),
+            "mermaid" => Ok(self.to_mermaid()),
+            "json" => Ok(serde_json::to_string_pretty(self)?),
+            _ => Err(anyhow!("Unsupported graph format: {} (expected dot, mermaid or json)", format)),
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let ids = self.ids();
+        let mut out = String::from("digraph codemap {\n");
+        out.push_str("    rankdir=LR;\n");
+        out.push_str("    node [shape=box, style=rounded, fontname=\"Helvetica\"];\n");
+
+        for (i, (dir, nodes)) in self.groups().into_iter().enumerate() {
+            let indent = if dir.is_some() { "        " } else { "    " };
+            if let Some(dir) = &dir {
+                let _ = writeln!(out, "    subgraph cluster_{} {{", i);
+                let _ = writeln!(out, "        label=\"{}\";", escape(dir));
+            }
+            for node in nodes {
+                let _ = writeln!(out, "{}{} [label=\"{}\"];", indent, ids[node], escape(node));
+            }
+            if dir.is_some() {
+                out.push_str("    }\n");
+            }
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(
+                out,
+                "    {} -> {} [label=\"{}\"];",
+                ids[edge.from.as_str()],
+                ids[edge.to.as_str()],
+                edge.imports
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn to_mermaid(&self) -> String {
+        let ids = self.ids();
+        let mut out = String::from("graph LR\n");
+
+        for (i, (dir, nodes)) in self.groups().into_iter().enumerate() {
+            let indent = if dir.is_some() { "        " } else { "    " };
+            if let Some(dir) = &dir {
+                let _ = writeln!(out, "    subgraph g{}[\"{}\"]", i, mermaid_label(dir));
+            }
+            for node in nodes {
+                let _ = writeln!(out, "{}{}[\"{}\"]", indent, ids[node], mermaid_label(node));
+            }
+            if dir.is_some() {
+                out.push_str("    end\n");
+            }
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(
+                out,
+                "    {} -->|{}| {}",
+                ids[edge.from.as_str()],
+                edge.imports,
+                ids[edge.to.as_str()]
+            );
+        }
+        out
+    }
+
+    // Stable identifiers, since paths aren't valid DOT or Mermaid ids
+    fn ids(&self) -> BTreeMap<&str, String> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.as_str(), format!("n{}", i)))
+            .collect()
+    }
+
+    // Nodes grouped by directory when clustering, otherwise one ungrouped set
+    fn groups(&self) -> Vec<(Option<String>, Vec<&str>)> {
+        if !self.cluster {
+            return vec![(None, self.nodes.iter().map(String::as_str).collect())];
+        }
+        let mut groups: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for node in &self.nodes {
+            groups.entry(module_of(node)).or_default().push(node);
+        }
+        groups.into_iter().map(|(dir, nodes)| (Some(dir), nodes)).collect()
+    }
+}
+
+fn truncate(path: &str, depth: usize) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    if depth == 0 || parts.len() <= depth {
+        path.to_string()
+    } else {
+        parts[..depth].join("/")
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_label(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
@@ -0,0 +1,420 @@
+// entrypoints.rs - Entry point discovery
+// Finds where execution starts from build targets, package manifests, main functions and framework conventions
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::manifests::{Ecosystem, Manifest};
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// An executable: a binary target, CLI script or main function
+    Binary,
+    /// The root of a library's public API
+    Library,
+    /// Where a web framework or app server boots the application
+    Framework,
+    /// A module that also runs as a standalone script
+    Script,
+    Example,
+    /// Runs at build time rather than in the product
+    Build,
+}
+
+#[derive(Debug, Clone)]
+pub struct Found {
+    pub path: String,
+    pub kind: Kind,
+    /// 1-10, higher is where a reader should start
+    pub rank: u8,
+    pub reason: String,
+}
+
+// Collects candidates; a path found twice keeps its highest-ranked reason
+#[derive(Default)]
+struct Discovery {
+    found: BTreeMap<String, Found>,
+}
+
+impl Discovery {
+    fn add(&mut self, source: &dyn FileSource, path: &Path, kind: Kind, rank: u8, reason: String) {
+        let path = normalize(path);
+        if path.is_empty() || !source.exists(Path::new(&path)) {
+            return;
+        }
+        let candidate = Found { path: path.clone(), kind, rank, reason };
+        match self.found.get(&path) {
+            Some(existing) if existing.rank >= candidate.rank => {}
+            _ => {
+                self.found.insert(path, candidate);
+            }
+        }
+    }
+}
+
+/// Every entry point found, best first
+pub fn discover(source: &dyn FileSource, paths: &[PathBuf], manifests: &[Manifest]) -> Vec<Found> {
+    let mut discovery = Discovery::default();
+    let depth = |path: &str| path.matches('/').count();
+    let root_depth = manifests.iter().map(|m| depth(&m.path)).min().unwrap_or(0);
+
+    for manifest in manifests {
+        let dir = Path::new(&manifest.path).parent().unwrap_or(Path::new("")).to_path_buf();
+        // Workspace members rank just below the root package
+        let nested = u8::from(depth(&manifest.path) > root_depth);
+        let Some(content) = source.read_to_string(Path::new(&manifest.path)) else { continue };
+        match manifest.ecosystem {
+            Ecosystem::Cargo => cargo_targets(&mut discovery, source, paths, &dir, &content, nested),
+            Ecosystem::Npm => npm_entries(&mut discovery, source, &dir, &content, nested),
+            Ecosystem::PyPI if manifest.path.ends_with("pyproject.toml") => {
+                python_scripts(&mut discovery, source, paths, &dir, &content, nested)
+            }
+            _ => {}
+        }
+    }
+
+    scan_sources(&mut discovery, source, paths);
+    framework_conventions(&mut discovery, source, paths, manifests);
+
+    let mut found: Vec<Found> = discovery.found.into_values().collect();
+    found.sort_by(|a, b| b.rank.cmp(&a.rank).then_with(|| depth(&a.path).cmp(&depth(&b.path))).then_with(|| a.path.cmp(&b.path)));
+    found
+}
+
+fn normalize(path: &Path) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    for component in path.to_string_lossy().replace('\\', "/").split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part.to_string()),
+        }
+    }
+    parts.join("/")
+}
+
+/// Test files and fixtures, by directory and file naming conventions
+pub fn is_test_path(path: &str) -> bool {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    path.split('/').any(|part| matches!(part, "test" | "tests" | "__tests__" | "spec" | "testdata"))
+        || file.starts_with("test_")
+        || file.contains("_test.")
+        || file.contains(".test.")
+        || file.contains(".spec.")
+}
+
+// ----- Cargo -----
+
+fn cargo_targets(discovery: &mut Discovery, source: &dyn FileSource, paths: &[PathBuf], dir: &Path, content: &str, nested: u8) {
+    let Ok(toml) = content.parse::<toml::Table>() else { return };
+    let Some(package) = toml.get("package") else { return };
+    let package_name = package.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+    let table_path = |table: &toml::Value| table.get("path").and_then(|p| p.as_str()).map(|p| dir.join(p));
+    let auto = |key: &str| package.get(key).and_then(|v| v.as_bool()).unwrap_or(true);
+
+    // Explicit targets first, so their reasons name the target
+    let mut explicit: Vec<PathBuf> = Vec::new();
+    for bin in toml.get("bin").and_then(|b| b.as_array()).into_iter().flatten() {
+        let name = bin.get("name").and_then(|n| n.as_str()).unwrap_or(package_name);
+        let path = table_path(bin).unwrap_or_else(|| {
+            if name == package_name { dir.join("src/main.rs") } else { dir.join(format!("src/bin/{}.rs", name)) }
+        });
+        discovery.add(source, &path, Kind::Binary, 10 - nested, format!("Cargo [[bin]] target `{}`", name));
+        explicit.push(path);
+    }
+    let lib = toml.get("lib");
+    let lib_path = lib.and_then(table_path).unwrap_or_else(|| dir.join("src/lib.rs"));
+    let lib_name = lib.and_then(|l| l.get("name")).and_then(|n| n.as_str()).unwrap_or(package_name);
+    discovery.add(source, &lib_path, Kind::Library, 9 - nested, format!("Library root of crate `{}`", lib_name.replace('-', "_")));
+    for example in toml.get("example").and_then(|e| e.as_array()).into_iter().flatten() {
+        let name = example.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+        let path = table_path(example).unwrap_or_else(|| dir.join(format!("examples/{}.rs", name)));
+        discovery.add(source, &path, Kind::Example, 4, format!("Cargo [[example]] `{}`", name));
+        explicit.push(path);
+    }
+
+    // Targets Cargo discovers on its own
+    if auto("autobins") && !explicit.contains(&dir.join("src/main.rs")) {
+        discovery.add(source, &dir.join("src/main.rs"), Kind::Binary, 10 - nested, format!("Binary `{}` (Cargo default target)", package_name));
+    }
+    let within = |sub: &str| {
+        let prefix = dir.join(sub);
+        paths.iter().filter(move |p| p.starts_with(&prefix) && p.extension().is_some_and(|e| e == "rs")).cloned()
+    };
+    if auto("autobins") {
+        for path in within("src/bin").filter(|p| !explicit.contains(p)) {
+            if let Some(name) = auto_target_name(&path, &dir.join("src/bin")) {
+                discovery.add(source, &path, Kind::Binary, 8 - nested, format!("Cargo binary `{}` in src/bin", name));
+            }
+        }
+    }
+    if auto("autoexamples") {
+        for path in within("examples").filter(|p| !explicit.contains(p)) {
+            if let Some(name) = auto_target_name(&path, &dir.join("examples")) {
+                discovery.add(source, &path, Kind::Example, 4, format!("Cargo example `{}`", name));
+            }
+        }
+    }
+    let build = package.get("build").and_then(|b| b.as_str()).map(|b| dir.join(b)).unwrap_or_else(|| dir.join("build.rs"));
+    discovery.add(source, &build, Kind::Build, 3, "Cargo build script, run before compiling the crate".to_string());
+}
+
+// `src/bin/tool.rs` and `src/bin/tool/main.rs` are both target `tool`; other files under a target dir are modules
+fn auto_target_name(path: &Path, base: &Path) -> Option<String> {
+    let relative = path.strip_prefix(base).ok()?;
+    let parts: Vec<String> = relative.iter().map(|p| p.to_string_lossy().to_string()).collect();
+    match parts.as_slice() {
+        [file] => Some(file.trim_end_matches(".rs").to_string()),
+        [dir, main] if main == "main.rs" => Some(dir.clone()),
+        _ => None,
+    }
+}
+
+// ----- npm -----
+
+static NODE_COMMAND: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:node|nodemon|ts-node|tsx|bun|deno run)\s+(?:--?[\w-]+(?:=\S+)?\s+)*([\w./-]+\.[cm]?[jt]sx?)\b").unwrap());
+
+fn npm_entries(discovery: &mut Discovery, source: &dyn FileSource, dir: &Path, content: &str, nested: u8) {
+    let Ok(json) = serde_json::from_str::<Json>(content) else { return };
+    let name = json.get("name").and_then(|n| n.as_str()).unwrap_or("package");
+
+    match json.get("bin") {
+        Some(Json::String(path)) => discovery.add(source, &dir.join(path), Kind::Binary, 10 - nested, format!("package.json bin of `{}`", name)),
+        Some(Json::Object(bins)) => {
+            for (command, path) in bins {
+                if let Some(path) = path.as_str() {
+                    discovery.add(source, &dir.join(path), Kind::Binary, 10 - nested, format!("package.json bin `{}`", command));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    // `exports` is a path, or conditions (`import`, `require`, `default`) per subpath
+    let mut exports: Vec<(String, String)> = Vec::new();
+    collect_exports(json.get("exports"), ".", &mut exports);
+    for (subpath, path) in exports {
+        let reason = if subpath == "." {
+            format!("package.json exports of `{}`", name)
+        } else {
+            format!("package.json exports `{}`", subpath.replacen('.', name, 1))
+        };
+        discovery.add(source, &dir.join(path), Kind::Library, 9 - nested, reason);
+    }
+    for field in ["main", "module", "browser"] {
+        if let Some(path) = json.get(field).and_then(|m| m.as_str()) {
+            discovery.add(source, &dir.join(path), Kind::Library, 9 - nested, format!("package.json {} of `{}`", field, name));
+        }
+    }
+
+    for script in ["start", "dev", "serve"] {
+        let command = json.get("scripts").and_then(|s| s.get(script)).and_then(|s| s.as_str()).unwrap_or_default();
+        if let Some(caps) = NODE_COMMAND.captures(command) {
+            discovery.add(source, &dir.join(&caps[1]), Kind::Binary, 9 - nested, format!("Run by `npm {}{}` ({})", if script == "start" { "" } else { "run " }, script, command));
+        }
+    }
+}
+
+fn collect_exports(value: Option<&Json>, subpath: &str, out: &mut Vec<(String, String)>) {
+    match value {
+        Some(Json::String(path)) => out.push((subpath.to_string(), path.clone())),
+        Some(Json::Object(map)) => {
+            for (key, value) in map {
+                // Keys starting with `.` are subpaths; anything else is a condition
+                let subpath = if key.starts_with('.') { key.as_str() } else { subpath };
+                collect_exports(Some(value), subpath, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+// ----- Python -----
+
+fn python_scripts(discovery: &mut Discovery, source: &dyn FileSource, paths: &[PathBuf], dir: &Path, content: &str, nested: u8) {
+    let Ok(toml) = content.parse::<toml::Table>() else { return };
+    let project = toml.get("project");
+    let poetry = toml.get("tool").and_then(|t| t.get("poetry"));
+    let tables = [
+        (project.and_then(|p| p.get("scripts")), "[project.scripts]"),
+        (project.and_then(|p| p.get("gui-scripts")), "[project.gui-scripts]"),
+        (poetry.and_then(|p| p.get("scripts")), "[tool.poetry.scripts]"),
+    ];
+
+    for (table, section) in tables {
+        for (command, target) in table.and_then(|t| t.as_table()).into_iter().flatten() {
+            // `pkg.module:function`; Poetry also allows `{ reference = ..., type = "console" }`
+            let Some(target) = target.as_str().or_else(|| target.get("reference").and_then(|r| r.as_str())) else { continue };
+            let module = target.split(':').next().unwrap_or(target).trim();
+            if let Some(path) = python_module(paths, dir, module) {
+                discovery.add(source, &path, Kind::Binary, 10 - nested, format!("{} console script `{}` ({})", section, command, target));
+            }
+        }
+    }
+}
+
+// A dotted module as a file, at the project root or in the src layout
+fn python_module(paths: &[PathBuf], dir: &Path, module: &str) -> Option<PathBuf> {
+    let relative = module.replace('.', "/");
+    ["", "src/"]
+        .iter()
+        .flat_map(|prefix| {
+            [
+                dir.join(format!("{}{}.py", prefix, relative)),
+                dir.join(format!("{}{}/__init__.py", prefix, relative)),
+            ]
+        })
+        .find(|candidate| paths.iter().any(|p| p == candidate))
+}
+
+// ----- Source scanning -----
+
+static PY_MAIN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^if\s+__name__\s*==\s*['"]__main__['"]\s*:"#).unwrap());
+static GO_PACKAGE_MAIN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^package\s+main\b").unwrap());
+static GO_FUNC_MAIN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^func\s+main\s*\(\s*\)").unwrap());
+static JAVA_MAIN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bpublic\s+static\s+void\s+main\s*\(\s*(?:final\s+)?String").unwrap());
+static KOTLIN_MAIN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^fun\s+main\s*\(").unwrap());
+static CSHARP_MAIN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bstatic\s+(?:async\s+)?(?:void|int|Task(?:<int>)?)\s+Main\s*\(").unwrap());
+static C_MAIN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\s*int\s+main\s*\(").unwrap());
+// ASP.NET and other .NET 6+ programs have top-level statements instead of Main
+static CSHARP_TOP_LEVEL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^(?:var\s+builder\s*=|await\s+|Console\.Write|WebApplication\.)").unwrap());
+
+fn scan_sources(discovery: &mut Discovery, source: &dyn FileSource, paths: &[PathBuf]) {
+    for path in paths {
+        let display = normalize(path);
+        if is_test_path(&display) {
+            continue;
+        }
+        let file = path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+        let extension = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let read = || source.read_to_string(path).unwrap_or_default();
+
+        match extension.as_str() {
+            "py" if file == "__main__.py" => {
+                let package = path.parent().and_then(|p| p.file_name()).map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+                discovery.add(source, path, Kind::Binary, 8, format!("Runs as `python -m {}`", package));
+            }
+            "py" if PY_MAIN.is_match(&read()) => {
+                discovery.add(source, path, Kind::Script, 6, "Runs as a script (`if __name__ == \"__main__\"`)".to_string());
+            }
+            "go" => {
+                let content = read();
+                if GO_PACKAGE_MAIN.is_match(&content) && GO_FUNC_MAIN.is_match(&content) {
+                    let command = path.parent().and_then(|p| p.file_name()).map(|p| p.to_string_lossy().to_string());
+                    let reason = match command {
+                        Some(command) if !command.is_empty() => format!("Go `package main` building command `{}`", command),
+                        _ => "Go `package main` with func main".to_string(),
+                    };
+                    discovery.add(source, path, Kind::Binary, 9, reason);
+                }
+            }
+            "java" if JAVA_MAIN.is_match(&read()) => {
+                let class = file.trim_end_matches(".java");
+                discovery.add(source, path, Kind::Binary, 8, format!("Java main class `{}`", class));
+            }
+            "kt" if KOTLIN_MAIN.is_match(&read()) => {
+                discovery.add(source, path, Kind::Binary, 8, "Kotlin top-level `fun main`".to_string());
+            }
+            "cs" => {
+                let content = read();
+                if CSHARP_MAIN.is_match(&content) {
+                    discovery.add(source, path, Kind::Binary, 8, "C# `static Main` method".to_string());
+                } else if file == "Program.cs" && CSHARP_TOP_LEVEL.is_match(&content) {
+                    discovery.add(source, path, Kind::Binary, 8, "C# top-level statements in Program.cs".to_string());
+                }
+            }
+            "c" | "cc" | "cpp" | "cxx" if C_MAIN.is_match(&read()) => {
+                discovery.add(source, path, Kind::Binary, 8, "C/C++ `main` function".to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+// ----- Frameworks -----
+
+static DJANGO_SETTINGS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"DJANGO_SETTINGS_MODULE").unwrap());
+static FLASK_APP: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(Flask|FastAPI|Starlette|Sanic|Quart)\s*\(").unwrap());
+static EXPRESS_LISTEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:express|fastify|Koa|Hapi\.server|new\s+Koa)\s*\(|\bcreateServer\s*\(").unwrap());
+static SPRING_APP: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"@SpringBootApplication").unwrap());
+
+fn framework_conventions(discovery: &mut Discovery, source: &dyn FileSource, paths: &[PathBuf], manifests: &[Manifest]) {
+    let declares = |package: &str| manifests.iter().any(|m| m.dependencies.iter().any(|d| d.name == package));
+
+    for path in paths {
+        let display = normalize(path);
+        if is_test_path(&display) {
+            continue;
+        }
+        let file = path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+        let stem = file.split('.').next().unwrap_or_default();
+        let extension = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let parent = path.parent().map(normalize).unwrap_or_default();
+        let script = matches!(extension.as_str(), "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs");
+        let read = || source.read_to_string(path).unwrap_or_default();
+
+        match (file.as_str(), extension.as_str()) {
+            ("manage.py", _) if DJANGO_SETTINGS.is_match(&read()) => {
+                discovery.add(source, path, Kind::Framework, 10, "Django management command entry (`manage.py runserver`)".to_string());
+            }
+            ("wsgi.py" | "asgi.py", _) => {
+                discovery.add(source, path, Kind::Framework, 7, format!("{} application served by the app server", stem.to_uppercase()));
+            }
+            ("urls.py", _) if source.exists(&path.with_file_name("settings.py")) => {
+                discovery.add(source, path, Kind::Framework, 7, "Django root URL configuration".to_string());
+            }
+            (_, "py") if matches!(stem, "app" | "main" | "server" | "application" | "api") => {
+                if let Some(caps) = FLASK_APP.captures(&read()) {
+                    discovery.add(source, path, Kind::Framework, 8, format!("Creates the {} application", &caps[1]));
+                }
+            }
+            ("artisan", _) => discovery.add(source, path, Kind::Framework, 9, "Laravel artisan console".to_string()),
+            ("index.php", _) if parent == "public" => {
+                discovery.add(source, path, Kind::Framework, 9, "Front controller serving every HTTP request".to_string());
+            }
+            ("config.ru", _) => discovery.add(source, path, Kind::Framework, 9, "Rack application booted by the app server".to_string()),
+            ("routes.rb", _) if parent == "config" => {
+                discovery.add(source, path, Kind::Framework, 7, "Rails routes".to_string());
+            }
+            (_, "java" | "kt") if SPRING_APP.is_match(&read()) => {
+                discovery.add(source, path, Kind::Framework, 10, "Spring Boot application class".to_string());
+            }
+            _ if script && declares("next") && next_entry(&parent, stem).is_some() => {
+                let rank = if matches!(stem, "_app" | "layout") { 9 } else { 7 };
+                discovery.add(source, path, Kind::Framework, rank, format!("Next.js {}", next_entry(&parent, stem).unwrap_or_default()));
+            }
+            _ if script && matches!(stem, "main" | "index") && parent == "src" && ["vue", "react-dom", "svelte", "@angular/core", "solid-js"].iter().any(|p| declares(p)) => {
+                discovery.add(source, path, Kind::Framework, 9, "Mounts the front-end application".to_string());
+            }
+            _ if script && matches!(stem, "server" | "app" | "index" | "main") && EXPRESS_LISTEN.is_match(&read()) => {
+                discovery.add(source, path, Kind::Framework, 8, "Creates the HTTP server".to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+// `pages/_app`, `pages/index`, `app/layout` and `app/page`, wherever the Next.js project sits
+fn next_entry(parent: &str, stem: &str) -> Option<&'static str> {
+    match (parent.rsplit('/').next().unwrap_or(parent), stem) {
+        ("pages", "_app") => Some("custom App wrapping every page"),
+        ("pages", "_document") => Some("HTML document shell"),
+        ("pages", "index") => Some("home page (pages router)"),
+        ("app", "layout") => Some("root layout (app router)"),
+        ("app", "page") => Some("home page (app router)"),
+        _ => None,
+    }
+}
@@ -0,0 +1,532 @@
+// graph.rs - Internal module dependency graph
+// Resolves Rust, Python, JS/TS and Go imports to files in the repository
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+use crate::parser::{Import, ImportKind, ParsedFile};
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub from: String,
+    pub to: String,
+    /// Line of the import in `from`
+    pub line: usize,
+    /// The import as written
+    pub import: String,
+    #[serde(default)]
+    pub kind: ImportKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DependencyGraph {
+    pub files: Vec<String>,
+    pub edges: Vec<Dependency>,
+    /// Package (crate, npm/Python/Go package) each file belongs to, keyed by file
+    #[serde(default)]
+    pub packages: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeMetrics {
+    pub fan_in: usize,
+    pub fan_out: usize,
+    pub centrality: f64,
+}
+
+impl DependencyGraph {
+    /// Builds the file-level graph from the imports of every parsed file.
+    /// Imports that don't resolve to a parsed file (third-party packages, std) are dropped.
+    pub fn build(source: &dyn FileSource, parsed_files: &[ParsedFile]) -> Self {
+        let resolver = Resolver::new(source, parsed_files);
+        let mut edges: Vec<Dependency> = Vec::new();
+        let mut seen: HashMap<(PathBuf, PathBuf), usize> = HashMap::new();
+
+        for file in parsed_files {
+            for import in &file.imports {
+                for target in resolver.resolve(file, import) {
+                    if target == file.path {
+                        continue;
+                    }
+                    let import_text = match import.kind {
+                        ImportKind::ModDeclaration => format!("mod {}", import.module.trim_start_matches("self::")),
+                        ImportKind::Use => import.module.clone(),
+                    };
+                    let edge = Dependency {
+                        from: path_string(&file.path),
+                        to: path_string(&target),
+                        line: import.line,
+                        import: import_text,
+                        kind: import.kind,
+                    };
+                    // One edge per pair of files; a `use` of a module outweighs its `mod` declaration
+                    match seen.get(&(file.path.clone(), target.clone())) {
+                        Some(&i) if edges[i].kind == ImportKind::ModDeclaration && edge.kind == ImportKind::Use => edges[i] = edge,
+                        Some(_) => {}
+                        None => {
+                            seen.insert((file.path.clone(), target), edges.len());
+                            edges.push(edge);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut files: Vec<String> = parsed_files.iter().map(|f| path_string(&f.path)).collect();
+        files.sort();
+        let packages = package_roots(source, &files);
+
+        DependencyGraph { files, edges, packages }
+    }
+
+    /// Fan-in, fan-out and PageRank centrality for every file
+    pub fn metrics(&self) -> BTreeMap<String, NodeMetrics> {
+        let ranks = self.page_rank();
+        let mut metrics: BTreeMap<String, NodeMetrics> = self
+            .files
+            .iter()
+            .map(|f| {
+                let metrics = NodeMetrics {
+                    fan_in: 0,
+                    fan_out: 0,
+                    centrality: ranks.get(f.as_str()).copied().unwrap_or_default(),
+                };
+                (f.clone(), metrics)
+            })
+            .collect();
+
+        for edge in &self.edges {
+            if let Some(m) = metrics.get_mut(&edge.to) {
+                m.fan_in += 1;
+            }
+            if let Some(m) = metrics.get_mut(&edge.from) {
+                m.fan_out += 1;
+            }
+        }
+        metrics
+    }
+
+    // Sparse power iteration; importing a file passes rank to it, so widely used files score high
+    fn page_rank(&self) -> HashMap<&str, f64> {
+        const DAMPING: f64 = 0.85;
+        const ITERATIONS: usize = 50;
+
+        let n = self.files.len();
+        if n == 0 {
+            return HashMap::new();
+        }
+        let index: HashMap<&str, usize> = self.files.iter().enumerate().map(|(i, f)| (f.as_str(), i)).collect();
+        let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for edge in &self.edges {
+            if let (Some(&from), Some(&to)) = (index.get(edge.from.as_str()), index.get(edge.to.as_str())) {
+                outgoing[from].push(to);
+            }
+        }
+
+        let mut rank = vec![1.0 / n as f64; n];
+        for _ in 0..ITERATIONS {
+            let dangling: f64 = (0..n).filter(|&i| outgoing[i].is_empty()).map(|i| rank[i]).sum();
+            let base = (1.0 - DAMPING) / n as f64 + DAMPING * dangling / n as f64;
+            let mut next = vec![base; n];
+            for (from, targets) in outgoing.iter().enumerate() {
+                for &to in targets {
+                    next[to] += DAMPING * rank[from] / targets.len() as f64;
+                }
+            }
+            rank = next;
+        }
+
+        self.files.iter().map(String::as_str).zip(rank).collect()
+    }
+
+    /// Collapses file edges into edges between modules (directories), with import counts
+    pub fn module_edges(&self) -> BTreeMap<(String, String), usize> {
+        let mut edges = BTreeMap::new();
+        for edge in &self.edges {
+            let (from, to) = (module_of(&edge.from), module_of(&edge.to));
+            if from != to {
+                *edges.entry((from, to)).or_insert(0) += 1;
+            }
+        }
+        edges
+    }
+
+    /// Package of a file, or `.` when it isn't under any manifest
+    pub fn package_of(&self, file: &str) -> &str {
+        self.packages.get(file).map_or(".", String::as_str)
+    }
+}
+
+// Manifests that mark the root of a package
+const PACKAGE_MANIFESTS: &[&str] = &[
+    "Cargo.toml", "package.json", "go.mod", "pyproject.toml", "setup.py", "pom.xml",
+    "build.gradle", "build.gradle.kts", "composer.json", "Gemfile",
+];
+
+// Maps every file to the nearest enclosing directory that holds a package manifest
+fn package_roots(source: &dyn FileSource, files: &[String]) -> BTreeMap<String, String> {
+    let roots: BTreeSet<PathBuf> = source
+        .files()
+        .into_iter()
+        .filter(|p| {
+            let name = p.file_name().unwrap_or_default().to_string_lossy();
+            PACKAGE_MANIFESTS.contains(&name.as_ref()) || name.ends_with(".csproj")
+        })
+        .map(|p| p.parent().map(Path::to_path_buf).unwrap_or_default())
+        .collect();
+
+    files
+        .iter()
+        .map(|file| {
+            let package = Path::new(file)
+                .ancestors()
+                .skip(1)
+                .find(|dir| roots.contains(*dir))
+                .map(|dir| if dir.as_os_str().is_empty() { ".".to_string() } else { path_string(dir) })
+                .unwrap_or_else(|| ".".to_string());
+            (file.clone(), package)
+        })
+        .collect()
+}
+
+/// The module a file belongs to: its directory, or `.` at the root
+pub fn module_of(path: &str) -> String {
+    match Path::new(path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => path_string(parent),
+        _ => ".".to_string(),
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+// ----- Import Resolution -----
+
+struct RustCrate {
+    name: String,
+    src: PathBuf,
+}
+
+struct GoModule {
+    path: String,
+    dir: PathBuf,
+}
+
+struct Resolver {
+    files: BTreeSet<PathBuf>,
+    rust_crates: Vec<RustCrate>,
+    go_modules: Vec<GoModule>,
+}
+
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts", "vue", "svelte"];
+
+impl Resolver {
+    fn new(source: &dyn FileSource, parsed_files: &[ParsedFile]) -> Self {
+        let mut rust_crates = Vec::new();
+        let mut go_modules = Vec::new();
+
+        for path in source.files() {
+            let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            match path.file_name().and_then(|n| n.to_str()) {
+                Some("Cargo.toml") => {
+                    let name = source
+                        .read_to_string(&path)
+                        .and_then(|text| text.parse::<toml::Table>().ok())
+                        .and_then(|manifest| manifest.get("package")?.get("name")?.as_str().map(str::to_string));
+                    if let Some(name) = name {
+                        rust_crates.push(RustCrate {
+                            name: name.replace('-', "_"),
+                            src: dir.join("src"),
+                        });
+                    }
+                }
+                Some("go.mod") => {
+                    let module = source.read_to_string(&path).and_then(|text| {
+                        text.lines()
+                            .find_map(|line| line.trim().strip_prefix("module "))
+                            .map(|m| m.trim().trim_matches('"').to_string())
+                    });
+                    if let Some(module) = module {
+                        go_modules.push(GoModule { path: module, dir });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Resolver {
+            files: parsed_files.iter().map(|f| f.path.clone()).collect(),
+            rust_crates,
+            go_modules,
+        }
+    }
+
+    fn resolve(&self, file: &ParsedFile, import: &Import) -> Vec<PathBuf> {
+        match file.language.as_str() {
+            "Rust" => self.resolve_rust(&file.path, import).into_iter().collect(),
+            "Python" => self.resolve_python(&file.path, import),
+            "JavaScript" | "TypeScript" => self.resolve_js(&file.path, &import.module).into_iter().collect(),
+            "Go" => self.resolve_go(&import.module),
+            _ => Vec::new(),
+        }
+    }
+
+    fn existing(&self, candidates: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+        candidates.into_iter().map(|p| normalize(&p)).find(|p| self.files.contains(p))
+    }
+
+    // ----- Rust -----
+
+    fn resolve_rust(&self, file: &Path, import: &Import) -> Option<PathBuf> {
+        let krate = self.rust_crates.iter().filter(|c| file.starts_with(&c.src)).max_by_key(|c| c.src.components().count())?;
+        let segments: Vec<&str> = import.module.split("::").filter(|s| !s.is_empty()).collect();
+        // `use super::*` in an inline `mod tests` names this file's module, not its parent
+        let mut current = rust_module_path(file, &krate.src)?;
+        current.extend(import.inline_modules.iter().cloned());
+
+        let (target_crate, base, rest): (&RustCrate, Vec<String>, &[&str]) = match segments.first()? {
+            &"crate" => (krate, Vec::new(), &segments[1..]),
+            &"self" => (krate, current.clone(), &segments[1..]),
+            &"super" => {
+                let supers = segments.iter().take_while(|s| **s == "super").count();
+                let base = current[..current.len().saturating_sub(supers)].to_vec();
+                (krate, base, &segments[supers..])
+            }
+            name => match self.rust_crates.iter().find(|c| c.name == *name) {
+                Some(other) if other.name != krate.name => (other, Vec::new(), &segments[1..]),
+                // 2018 paths: a bare name is resolved relative to the current module
+                _ => (krate, current.clone(), &segments[..]),
+            },
+        };
+
+        // Longest prefix that names a module file; the remainder is an item inside it
+        (0..=rest.len()).rev().find_map(|len| {
+            let mut module = base.clone();
+            module.extend(rest[..len].iter().map(|s| s.to_string()));
+            self.rust_module_file(&target_crate.src, &module)
+        })
+    }
+
+    fn rust_module_file(&self, src: &Path, module: &[String]) -> Option<PathBuf> {
+        if module.is_empty() {
+            return self.existing([src.join("lib.rs"), src.join("main.rs")]);
+        }
+        let dir = module.iter().fold(src.to_path_buf(), |dir, part| dir.join(part));
+        self.existing([dir.with_extension("rs"), dir.join("mod.rs")])
+    }
+
+    // ----- Python -----
+
+    fn resolve_python(&self, file: &Path, import: &Import) -> Vec<PathBuf> {
+        let dots = import.module.chars().take_while(|c| *c == '.').count();
+        let module = &import.module[dots..];
+        let parts: Vec<&str> = module.split('.').filter(|p| !p.is_empty()).collect();
+        let file_dir = file.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        let roots: Vec<PathBuf> = if dots > 0 {
+            let mut base = file_dir;
+            for _ in 1..dots {
+                base.pop();
+            }
+            vec![base]
+        } else {
+            vec![file_dir, PathBuf::new(), PathBuf::from("src"), PathBuf::from("lib")]
+        };
+
+        let module_file = |root: &Path, parts: &[&str]| -> Option<PathBuf> {
+            let dir = parts.iter().fold(root.to_path_buf(), |dir, part| dir.join(part));
+            if parts.is_empty() {
+                return self.existing([dir.join("__init__.py")]);
+            }
+            self.existing([dir.with_extension("py"), dir.join("__init__.py")])
+        };
+
+        for root in &roots {
+            // `from pkg import mod` imports submodules when they exist
+            let submodules: Vec<PathBuf> = import
+                .names
+                .iter()
+                .filter_map(|name| {
+                    let mut with_name = parts.clone();
+                    with_name.push(name);
+                    module_file(root, &with_name)
+                })
+                .collect();
+            if !submodules.is_empty() {
+                return submodules;
+            }
+            if let Some(found) = module_file(root, &parts) {
+                return vec![found];
+            }
+        }
+        Vec::new()
+    }
+
+    // ----- JavaScript / TypeScript -----
+
+    fn resolve_js(&self, file: &Path, specifier: &str) -> Option<PathBuf> {
+        let base = if specifier.starts_with('.') {
+            file.parent().unwrap_or(Path::new("")).join(specifier)
+        } else if let Some(rest) = specifier.strip_prefix("@/").or_else(|| specifier.strip_prefix("~/")) {
+            // Common bundler alias for src/
+            PathBuf::from("src").join(rest)
+        } else if specifier.starts_with("src/") {
+            PathBuf::from(specifier)
+        } else {
+            return None;
+        };
+
+        let mut candidates = vec![base.clone()];
+        // TypeScript ESM imports name the emitted .js file
+        let stem = ["js", "jsx", "mjs", "cjs"]
+            .iter()
+            .find(|ext| base.extension().is_some_and(|e| e == **ext))
+            .map(|_| base.with_extension(""))
+            .unwrap_or_else(|| base.clone());
+        for ext in JS_EXTENSIONS {
+            candidates.push(PathBuf::from(format!("{}.{}", stem.display(), ext)));
+        }
+        for ext in JS_EXTENSIONS {
+            candidates.push(base.join(format!("index.{}", ext)));
+        }
+        self.existing(candidates)
+    }
+
+    // ----- Go -----
+
+    fn resolve_go(&self, import: &str) -> Vec<PathBuf> {
+        let Some((module, rest)) = self.go_modules.iter().find_map(|m| {
+            let rest = import.strip_prefix(&m.path)?;
+            (rest.is_empty() || rest.starts_with('/')).then_some((m, rest.trim_start_matches('/')))
+        }) else {
+            return Vec::new();
+        };
+
+        let package_dir = normalize(&module.dir.join(rest));
+        self.files
+            .iter()
+            .filter(|f| f.parent() == Some(package_dir.as_path()))
+            .filter(|f| {
+                let name = f.to_string_lossy();
+                name.ends_with(".go") && !name.ends_with("_test.go")
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+// src/a/b.rs -> [a, b]; src/a/mod.rs -> [a]; src/lib.rs -> []
+fn rust_module_path(file: &Path, src: &Path) -> Option<Vec<String>> {
+    let relative = file.strip_prefix(src).ok()?;
+    let mut parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let last = parts.pop()?;
+    match last.as_str() {
+        "mod.rs" => {}
+        "lib.rs" | "main.rs" if parts.is_empty() => {}
+        _ => parts.push(last.trim_end_matches(".rs").to_string()),
+    }
+    Some(parts)
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    struct Files(BTreeMap<PathBuf, String>);
+
+    impl FileSource for Files {
+        fn name(&self) -> String {
+            "demo".to_string()
+        }
+
+        fn files(&self) -> Vec<PathBuf> {
+            self.0.keys().cloned().collect()
+        }
+
+        fn read(&self, path: &Path) -> Option<Vec<u8>> {
+            self.0.get(path).map(|content| content.clone().into_bytes())
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            self.0.keys().any(|p| p.starts_with(path))
+        }
+    }
+
+    fn graph(files: &[(&str, &str)]) -> Vec<(String, String)> {
+        build(files).edges.into_iter().map(|e| (e.from, e.to)).collect()
+    }
+
+    fn build(files: &[(&str, &str)]) -> DependencyGraph {
+        let mut all: BTreeMap<PathBuf, String> = files.iter().map(|(p, c)| (PathBuf::from(p), c.to_string())).collect();
+        all.insert(PathBuf::from("Cargo.toml"), "[package]\nname = \"demo\"\n".to_string());
+        let parsed: Vec<ParsedFile> = files
+            .iter()
+            .filter_map(|(path, content)| parser::parse(Path::new(path), content, "Rust"))
+            .collect();
+        DependencyGraph::build(&Files(all), &parsed)
+    }
+
+    #[test]
+    fn super_inside_an_inline_module_is_the_enclosing_file() {
+        let edges = graph(&[
+            ("src/main.rs", "mod licenses;\nuse crate::licenses::check;\nfn main() { check(); }\n"),
+            ("src/licenses.rs", "pub fn check() {}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n}\n"),
+        ]);
+        assert!(!edges.iter().any(|(from, _)| from == "src/licenses.rs"), "{:?}", edges);
+    }
+
+    #[test]
+    fn super_inside_nested_inline_modules_climbs_one_level_each() {
+        let edges = graph(&[
+            ("src/main.rs", "mod util;\nfn main() {}\n"),
+            ("src/util.rs", "pub fn helper() {}\n"),
+            ("src/app.rs", "mod outer {\n    mod inner {\n        use super::super::super::util::helper;\n    }\n}\n"),
+        ]);
+        assert!(edges.contains(&("src/app.rs".to_string(), "src/util.rs".to_string())), "{:?}", edges);
+    }
+
+    #[test]
+    fn mod_declarations_link_modules_only_reached_by_path() {
+        let graph = build(&[
+            ("src/main.rs", "mod sbom;\nmod util;\nuse util::helper;\nfn main() { sbom::export(); helper(); }\n"),
+            ("src/sbom.rs", "pub fn export() {}\n"),
+            ("src/util.rs", "pub fn helper() {}\n"),
+        ]);
+        let kind = |to: &str| graph.edges.iter().find(|e| e.from == "src/main.rs" && e.to == to).map(|e| e.kind);
+        assert_eq!(kind("src/sbom.rs"), Some(ImportKind::ModDeclaration));
+        // A `use` of a declared module replaces the declaration's edge rather than adding a second one
+        assert_eq!(kind("src/util.rs"), Some(ImportKind::Use));
+        assert_eq!(graph.edges.len(), 2);
+    }
+
+    #[test]
+    fn mod_declarations_do_not_close_cycles() {
+        let graph = build(&[
+            ("src/main.rs", "mod config;\npub struct App;\nfn main() {}\n"),
+            ("src/config.rs", "use crate::App;\n"),
+        ]);
+        assert_eq!(graph.edges.len(), 2);
+        assert!(crate::cycles::find_cycles(&graph).is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+// languages.rs - Language registry
+// Maps extensions, well-known filenames, shebangs and editor modelines to canonical languages
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::OnceLock;
+
+// ----- Registry -----
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LanguageKind {
+    Programming,
+    Markup,
+    Data,
+    Config,
+}
+
+#[derive(Debug)]
+pub struct Language {
+    pub name: &'static str,
+    pub kind: LanguageKind,
+    pub extensions: &'static [&'static str],
+    pub filenames: &'static [&'static str],
+    pub interpreters: &'static [&'static str],
+    pub aliases: &'static [&'static str],
+}
+
+use LanguageKind::*;
+
+macro_rules! lang {
+    ($name:expr, $kind:expr, ext: [$($ext:expr),*], files: [$($file:expr),*], interp: [$($interp:expr),*], alias: [$($alias:expr),*]) => {
+        Language {
+            name: $name,
+            kind: $kind,
+            extensions: &[$($ext),*],
+            filenames: &[$($file),*],
+            interpreters: &[$($interp),*],
+            aliases: &[$($alias),*],
+        }
+    };
+}
+
+pub static LANGUAGES: &[Language] = &[
+    // Programming languages
+    lang!("Rust", Programming, ext: ["rs"], files: [], interp: [], alias: ["rs"]),
+    lang!("Python", Programming, ext: ["py", "pyw", "pyi"], files: ["SConstruct", "SConscript"], interp: ["python"], alias: ["py", "python3"]),
+    lang!("JavaScript", Programming, ext: ["js", "mjs", "cjs", "jsx"], files: ["Jakefile"], interp: ["node", "nodejs"], alias: ["js", "node"]),
+    lang!("TypeScript", Programming, ext: ["ts", "mts", "cts", "tsx"], files: [], interp: ["deno", "ts-node", "tsx"], alias: ["ts", "typescriptreact"]),
+    lang!("Go", Programming, ext: ["go"], files: [], interp: [], alias: ["golang"]),
+    lang!("Java", Programming, ext: ["java"], files: [], interp: [], alias: []),
+    lang!("Kotlin", Programming, ext: ["kt", "kts"], files: [], interp: [], alias: []),
+    lang!("Scala", Programming, ext: ["scala", "sc", "sbt"], files: [], interp: ["scala"], alias: []),
+    lang!("Groovy", Programming, ext: ["groovy", "gradle"], files: ["Jenkinsfile"], interp: ["groovy"], alias: []),
+    lang!("C", Programming, ext: ["c", "h"], files: [], interp: [], alias: []),
+    lang!("C++", Programming, ext: ["cpp", "cc", "cxx", "c++", "hpp", "hh", "hxx", "ipp"], files: [], interp: [], alias: ["cpp"]),
+    lang!("C#", Programming, ext: ["cs", "csx"], files: [], interp: [], alias: ["cs", "csharp"]),
+    lang!("F#", Programming, ext: ["fs", "fsi", "fsx"], files: [], interp: [], alias: ["fsharp"]),
+    lang!("Objective-C", Programming, ext: ["m", "mm"], files: [], interp: [], alias: ["objc"]),
+    lang!("Swift", Programming, ext: ["swift"], files: [], interp: [], alias: []),
+    lang!("PHP", Programming, ext: ["php", "phtml"], files: [], interp: ["php"], alias: []),
+    lang!("Ruby", Programming, ext: ["rb", "rake", "gemspec"], files: ["Gemfile", "Rakefile", "Guardfile", "Vagrantfile"], interp: ["ruby", "jruby"], alias: ["rb"]),
+    lang!("Perl", Programming, ext: ["pl", "pm"], files: [], interp: ["perl"], alias: []),
+    lang!("Lua", Programming, ext: ["lua"], files: [], interp: ["lua", "luajit"], alias: []),
+    lang!("R", Programming, ext: ["r"], files: [], interp: ["rscript"], alias: []),
+    lang!("Julia", Programming, ext: ["jl"], files: [], interp: ["julia"], alias: []),
+    lang!("Dart", Programming, ext: ["dart"], files: [], interp: ["dart"], alias: []),
+    lang!("Elixir", Programming, ext: ["ex", "exs"], files: [], interp: ["elixir"], alias: []),
+    lang!("Erlang", Programming, ext: ["erl", "hrl"], files: ["rebar.config"], interp: ["escript"], alias: []),
+    lang!("Haskell", Programming, ext: ["hs", "lhs"], files: [], interp: ["runhaskell"], alias: []),
+    lang!("OCaml", Programming, ext: ["ml", "mli"], files: [], interp: ["ocaml"], alias: []),
+    lang!("Clojure", Programming, ext: ["clj", "cljs", "cljc", "edn"], files: [], interp: [], alias: []),
+    lang!("Zig", Programming, ext: ["zig"], files: [], interp: [], alias: []),
+    lang!("Nix", Programming, ext: ["nix"], files: [], interp: [], alias: []),
+    lang!("Shell", Programming, ext: ["sh", "bash", "zsh", "ksh", "fish"], files: [".bashrc", ".bash_profile", ".zshrc", ".profile"], interp: ["sh", "bash", "zsh", "ksh", "dash", "ash", "fish"], alias: ["bash", "zsh", "sh"]),
+    lang!("PowerShell", Programming, ext: ["ps1", "psm1", "psd1"], files: [], interp: ["pwsh", "powershell"], alias: ["ps1"]),
+    lang!("Batchfile", Programming, ext: ["bat", "cmd"], files: [], interp: [], alias: ["bat"]),
+    lang!("SQL", Programming, ext: ["sql"], files: [], interp: [], alias: []),
+    lang!("Assembly", Programming, ext: ["asm", "s"], files: [], interp: [], alias: ["nasm"]),
+    lang!("Makefile", Programming, ext: ["mk", "mak"], files: ["Makefile", "makefile", "GNUmakefile"], interp: ["make"], alias: ["make"]),
+    lang!("CMake", Programming, ext: ["cmake"], files: ["CMakeLists.txt"], interp: [], alias: []),
+    // Markup and styling
+    lang!("Vue", Markup, ext: ["vue"], files: [], interp: [], alias: []),
+    lang!("Svelte", Markup, ext: ["svelte"], files: [], interp: [], alias: []),
+    lang!("HTML", Markup, ext: ["html", "htm", "xhtml"], files: [], interp: [], alias: ["xhtml"]),
+    lang!("CSS", Markup, ext: ["css"], files: [], interp: [], alias: []),
+    lang!("SCSS", Markup, ext: ["scss", "sass"], files: [], interp: [], alias: ["sass"]),
+    lang!("Less", Markup, ext: ["less"], files: [], interp: [], alias: []),
+    lang!("Markdown", Markup, ext: ["md", "markdown", "mdx"], files: [], interp: [], alias: ["md"]),
+    lang!("reStructuredText", Markup, ext: ["rst"], files: [], interp: [], alias: ["rst"]),
+    lang!("AsciiDoc", Markup, ext: ["adoc", "asciidoc"], files: [], interp: [], alias: []),
+    lang!("TeX", Markup, ext: ["tex", "sty", "cls"], files: [], interp: [], alias: ["latex"]),
+    // Data formats
+    lang!("JSON", Data, ext: ["json", "jsonc", "json5", "geojson"], files: [".babelrc", ".eslintrc"], interp: [], alias: []),
+    lang!("YAML", Data, ext: ["yml", "yaml"], files: [], interp: [], alias: ["yml"]),
+    lang!("XML", Data, ext: ["xml", "xsd", "xsl", "svg", "plist"], files: [], interp: [], alias: []),
+    lang!("CSV", Data, ext: ["csv", "tsv"], files: [], interp: [], alias: []),
+    lang!("Protocol Buffers", Data, ext: ["proto"], files: [], interp: [], alias: ["proto"]),
+    lang!("GraphQL", Data, ext: ["graphql", "gql"], files: [], interp: [], alias: []),
+    // Configuration
+    lang!("TOML", Config, ext: ["toml"], files: ["Cargo.lock", "Pipfile"], interp: [], alias: []),
+    lang!("INI", Config, ext: ["ini", "cfg", "conf", "properties"], files: [".editorconfig", ".gitconfig"], interp: [], alias: ["dosini"]),
+    lang!("Dockerfile", Config, ext: ["dockerfile"], files: ["Dockerfile", "Containerfile"], interp: [], alias: ["docker"]),
+    lang!("HCL", Config, ext: ["hcl", "tf", "tfvars"], files: [], interp: [], alias: ["terraform"]),
+    lang!("Dotenv", Config, ext: ["env"], files: [".env", ".env.example", ".env.local"], interp: [], alias: []),
+    lang!("Ignore List", Config, ext: [], files: [".gitignore", ".dockerignore", ".npmignore", ".eslintignore", ".prettierignore"], interp: [], alias: ["gitignore"]),
+    lang!("Git Attributes", Config, ext: [], files: [".gitattributes"], interp: [], alias: ["gitattributes"]),
+];
+
+// ----- Detection -----
+
+/// Detects a file's language, checking modelines, then exact filenames, then the
+/// shebang line, then the extension (the same precedence GitHub Linguist uses).
+pub fn detect(path: &Path, content: &str) -> Option<&'static Language> {
+    from_modeline(content)
+        .or_else(|| from_filename(path))
+        .or_else(|| from_shebang(content))
+        .or_else(|| from_extension(path))
+}
+
+pub fn by_name(name: &str) -> Option<&'static Language> {
+    let name = name.to_lowercase();
+    LANGUAGES.iter().find(|lang| {
+        lang.name.to_lowercase() == name || lang.aliases.iter().any(|alias| *alias == name)
+    })
+}
+
+fn from_filename(path: &Path) -> Option<&'static Language> {
+    let file_name = path.file_name()?.to_str()?;
+    LANGUAGES.iter().find(|lang| lang.filenames.contains(&file_name)).or_else(|| {
+        // Variants like `Dockerfile.dev` or `Makefile.am`
+        let stem = file_name.split('.').next()?;
+        if !["Dockerfile", "Containerfile", "Makefile", "Jenkinsfile"].contains(&stem) {
+            return None;
+        }
+        LANGUAGES.iter().find(|lang| lang.filenames.contains(&stem))
+    })
+}
+
+fn from_extension(path: &Path) -> Option<&'static Language> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    LANGUAGES.iter().find(|lang| lang.extensions.contains(&ext.as_str()))
+}
+
+fn from_shebang(content: &str) -> Option<&'static Language> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        // Skip env flags such as `-S`
+        program = words.find(|w| !w.starts_with('-'))?;
+    }
+
+    // python3.11 -> python, ruby2 -> ruby
+    let interpreter = program
+        .trim_end_matches(|c: char| c.is_ascii_digit() || c == '.')
+        .to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|lang| lang.interpreters.contains(&interpreter.as_str()))
+}
+
+fn from_modeline(content: &str) -> Option<&'static Language> {
+    static VIM: OnceLock<Regex> = OnceLock::new();
+    static EMACS: OnceLock<Regex> = OnceLock::new();
+    let vim = VIM.get_or_init(|| {
+        Regex::new(r"(?:^|\s)(?:vim?|ex):.*?\b(?:ft|filetype|syntax)=([\w+#.-]+)").unwrap()
+    });
+    let emacs = EMACS.get_or_init(|| {
+        Regex::new(r"-\*-\s*(?:.*?\bmode:\s*([\w+#.-]+)|([\w+#.-]+))\s*(?:;.*?)?-\*-").unwrap()
+    });
+
+    // Modelines are only honoured in the first and last five lines
+    let lines: Vec<&str> = content.lines().collect();
+    let tail_start = lines.len().saturating_sub(5).max(5.min(lines.len()));
+    lines[..5.min(lines.len())]
+        .iter()
+        .chain(&lines[tail_start..])
+        .find_map(|line| {
+            let caps = vim.captures(line).or_else(|| emacs.captures(line))?;
+            let name = caps.get(1).or_else(|| caps.get(2))?.as_str();
+            by_name(name.trim_end_matches("-mode"))
+        })
+}
@@ -0,0 +1,193 @@
+// layers.rs - Architectural layer inference
+// Assigns files to layers from directory and file naming, then checks imports against the conventional top-down order
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::graph::DependencyGraph;
+
+// Conventional layers, outermost first, with the path segments that identify them
+const LAYERS: &[(&str, &[&str])] = &[
+    ("Presentation", &[
+        "ui", "components", "component", "views", "view", "pages", "page", "screens", "screen",
+        "templates", "widgets", "layouts", "frontend",
+    ]),
+    ("API", &[
+        "controllers", "controller", "handlers", "handler", "routes", "route", "router", "routers",
+        "api", "endpoints", "resolvers", "resolver", "commands", "cli",
+    ]),
+    ("Business Logic", &[
+        "services", "service", "domain", "usecases", "usecase", "interactors", "application",
+        "logic", "business", "core",
+    ]),
+    ("Data", &[
+        "repositories", "repository", "repo", "repos", "models", "model", "db", "database", "dal",
+        "dao", "persistence", "store", "stores", "storage", "entities", "entity", "migrations", "schema",
+    ]),
+];
+
+// Rounds of propagating layers to unnamed files through their importers
+const PROPAGATION_ROUNDS: usize = 5;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Layer {
+    pub name: String,
+    pub files: Vec<String>,
+    /// How many of `files` were placed by who imports them rather than by name
+    pub inferred: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LayerFlow {
+    pub from: String,
+    pub to: String,
+    pub imports: usize,
+    /// The imports point from a lower layer up to a higher one
+    pub violation: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Layering {
+    /// Layers in conventional order, outermost first
+    pub layers: Vec<Layer>,
+    pub flows: Vec<LayerFlow>,
+    /// Share of cross-layer imports that point down the conventional order
+    pub confidence: f32,
+}
+
+impl Layering {
+    pub fn infer(graph: &DependencyGraph) -> Self {
+        let mut assigned: HashMap<&str, (usize, bool)> = graph
+            .files
+            .iter()
+            .filter_map(|f| layer_by_name(f).map(|layer| (f.as_str(), (layer, false))))
+            .collect();
+        propagate(graph, &mut assigned);
+
+        let mut counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for edge in &graph.edges {
+            if let (Some(&(from, _)), Some(&(to, _))) = (assigned.get(edge.from.as_str()), assigned.get(edge.to.as_str())) {
+                if from != to {
+                    *counts.entry((from, to)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        // Layer indices follow LAYERS, so an import to a smaller index points up the stack
+        let mut present: Vec<usize> = assigned.values().map(|&(layer, _)| layer).collect();
+        present.sort_unstable();
+        present.dedup();
+
+        let layers = present
+            .iter()
+            .map(|&layer| {
+                let mut files: Vec<String> = assigned
+                    .iter()
+                    .filter(|(_, &(l, _))| l == layer)
+                    .map(|(f, _)| f.to_string())
+                    .collect();
+                files.sort();
+                Layer {
+                    name: LAYERS[layer].0.to_string(),
+                    inferred: assigned.values().filter(|&&(l, inferred)| l == layer && inferred).count(),
+                    files,
+                }
+            })
+            .collect();
+
+        let flows: Vec<LayerFlow> = counts
+            .iter()
+            .map(|(&(from, to), &imports)| LayerFlow {
+                from: LAYERS[from].0.to_string(),
+                to: LAYERS[to].0.to_string(),
+                imports,
+                violation: to < from,
+            })
+            .collect();
+
+        let total: usize = flows.iter().map(|f| f.imports).sum();
+        let respected: usize = flows.iter().filter(|f| !f.violation).map(|f| f.imports).sum();
+
+        Layering {
+            layers,
+            flows,
+            confidence: if total > 0 { respected as f32 / total as f32 } else { 0.0 },
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.layers.iter().map(|l| l.name.clone()).collect()
+    }
+
+    /// The downward imports between layers, linked into chains where one ends where the next starts,
+    /// e.g. `Presentation → API → Data, Presentation → Data`
+    pub fn data_flow(&self) -> String {
+        let mut chains: Vec<Vec<&str>> = Vec::new();
+        // Flows are in layer order, so each chain grows downwards
+        for flow in self.flows.iter().filter(|f| !f.violation) {
+            match chains.iter_mut().find(|chain| chain.last() == Some(&flow.from.as_str())) {
+                Some(chain) => chain.push(&flow.to),
+                None => chains.push(vec![&flow.from, &flow.to]),
+            }
+        }
+
+        if chains.is_empty() {
+            "No imports between layers".to_string()
+        } else {
+            chains.iter().map(|chain| chain.join(" → ")).collect::<Vec<_>>().join(", ")
+        }
+    }
+
+    pub fn violations(&self) -> impl Iterator<Item = &LayerFlow> {
+        self.flows.iter().filter(|f| f.violation)
+    }
+}
+
+// The deepest path segment naming a layer wins, so `api/models/user.py` is Data
+fn layer_by_name(file: &str) -> Option<usize> {
+    let path = Path::new(file);
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_lowercase());
+    let segments = path
+        .parent()
+        .into_iter()
+        .flat_map(|p| p.components())
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .chain(stem);
+
+    segments
+        .filter_map(|segment| {
+            segment
+                .split(['_', '-', '.'])
+                .find_map(|token| LAYERS.iter().position(|(_, names)| names.contains(&token)))
+        })
+        .next_back()
+}
+
+// A file without a telling name joins the layer of its importers when they all agree
+fn propagate<'a>(graph: &'a DependencyGraph, assigned: &mut HashMap<&'a str, (usize, bool)>) {
+    let mut importers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &graph.edges {
+        importers.entry(edge.to.as_str()).or_default().push(edge.from.as_str());
+    }
+
+    for _ in 0..PROPAGATION_ROUNDS {
+        let mut changed = false;
+        for file in &graph.files {
+            if assigned.contains_key(file.as_str()) {
+                continue;
+            }
+            let Some(from) = importers.get(file.as_str()) else { continue };
+            let layers: Vec<Option<usize>> = from.iter().map(|f| assigned.get(f).map(|&(l, _)| l)).collect();
+            if let Some(Some(layer)) = layers.first() {
+                if layers.iter().all(|l| *l == Some(*layer)) {
+                    assigned.insert(file.as_str(), (*layer, true));
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+}
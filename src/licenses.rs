@@ -0,0 +1,556 @@
+// licenses.rs - License detection and compliance policy
+// Identifies the project's license and resolves dependency licenses from lockfiles, installed packages and offline registry caches
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lockfiles::DependencyReport;
+use crate::manifests::{normalize_python, Ecosystem, Manifest};
+use crate::source::FileSource;
+
+// Only the top of a file is searched for an SPDX-License-Identifier header
+const HEADER_LINES: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProjectLicense {
+    /// SPDX expression from the root manifest, or from the license files
+    pub expression: Option<String>,
+    /// License files and what each was identified as
+    pub files: Vec<(String, Option<String>)>,
+    /// SPDX-License-Identifier headers and how many files carry each
+    pub spdx_headers: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DependencyLicense {
+    pub name: String,
+    pub version: String,
+    pub ecosystem: Ecosystem,
+    pub lockfile: String,
+    pub license: Option<String>,
+    /// Where the license was read: the lockfile, an installed package or a registry cache
+    pub source: Option<String>,
+}
+
+/// The `[licenses]` table: dependencies must be satisfiable with allowed licenses and without denied ones
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LicensePolicy {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    #[serde(default)]
+    pub fail_on_unknown: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Allowed,
+    Denied,
+    NotAllowed,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LicenseCheck {
+    pub dependency: DependencyLicense,
+    pub status: Status,
+    pub violation: bool,
+}
+
+// ----- Project license -----
+
+/// License files at the root, SPDX headers in `paths`, and the root manifest's declared license
+pub fn detect_project(source: &dyn FileSource, paths: &[PathBuf], manifests: &[Manifest]) -> ProjectLicense {
+    let mut project = ProjectLicense::default();
+
+    for path in source.files().iter().filter(|p| p.components().count() == 1) {
+        let name = path.to_string_lossy().to_string();
+        let upper = name.to_uppercase();
+        if ["LICENSE", "LICENCE", "COPYING", "UNLICENSE"].iter().any(|prefix| upper.starts_with(prefix)) {
+            let identified = source.read_to_string(path).and_then(|text| identify_text(&text));
+            project.files.push((name, identified));
+        }
+    }
+    project.files.sort();
+
+    for path in paths {
+        let Some(content) = source.read_to_string(path) else { continue };
+        if let Some(expression) = spdx_header(&content) {
+            *project.spdx_headers.entry(expression).or_insert(0) += 1;
+        }
+    }
+
+    // Several license files (LICENSE-MIT, LICENSE-APACHE) mean the user may pick one
+    let mut from_files: Vec<&str> = Vec::new();
+    for id in project.files.iter().filter_map(|(_, id)| id.as_deref()) {
+        if !from_files.contains(&id) {
+            from_files.push(id);
+        }
+    }
+    let root = manifests.iter().filter(|m| m.license.is_some()).min_by_key(|m| m.path.matches('/').count());
+    project.expression = root
+        .and_then(|m| m.license.clone())
+        .or_else(|| (!from_files.is_empty()).then(|| from_files.join(" OR ")))
+        .or_else(|| project.spdx_headers.iter().max_by_key(|(_, count)| **count).map(|(id, _)| id.clone()));
+    project
+}
+
+fn spdx_header(content: &str) -> Option<String> {
+    content.lines().take(HEADER_LINES).find_map(|line| {
+        let (_, rest) = line.split_once("SPDX-License-Identifier:")?;
+        let expression = rest.trim().trim_end_matches("*/").trim_end_matches("-->").trim();
+        (!expression.is_empty()).then(|| expression.to_string())
+    })
+}
+
+/// Recognises common license texts by phrases that survive reformatting
+pub fn identify_text(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let has = |phrase: &str| text.contains(phrase);
+    // Every full GNU license text ends with a "How to Apply" appendix quoting "any later version", so only
+    // a notice ahead of the text decides; the bare text doesn't say which versions apply
+    let gnu = |title: &str, id: &str| {
+        let full_text = has("TERMS AND CONDITIONS");
+        let notice = if full_text { &text[..text.find(title).unwrap_or(0)] } else { text.as_str() };
+        if notice.contains("any later version") {
+            format!("{}-or-later", id)
+        } else if full_text {
+            id.to_string()
+        } else {
+            format!("{}-only", id)
+        }
+    };
+
+    let id = if has("GNU AFFERO GENERAL PUBLIC LICENSE") {
+        gnu("GNU AFFERO GENERAL PUBLIC LICENSE", "AGPL-3.0")
+    } else if has("GNU LESSER GENERAL PUBLIC LICENSE") {
+        gnu("GNU LESSER GENERAL PUBLIC LICENSE", if has("Version 2.1") { "LGPL-2.1" } else { "LGPL-3.0" })
+    } else if has("GNU GENERAL PUBLIC LICENSE") {
+        let id = if has("Version 2,") || has("Version 2 ") { "GPL-2.0" } else { "GPL-3.0" };
+        gnu("GNU GENERAL PUBLIC LICENSE", id)
+    } else if has("Apache License") && (has("Version 2.0") || has("version 2.0")) {
+        "Apache-2.0".to_string()
+    } else if has("Mozilla Public License") && has("2.0") {
+        "MPL-2.0".to_string()
+    } else if has("Permission is hereby granted, free of charge") {
+        "MIT".to_string()
+    } else if has("Redistribution and use in source and binary forms") {
+        if has("Neither the name") || has("endorse or promote") { "BSD-3-Clause" } else { "BSD-2-Clause" }.to_string()
+    } else if has("Permission to use, copy, modify, and/or distribute this software for any purpose") {
+        "ISC".to_string()
+    } else if has("This is free and unencumbered software released into the public domain") {
+        "Unlicense".to_string()
+    } else if has("Boost Software License") {
+        "BSL-1.0".to_string()
+    } else if has("CC0 1.0 Universal") {
+        "CC0-1.0".to_string()
+    } else if has("This software is provided 'as-is'") && has("misrepresented") {
+        "Zlib".to_string()
+    } else {
+        return None;
+    };
+    Some(id)
+}
+
+// ----- Dependency licenses -----
+
+/// Every locked package with its license. Licenses the lockfile doesn't record are looked up in installed
+/// packages (`node_modules`, virtualenvs) and the local Cargo and Go caches, and written back into `reports`.
+pub fn resolve(source: &dyn FileSource, reports: &mut [DependencyReport]) -> Vec<DependencyLicense> {
+    let dist_infos: Vec<PathBuf> = source
+        .files()
+        .into_iter()
+        .filter(|p| p.ends_with("METADATA") && p.parent().is_some_and(|d| d.to_string_lossy().ends_with(".dist-info")))
+        .collect();
+    let caches = Caches::locate();
+
+    let mut resolved = Vec::new();
+    for report in reports.iter_mut() {
+        let dir = Path::new(&report.lockfile).parent().unwrap_or(Path::new("")).to_path_buf();
+        for package in &mut report.packages {
+            let found = match &package.license {
+                Some(license) => Some((license.clone(), report.lockfile.clone())),
+                None => match report.ecosystem {
+                    Ecosystem::Cargo => caches.cargo(&package.name, &package.version),
+                    Ecosystem::Npm => node_module(source, &dir, &package.name),
+                    Ecosystem::PyPI => dist_info(source, &dist_infos, &package.name, &package.version),
+                    Ecosystem::Go => caches.go(&package.name, &package.version),
+                    _ => None,
+                },
+            };
+            if package.license.is_none() {
+                package.license = found.as_ref().map(|(license, _)| license.clone());
+            }
+            resolved.push(DependencyLicense {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                ecosystem: report.ecosystem,
+                lockfile: report.lockfile.clone(),
+                license: found.as_ref().map(|(license, _)| license.clone()),
+                source: found.map(|(_, source)| source),
+            });
+        }
+    }
+    resolved
+}
+
+// Registry caches outside the analyzed source, as populated by `cargo fetch` and `go mod download`
+struct Caches {
+    cargo_registries: Vec<PathBuf>,
+    go_modules: Option<PathBuf>,
+}
+
+impl Caches {
+    fn locate() -> Self {
+        let home = env::var_os("HOME").map(PathBuf::from);
+        let cargo_home = env::var_os("CARGO_HOME").map(PathBuf::from).or_else(|| home.as_ref().map(|h| h.join(".cargo")));
+        let cargo_registries = cargo_home
+            .and_then(|c| fs::read_dir(c.join("registry/src")).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .collect();
+        let go_modules = env::var_os("GOMODCACHE")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("GOPATH").map(|p| PathBuf::from(p).join("pkg/mod")))
+            .or_else(|| home.map(|h| h.join("go/pkg/mod")));
+        Caches { cargo_registries, go_modules }
+    }
+
+    fn cargo(&self, name: &str, version: &str) -> Option<(String, String)> {
+        self.cargo_registries.iter().find_map(|registry| {
+            let dir = registry.join(format!("{}-{}", name, version));
+            let manifest: toml::Value = toml::from_str(&fs::read_to_string(dir.join("Cargo.toml")).ok()?).ok()?;
+            let package = manifest.get("package")?;
+            let license = match package.get("license").and_then(|l| l.as_str()) {
+                Some(license) => license.to_string(),
+                None => identify_text(&fs::read_to_string(dir.join(package.get("license-file")?.as_str()?)).ok()?)?,
+            };
+            Some((license, dir.display().to_string()))
+        })
+    }
+
+    // Module paths are stored with upper-case letters escaped as `!` + lower-case
+    fn go(&self, module: &str, version: &str) -> Option<(String, String)> {
+        let escaped: String = module
+            .chars()
+            .map(|c| if c.is_ascii_uppercase() { format!("!{}", c.to_ascii_lowercase()) } else { c.to_string() })
+            .collect();
+        let dir = self.go_modules.as_ref()?.join(format!("{}@{}", escaped, version));
+        let license = ["LICENSE", "LICENSE.md", "LICENSE.txt", "COPYING"]
+            .iter()
+            .find_map(|file| identify_text(&fs::read_to_string(dir.join(file)).ok()?))?;
+        Some((license, dir.display().to_string()))
+    }
+}
+
+fn node_module(source: &dyn FileSource, dir: &Path, name: &str) -> Option<(String, String)> {
+    let path = dir.join("node_modules").join(name).join("package.json");
+    let json: serde_json::Value = serde_json::from_str(&source.read_to_string(&path)?).ok()?;
+    let license = json.get("license").and_then(|l| l.as_str().or_else(|| l.get("type")?.as_str()))?;
+    Some((license.to_string(), path.to_string_lossy().replace('\\', "/")))
+}
+
+// Wheel metadata: `License-Expression` (PEP 639), a short `License` field, or a trove classifier
+fn dist_info(source: &dyn FileSource, dist_infos: &[PathBuf], name: &str, version: &str) -> Option<(String, String)> {
+    // `<name>-<version>.dist-info`, where the name is escaped to `_`
+    let wanted = normalize_python(name);
+    let path = dist_infos.iter().find(|p| {
+        let dir = p.parent().and_then(|d| d.file_name()).map(|d| d.to_string_lossy().to_string()).unwrap_or_default();
+        dir.strip_suffix(".dist-info")
+            .and_then(|stem| stem.rsplit_once('-'))
+            .is_some_and(|(n, v)| normalize_python(n) == wanted && v == version)
+    })?;
+    let metadata = source.read_to_string(path)?;
+    let field = |key: &str| metadata.lines().find_map(|l| l.strip_prefix(key)).map(str::trim);
+
+    let license = field("License-Expression:")
+        .map(str::to_string)
+        .or_else(|| field("License:").filter(|l| !l.is_empty() && l.len() < 40 && !l.contains('\n')).map(str::to_string))
+        .or_else(|| {
+            metadata
+                .lines()
+                .filter_map(|l| l.strip_prefix("Classifier: License :: OSI Approved :: "))
+                .find_map(classifier_license)
+        })?;
+    Some((license, path.to_string_lossy().replace('\\', "/")))
+}
+
+fn classifier_license(classifier: &str) -> Option<String> {
+    let id = match classifier.trim() {
+        "MIT License" => "MIT",
+        "Apache Software License" => "Apache-2.0",
+        "BSD License" => "BSD-3-Clause",
+        "ISC License (ISCL)" => "ISC",
+        "Mozilla Public License 2.0 (MPL 2.0)" => "MPL-2.0",
+        "Python Software Foundation License" => "PSF-2.0",
+        "GNU General Public License v2 (GPLv2)" => "GPL-2.0-only",
+        "GNU General Public License v3 (GPLv3)" => "GPL-3.0-only",
+        "GNU Lesser General Public License v3 (LGPLv3)" => "LGPL-3.0-only",
+        "GNU Affero General Public License v3" => "AGPL-3.0-only",
+        _ => return None,
+    };
+    Some(id.to_string())
+}
+
+// ----- Policy -----
+
+enum Expression {
+    License(String),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    // OR binds looser than AND; `X WITH exception` is one term. Cargo's legacy `MIT/Apache-2.0` reads as OR.
+    fn parse(text: &str) -> Option<Self> {
+        let spaced = text.replace('/', " OR ").replace('(', " ( ").replace(')', " ) ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+        let mut position = 0;
+        let expression = Self::or(&tokens, &mut position)?;
+        (position == tokens.len()).then_some(expression)
+    }
+
+    fn or(tokens: &[&str], position: &mut usize) -> Option<Self> {
+        let mut left = Self::and(tokens, position)?;
+        while tokens.get(*position).is_some_and(|t| t.eq_ignore_ascii_case("OR")) {
+            *position += 1;
+            left = Expression::Or(Box::new(left), Box::new(Self::and(tokens, position)?));
+        }
+        Some(left)
+    }
+
+    fn and(tokens: &[&str], position: &mut usize) -> Option<Self> {
+        let mut left = Self::term(tokens, position)?;
+        while tokens.get(*position).is_some_and(|t| t.eq_ignore_ascii_case("AND")) {
+            *position += 1;
+            left = Expression::And(Box::new(left), Box::new(Self::term(tokens, position)?));
+        }
+        Some(left)
+    }
+
+    fn term(tokens: &[&str], position: &mut usize) -> Option<Self> {
+        let token = *tokens.get(*position)?;
+        *position += 1;
+        if token == "(" {
+            let inner = Self::or(tokens, position)?;
+            if tokens.get(*position) != Some(&")") {
+                return None;
+            }
+            *position += 1;
+            return Some(inner);
+        }
+        let id_chars = token.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+' | ':'));
+        if matches!(token.to_uppercase().as_str(), "AND" | "OR" | "WITH" | ")") || !id_chars {
+            return None;
+        }
+        let mut id = normalize_id(token);
+        if tokens.get(*position).is_some_and(|t| t.eq_ignore_ascii_case("WITH")) {
+            id = format!("{} with {}", id, tokens.get(*position + 1)?.to_lowercase());
+            *position += 2;
+        }
+        Some(Expression::License(id))
+    }
+
+    fn satisfiable(&self, ok: &dyn Fn(&str) -> bool) -> bool {
+        match self {
+            Expression::License(id) => ok(id),
+            Expression::And(a, b) => a.satisfiable(ok) && b.satisfiable(ok),
+            Expression::Or(a, b) => a.satisfiable(ok) || b.satisfiable(ok),
+        }
+    }
+
+    fn ids(&self, into: &mut Vec<String>) {
+        match self {
+            Expression::License(id) => into.push(id.clone()),
+            Expression::And(a, b) | Expression::Or(a, b) => {
+                a.ids(into);
+                b.ids(into);
+            }
+        }
+    }
+}
+
+/// The declared license as an SPDX expression, or `None` for free-form text (`SEE LICENSE IN ...`)
+pub fn spdx_expression(license: &str) -> Option<String> {
+    let expression = license.trim().replace('/', " OR ");
+    let free_form = expression == "UNLICENSED" || expression.starts_with("SEE ");
+    (!free_form && Expression::parse(&expression).is_some()).then_some(expression)
+}
+
+// `GPL-3.0`, `GPL-3.0-only` and `gpl-3.0` name the same license; `GPL-3.0+` is `GPL-3.0-or-later`
+fn normalize_id(id: &str) -> String {
+    let id = id.to_lowercase();
+    match id.strip_suffix('+') {
+        Some(base) => format!("{}-or-later", base),
+        None => id.strip_suffix("-only").unwrap_or(&id).to_string(),
+    }
+}
+
+fn policy_ids(entries: &[String]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for entry in entries {
+        match Expression::parse(entry) {
+            Some(expression) => expression.ids(&mut ids),
+            None => ids.push(normalize_id(entry.trim())),
+        }
+    }
+    ids
+}
+
+// A policy entry naming a license also covers its `-or-later` form and the license with an exception;
+// `GPL-3.0-or-later` in the policy still means only that form
+fn covers(entry: &str, id: &str) -> bool {
+    let base = id.split(" with ").next().unwrap_or(id);
+    entry == id || entry == base || base.strip_suffix("-or-later") == Some(entry)
+}
+
+impl LicensePolicy {
+    /// Classifies each dependency; a license passes if some choice within it uses only allowed, undenied licenses
+    pub fn check(&self, dependencies: &[DependencyLicense]) -> Vec<LicenseCheck> {
+        let allow = policy_ids(&self.allow);
+        let deny = policy_ids(&self.deny);
+        let not_denied = |id: &str| !deny.iter().any(|d| covers(d, id));
+        let permitted = |id: &str| not_denied(id) && (allow.is_empty() || allow.iter().any(|a| covers(a, id)));
+
+        dependencies
+            .iter()
+            .map(|dependency| {
+                let status = match dependency.license.as_deref().and_then(Expression::parse) {
+                    None => Status::Unknown,
+                    Some(expression) if expression.satisfiable(&permitted) => Status::Allowed,
+                    Some(expression) if expression.satisfiable(&not_denied) => Status::NotAllowed,
+                    Some(_) => Status::Denied,
+                };
+                LicenseCheck {
+                    dependency: dependency.clone(),
+                    status,
+                    violation: match status {
+                        Status::Allowed => false,
+                        Status::Unknown => self.fail_on_unknown,
+                        Status::Denied | Status::NotAllowed => true,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(text: &str) -> Vec<String> {
+        let mut ids = Vec::new();
+        Expression::parse(text).expect("valid expression").ids(&mut ids);
+        ids
+    }
+
+    fn dependency(license: Option<&str>) -> DependencyLicense {
+        DependencyLicense {
+            name: "demo".to_string(),
+            version: "1.0.0".to_string(),
+            ecosystem: Ecosystem::Npm,
+            lockfile: "package-lock.json".to_string(),
+            license: license.map(str::to_string),
+            source: None,
+        }
+    }
+
+    fn status(policy: &LicensePolicy, license: &str) -> Status {
+        policy.check(&[dependency(Some(license))])[0].status
+    }
+
+    fn policy(allow: &[&str], deny: &[&str]) -> LicensePolicy {
+        LicensePolicy {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+            fail_on_unknown: false,
+        }
+    }
+
+    #[test]
+    fn parses_expressions_with_precedence_and_exceptions() {
+        assert_eq!(ids("MIT OR Apache-2.0"), ["mit", "apache-2.0"]);
+        assert_eq!(ids("MIT/Apache-2.0"), ["mit", "apache-2.0"]);
+        assert_eq!(ids("GPL-2.0+ WITH Classpath-exception-2.0"), ["gpl-2.0-or-later with classpath-exception-2.0"]);
+        assert_eq!(ids("GPL-3.0-only"), ["gpl-3.0"]);
+
+        // AND binds tighter: MIT OR (Apache-2.0 AND BSD-3-Clause)
+        let expression = Expression::parse("MIT OR Apache-2.0 AND BSD-3-Clause").unwrap();
+        assert!(expression.satisfiable(&|id| id == "mit"));
+        assert!(!expression.satisfiable(&|id| id == "apache-2.0"));
+        assert!(Expression::parse("(MIT OR Apache-2.0) AND BSD-3-Clause").unwrap().satisfiable(&|id| id != "mit"));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert!(Expression::parse("").is_none());
+        assert!(Expression::parse("MIT OR").is_none());
+        assert!(Expression::parse("(MIT").is_none());
+        assert!(Expression::parse("MIT Apache-2.0").is_none());
+    }
+
+    #[test]
+    fn spdx_expression_keeps_ids_and_drops_free_form_text() {
+        assert_eq!(spdx_expression("MIT/Apache-2.0").as_deref(), Some("MIT OR Apache-2.0"));
+        assert_eq!(spdx_expression("(MIT OR Apache-2.0) AND Zlib").as_deref(), Some("(MIT OR Apache-2.0) AND Zlib"));
+        assert_eq!(spdx_expression("SEE LICENSE IN LICENSE.txt"), None);
+        assert_eq!(spdx_expression("UNLICENSED"), None);
+        assert_eq!(spdx_expression("Apache License, Version 2.0"), None);
+    }
+
+    #[test]
+    fn denied_base_license_covers_or_later_forms() {
+        let policy = policy(&[], &["GPL-3.0", "AGPL-3.0"]);
+        assert_eq!(status(&policy, "GPL-3.0-or-later"), Status::Denied);
+        assert_eq!(status(&policy, "GPL-3.0+"), Status::Denied);
+        assert_eq!(status(&policy, "GPL-3.0-only"), Status::Denied);
+        assert_eq!(status(&policy, "AGPL-3.0-or-later"), Status::Denied);
+        assert_eq!(status(&policy, "GPL-2.0-or-later"), Status::Allowed);
+    }
+
+    #[test]
+    fn or_later_entry_does_not_cover_only() {
+        let policy = policy(&[], &["GPL-3.0-or-later"]);
+        assert_eq!(status(&policy, "GPL-3.0-or-later"), Status::Denied);
+        assert_eq!(status(&policy, "GPL-3.0-only"), Status::Allowed);
+    }
+
+    #[test]
+    fn dual_licenses_pass_when_one_choice_is_allowed() {
+        let policy = policy(&["MIT", "Apache-2.0"], &["GPL-3.0"]);
+        assert_eq!(status(&policy, "MIT OR GPL-3.0-or-later"), Status::Allowed);
+        assert_eq!(status(&policy, "MIT AND GPL-3.0-or-later"), Status::Denied);
+        assert_eq!(status(&policy, "ISC"), Status::NotAllowed);
+    }
+
+    #[test]
+    fn unknown_licenses_fail_only_when_configured() {
+        let mut policy = policy(&["MIT"], &[]);
+        assert!(!policy.check(&[dependency(None)])[0].violation);
+        policy.fail_on_unknown = true;
+        let check = &policy.check(&[dependency(None)])[0];
+        assert_eq!(check.status, Status::Unknown);
+        assert!(check.violation);
+    }
+
+    #[test]
+    fn full_gpl_text_is_not_or_later() {
+        let text = "GNU GENERAL PUBLIC LICENSE Version 3, 29 June 2007 ... TERMS AND CONDITIONS ... \
+            How to Apply These Terms to Your New Programs ... either version 3 of the License, or \
+            (at your option) any later version.";
+        assert_eq!(identify_text(text).as_deref(), Some("GPL-3.0"));
+
+        let with_notice = format!("This program is released under the GPL, version 3 or any later version.\n\n{}", text);
+        assert_eq!(identify_text(&with_notice).as_deref(), Some("GPL-3.0-or-later"));
+
+        let header = "This program is free software: you can redistribute it under the terms of the \
+            GNU GENERAL PUBLIC LICENSE as published by the Free Software Foundation, version 3.";
+        assert_eq!(identify_text(header).as_deref(), Some("GPL-3.0-only"));
+    }
+}
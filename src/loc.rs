@@ -0,0 +1,289 @@
+// loc.rs - Code, comment and blank line counting
+// A cloc-style counter that understands each language's comment and string syntax
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCounts {
+    pub code: usize,
+    pub comment: usize,
+    pub blank: usize,
+}
+
+// ----- Comment Syntax -----
+
+struct CommentSyntax {
+    line: &'static [&'static str],
+    block: &'static [(&'static str, &'static str)],
+    nested: bool,
+    // String delimiters, longest first; multi-line ones may stay open across lines
+    strings: &'static [&'static str],
+    // Triple-quoted strings that start a line are docstrings (Python)
+    docstrings: &'static [&'static str],
+    // Single-character delimiters whose strings may span lines; longer ones always may
+    multiline: &'static [&'static str],
+    // Prefixes of strings without escapes: Rust `r"..."`/`r#"..."#`, C# verbatim `@"..."`
+    raw: &'static [&'static str],
+}
+
+const C_BLOCK: &[(&str, &str)] = &[("/*", "*/")];
+const HTML_BLOCK: &[(&str, &str)] = &[("<!--", "-->")];
+const QUOTES: &[&str] = &["\"", "'"];
+const DOUBLE_QUOTE: &[&str] = &["\""];
+
+const fn syntax(
+    line: &'static [&'static str],
+    block: &'static [(&'static str, &'static str)],
+    nested: bool,
+    strings: &'static [&'static str],
+) -> CommentSyntax {
+    CommentSyntax { line, block, nested, strings, docstrings: &[], multiline: &[], raw: &[] }
+}
+
+fn comment_syntax(language: &str) -> CommentSyntax {
+    match language {
+        "Rust" => CommentSyntax { multiline: DOUBLE_QUOTE, raw: &["br", "r"], ..syntax(&["//"], C_BLOCK, true, DOUBLE_QUOTE) },
+        "Kotlin" | "Scala" | "Swift" | "Dart" => syntax(&["//"], C_BLOCK, true, &["\"\"\"", "\"", "'"]),
+        "C#" => CommentSyntax { raw: &["@"], ..syntax(&["//"], C_BLOCK, false, QUOTES) },
+        "C" | "C++" | "Java" | "Objective-C" | "Groovy" | "Protocol Buffers" => {
+            syntax(&["//"], C_BLOCK, false, QUOTES)
+        }
+        "JavaScript" | "TypeScript" | "Vue" | "Svelte" | "Go" => {
+            CommentSyntax { multiline: &["`"], ..syntax(&["//"], C_BLOCK, false, &["`", "\"", "'"]) }
+        }
+        "PHP" => syntax(&["//", "#"], C_BLOCK, false, QUOTES),
+        "F#" => syntax(&["//"], &[("(*", "*)")], true, DOUBLE_QUOTE),
+        "Zig" => syntax(&["//"], &[], false, QUOTES),
+        "CSS" => syntax(&[], C_BLOCK, false, QUOTES),
+        "SCSS" | "Less" => syntax(&["//"], C_BLOCK, false, QUOTES),
+        "Python" => CommentSyntax {
+            line: &["#"],
+            block: &[],
+            nested: false,
+            strings: &["\"\"\"", "'''", "\"", "'"],
+            docstrings: &["\"\"\"", "'''"],
+            multiline: &[],
+            raw: &[],
+        },
+        "Ruby" => syntax(&["#"], &[("=begin", "=end")], false, QUOTES),
+        "Perl" => syntax(&["#"], &[("=pod", "=cut")], false, QUOTES),
+        "PowerShell" => syntax(&["#"], &[("<#", "#>")], false, QUOTES),
+        "Julia" => syntax(&["#"], &[("#=", "=#")], true, &["\"\"\"", "\""]),
+        "Nix" => syntax(&["#"], C_BLOCK, false, &["''", "\""]),
+        "HCL" => syntax(&["#", "//"], C_BLOCK, false, DOUBLE_QUOTE),
+        "Shell" | "R" | "Elixir" | "Makefile" | "CMake" | "Dockerfile" | "YAML" | "TOML" | "Dotenv"
+        | "Ignore List" | "Git Attributes" | "GraphQL" => syntax(&["#"], &[], false, QUOTES),
+        "INI" => syntax(&[";", "#"], &[], false, &[]),
+        "SQL" => syntax(&["--"], C_BLOCK, false, QUOTES),
+        "Lua" => syntax(&["--"], &[("--[[", "]]")], false, QUOTES),
+        "Haskell" => syntax(&["--"], &[("{-", "-}")], true, DOUBLE_QUOTE),
+        "OCaml" => syntax(&[], &[("(*", "*)")], true, DOUBLE_QUOTE),
+        "Erlang" | "TeX" => syntax(&["%"], &[], false, DOUBLE_QUOTE),
+        "Clojure" => syntax(&[";"], &[], false, DOUBLE_QUOTE),
+        "Assembly" => syntax(&[";", "#"], &[], false, DOUBLE_QUOTE),
+        "Batchfile" => syntax(&["REM ", "rem ", "::"], &[], false, &[]),
+        "HTML" | "XML" | "Markdown" | "reStructuredText" | "AsciiDoc" => syntax(&[], HTML_BLOCK, false, &[]),
+        _ => syntax(&[], &[], false, &[]),
+    }
+}
+
+// ----- Counting -----
+
+#[derive(Default)]
+struct State {
+    // Closing marker and nesting depth of the open block comment
+    block: Option<(&'static str, &'static str, usize)>,
+    // The string the scan is inside, if any
+    string: Option<OpenString>,
+}
+
+struct OpenString {
+    close: String,
+    escapes: bool,
+    multiline: bool,
+}
+
+/// Counts code, comment and blank lines for `content` written in `language`.
+/// Lines mixing code and a trailing comment count as code, as cloc does.
+pub fn count_lines(content: &str, language: &str) -> LineCounts {
+    let syntax = comment_syntax(language);
+    let mut state = State::default();
+    let mut counts = LineCounts::default();
+
+    for line in content.lines() {
+        let in_string = state.string.is_some();
+        let (has_code, has_comment) = scan_line(line, &syntax, &mut state);
+
+        if has_code || in_string {
+            counts.code += 1;
+        } else if has_comment {
+            counts.comment += 1;
+        } else if line.trim().is_empty() {
+            counts.blank += 1;
+        } else {
+            counts.code += 1;
+        }
+
+        // An unterminated string that can't span lines ends with the line
+        if state.string.as_ref().is_some_and(|s| !s.multiline) {
+            state.string = None;
+        }
+    }
+
+    counts
+}
+
+fn scan_line(line: &str, syntax: &CommentSyntax, state: &mut State) -> (bool, bool) {
+    let mut has_code = false;
+    let mut has_comment = false;
+    let mut i = 0;
+
+    while i < line.len() {
+        let rest = &line[i..];
+
+        if let Some((open, close, depth)) = state.block {
+            has_comment = true;
+            if syntax.nested && rest.starts_with(open) {
+                state.block = Some((open, close, depth + 1));
+                i += open.len();
+            } else if rest.starts_with(close) {
+                state.block = if depth > 1 { Some((open, close, depth - 1)) } else { None };
+                i += close.len();
+            } else {
+                i += char_len(rest);
+            }
+            continue;
+        }
+
+        if let Some(open) = &state.string {
+            has_code = true;
+            if let Some(escaped) = rest.strip_prefix('\\').filter(|_| open.escapes) {
+                i += 1 + escaped.chars().next().map_or(0, char::len_utf8);
+            } else if !open.escapes && open.close == "\"" && rest.starts_with("\"\"") {
+                // A doubled quote inside a verbatim string
+                i += 2;
+            } else if rest.starts_with(open.close.as_str()) {
+                i += open.close.len();
+                state.string = None;
+            } else {
+                i += char_len(rest);
+            }
+            continue;
+        }
+
+        if rest.starts_with(char::is_whitespace) {
+            i += char_len(rest);
+            continue;
+        }
+
+        if let Some(&(open, close)) = syntax.block.iter().find(|(open, _)| rest.starts_with(open)) {
+            state.block = Some((open, close, 1));
+            has_comment = true;
+            i += open.len();
+            continue;
+        }
+
+        if syntax.line.iter().any(|marker| rest.starts_with(marker)) {
+            has_comment = true;
+            break;
+        }
+
+        if !has_code {
+            if let Some(&delim) = syntax.docstrings.iter().find(|d| rest.starts_with(*d)) {
+                state.block = Some((delim, delim, 1));
+                has_comment = true;
+                i += delim.len();
+                continue;
+            }
+        }
+
+        // A quote character literal (`'"'` in Rust) doesn't open a string
+        if !syntax.strings.contains(&"'") {
+            if let Some(literal) = ["'\"'", "'\\\"'"].iter().find(|l| rest.starts_with(**l)) {
+                has_code = true;
+                i += literal.len();
+                continue;
+            }
+        }
+
+        if let Some((len, close)) = raw_string(&line[..i], rest, syntax) {
+            state.string = Some(OpenString { close, escapes: false, multiline: true });
+            i += len;
+        } else if let Some(&delim) = syntax.strings.iter().find(|d| rest.starts_with(*d)) {
+            state.string = Some(OpenString {
+                close: delim.to_string(),
+                escapes: true,
+                multiline: delim.len() > 1 || syntax.multiline.contains(&delim),
+            });
+            i += delim.len();
+        } else {
+            i += char_len(rest);
+        }
+        has_code = true;
+    }
+
+    (has_code, has_comment)
+}
+
+// Length of a raw string's opening (`r#"`) and its closing (`"#`), unless `rest` is part of an identifier
+fn raw_string(before: &str, rest: &str, syntax: &CommentSyntax) -> Option<(usize, String)> {
+    if before.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    let prefix = syntax.raw.iter().find(|p| rest.starts_with(**p))?;
+    let after = &rest[prefix.len()..];
+    let hashes = if *prefix == "@" { 0 } else { after.len() - after.trim_start_matches('#').len() };
+    after[hashes..].starts_with('"').then(|| (prefix.len() + hashes + 1, format!("\"{}", "#".repeat(hashes))))
+}
+
+fn char_len(s: &str) -> usize {
+    s.chars().next().map_or(1, char::len_utf8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(content: &str, language: &str) -> (usize, usize, usize) {
+        let counts = count_lines(content, language);
+        (counts.code, counts.comment, counts.blank)
+    }
+
+    #[test]
+    fn separates_code_comments_and_blanks() {
+        assert_eq!(counts("// header\n\nfn main() {} // trailing\n/* block\n   still */\n", "Rust"), (1, 3, 1));
+    }
+
+    #[test]
+    fn rust_strings_span_lines() {
+        let source = "let help = \"usage:\n  // not a comment\n  /* nor this\";\nlet x = 1;\n";
+        assert_eq!(counts(source, "Rust"), (4, 0, 0));
+    }
+
+    #[test]
+    fn rust_raw_strings_ignore_quotes_and_escapes() {
+        let source = "let re = r#\"\\d+ \"quoted\"\n// inside\n\"#;\n// after\n";
+        assert_eq!(counts(source, "Rust"), (3, 1, 0));
+        assert_eq!(counts("let path = r\"C:\\\";\n// after\n", "Rust"), (1, 1, 0));
+    }
+
+    #[test]
+    fn rust_quote_char_literals_do_not_open_strings() {
+        assert_eq!(counts("let q = '\"';\n// comment\nlet e = '\\\"';\n// comment\n", "Rust"), (2, 2, 0));
+    }
+
+    #[test]
+    fn rust_block_comments_nest() {
+        assert_eq!(counts("/* outer /* inner */ still outer */\nfn f() {}\n", "Rust"), (1, 1, 0));
+        assert_eq!(counts("/* /*\n */\n */\nfn f() {}\n", "Rust"), (1, 3, 0));
+    }
+
+    #[test]
+    fn go_backticks_and_csharp_verbatim_strings_span_lines() {
+        assert_eq!(counts("q := `SELECT *\n-- all\n// rows`\n", "Go"), (3, 0, 0));
+        assert_eq!(counts("var s = @\"a \"\"b\"\"\n// c\";\n", "C#"), (2, 0, 0));
+    }
+
+    #[test]
+    fn single_quoted_strings_end_with_the_line() {
+        assert_eq!(counts("char *s = \"unterminated\n// comment\n", "C"), (1, 1, 0));
+    }
+}
@@ -0,0 +1,623 @@
+// lockfiles.rs - Resolved dependency tree analysis
+// Reads Cargo, npm, Yarn, pnpm, Poetry and Go lockfiles to count, de-duplicate and measure dependencies
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use crate::manifests::{normalize_python, DependencyKind, Ecosystem, Manifest};
+use crate::source::FileSource;
+
+pub const LOCKFILES: &[&str] = &[
+    "Cargo.lock", "package-lock.json", "yarn.lock", "pnpm-lock.yaml", "poetry.lock", "go.sum",
+];
+
+// Chains reported per lockfile
+const CHAINS: usize = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// Required by a workspace member or manifest rather than by another package
+    #[serde(default)]
+    pub direct: bool,
+    /// `name@version` keys of the packages it requires
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// SPDX expression, when the lockfile records one
+    #[serde(default)]
+    pub license: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DuplicatePackage {
+    pub name: String,
+    pub versions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DependencyReport {
+    pub lockfile: String,
+    pub ecosystem: Ecosystem,
+    /// Third-party packages, excluding the project's own workspace members
+    pub total: usize,
+    pub direct: usize,
+    pub transitive: usize,
+    pub duplicates: Vec<DuplicatePackage>,
+    /// Depth of the package furthest from any direct dependency (direct = 1)
+    pub max_depth: usize,
+    /// The longest of those chains, as `name@version` steps
+    pub deepest_chains: Vec<Vec<String>>,
+    /// Every locked package, for SBOM and audit
+    #[serde(default)]
+    pub packages: Vec<LockedPackage>,
+}
+
+// Packages are keyed `name@version`
+#[derive(Default)]
+struct LockGraph {
+    packages: BTreeMap<String, LockedPackage>,
+    edges: BTreeMap<String, BTreeSet<String>>,
+    /// Workspace members; their dependencies are the direct ones
+    roots: BTreeSet<String>,
+    /// Direct dependencies, when they come from the manifest instead of root packages
+    direct: BTreeSet<String>,
+}
+
+impl LockGraph {
+    fn add(&mut self, name: &str, version: &str) -> String {
+        let key = format!("{}@{}", name, version);
+        self.packages.entry(key.clone()).or_insert_with(|| LockedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            direct: false,
+            dependencies: Vec::new(),
+            license: None,
+        });
+        key
+    }
+
+    fn link(&mut self, from: &str, to: String) {
+        if from != to {
+            self.edges.entry(from.to_string()).or_default().insert(to);
+        }
+    }
+
+    fn by_name(&self) -> HashMap<&str, Vec<&str>> {
+        let mut by_name: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (key, package) in &self.packages {
+            by_name.entry(package.name.as_str()).or_default().push(key.as_str());
+        }
+        by_name
+    }
+}
+
+/// Analyzes every lockfile among `paths`, taking direct dependencies from sibling manifests when
+/// the lockfile doesn't record them
+pub fn analyze(source: &dyn FileSource, paths: &[PathBuf], manifests: &[Manifest]) -> Vec<DependencyReport> {
+    let mut reports: Vec<DependencyReport> = paths
+        .iter()
+        .filter_map(|path| {
+            let file_name = path.file_name()?.to_str()?;
+            let content = source.read_to_string(path)?;
+            let dir = path.parent().unwrap_or(Path::new(""));
+            let manifest = |ecosystem: Ecosystem| {
+                manifests
+                    .iter()
+                    .find(|m| m.ecosystem == ecosystem && Path::new(&m.path).parent().unwrap_or(Path::new("")) == dir)
+            };
+
+            let (ecosystem, graph) = match file_name {
+                "Cargo.lock" => (Ecosystem::Cargo, parse_cargo_lock(&content)?),
+                "package-lock.json" => (Ecosystem::Npm, parse_package_lock(&content, manifest(Ecosystem::Npm))?),
+                "yarn.lock" => (Ecosystem::Npm, parse_yarn_lock(&content, manifest(Ecosystem::Npm))),
+                "pnpm-lock.yaml" => (Ecosystem::Npm, parse_pnpm_lock(&content)?),
+                "poetry.lock" => (Ecosystem::PyPI, parse_poetry_lock(&content, manifest(Ecosystem::PyPI))?),
+                "go.sum" => (Ecosystem::Go, parse_go_sum(&content, manifest(Ecosystem::Go))),
+                _ => return None,
+            };
+            Some(report(&path.to_string_lossy().replace('\\', "/"), ecosystem, &graph))
+        })
+        .collect();
+
+    reports.sort_by(|a, b| a.lockfile.cmp(&b.lockfile));
+    reports
+}
+
+fn report(lockfile: &str, ecosystem: Ecosystem, graph: &LockGraph) -> DependencyReport {
+    let direct: BTreeSet<&str> = if graph.roots.is_empty() {
+        graph.direct.iter().map(String::as_str).collect()
+    } else {
+        graph
+            .roots
+            .iter()
+            .flat_map(|root| graph.edges.get(root).into_iter().flatten())
+            .filter(|key| !graph.roots.contains(*key))
+            .map(String::as_str)
+            .collect()
+    };
+
+    // Breadth-first from the direct dependencies gives each package its shortest chain
+    let mut depth: HashMap<&str, usize> = direct.iter().map(|&key| (key, 1)).collect();
+    let mut parent: HashMap<&str, &str> = HashMap::new();
+    let mut queue: VecDeque<&str> = direct.iter().copied().collect();
+    while let Some(key) = queue.pop_front() {
+        for next in graph.edges.get(key).into_iter().flatten() {
+            if !depth.contains_key(next.as_str()) && !graph.roots.contains(next) {
+                depth.insert(next, depth[key] + 1);
+                parent.insert(next, key);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let max_depth = depth.values().copied().max().unwrap_or(0);
+    let mut deepest: Vec<(&str, usize)> = depth.iter().map(|(&k, &d)| (k, d)).filter(|&(_, d)| d > 1).collect();
+    deepest.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    // Skip packages already on a reported chain so each chain shows a different path
+    let mut deepest_chains: Vec<Vec<String>> = Vec::new();
+    for &(key, _) in &deepest {
+        if deepest_chains.len() == CHAINS {
+            break;
+        }
+        if deepest_chains.iter().flatten().any(|step| step == key) {
+            continue;
+        }
+        let mut chain = vec![key.to_string()];
+        let mut node = key;
+        while let Some(&previous) = parent.get(node) {
+            chain.push(previous.to_string());
+            node = previous;
+        }
+        chain.reverse();
+        deepest_chains.push(chain);
+    }
+
+    let packages: Vec<LockedPackage> = graph
+        .packages
+        .iter()
+        .filter(|(key, _)| !graph.roots.contains(*key))
+        .map(|(key, package)| LockedPackage {
+            direct: direct.contains(key.as_str()),
+            dependencies: graph
+                .edges
+                .get(key)
+                .into_iter()
+                .flatten()
+                .filter(|to| !graph.roots.contains(*to))
+                .cloned()
+                .collect(),
+            ..package.clone()
+        })
+        .collect();
+
+    let mut versions: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for package in &packages {
+        versions.entry(&package.name).or_default().insert(&package.version);
+    }
+    let duplicates = versions
+        .into_iter()
+        .filter(|(_, v)| v.len() > 1)
+        .map(|(name, v)| DuplicatePackage {
+            name: name.to_string(),
+            versions: v.into_iter().map(str::to_string).collect(),
+        })
+        .collect();
+
+    DependencyReport {
+        lockfile: lockfile.to_string(),
+        ecosystem,
+        total: packages.len(),
+        direct: direct.len(),
+        transitive: packages.len().saturating_sub(direct.len()),
+        duplicates,
+        max_depth,
+        deepest_chains,
+        packages,
+    }
+}
+
+// Resolves manifest dependencies by name, for lockfiles that don't record the root's dependencies
+fn direct_from_manifest(graph: &mut LockGraph, manifest: Option<&Manifest>, normalize: fn(&str) -> String) {
+    let Some(manifest) = manifest else { return };
+    let by_name: HashMap<String, String> = graph
+        .packages
+        .iter()
+        .map(|(key, package)| (normalize(&package.name), key.clone()))
+        .collect();
+    graph.direct = manifest
+        .dependencies
+        .iter()
+        .filter_map(|d| by_name.get(&normalize(&d.name)).cloned())
+        .collect();
+}
+
+// ----- Cargo -----
+
+fn parse_cargo_lock(content: &str) -> Option<LockGraph> {
+    let toml: toml::Value = toml::from_str(content).ok()?;
+    let packages = toml.get("package")?.as_array()?;
+    let mut graph = LockGraph::default();
+
+    let mut entries = Vec::new();
+    for package in packages {
+        let name = package.get("name")?.as_str()?;
+        let version = package.get("version")?.as_str()?;
+        let key = graph.add(name, version);
+        // Workspace members are the only packages without a registry or git source
+        if package.get("source").is_none() {
+            graph.roots.insert(key.clone());
+        }
+        let deps: Vec<String> = package
+            .get("dependencies")
+            .and_then(|d| d.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|d| d.as_str().map(str::to_string))
+            .collect();
+        entries.push((key, deps));
+    }
+
+    // Entries are `name` when only one version is locked, otherwise `name version (source)`
+    let by_name: HashMap<String, Vec<String>> = graph
+        .by_name()
+        .into_iter()
+        .map(|(name, keys)| (name.to_string(), keys.into_iter().map(str::to_string).collect()))
+        .collect();
+    for (key, deps) in entries {
+        for dep in deps {
+            let mut parts = dep.split_whitespace();
+            let name = parts.next().unwrap_or_default();
+            let target = match parts.next() {
+                Some(version) => format!("{}@{}", name, version),
+                None => match by_name.get(name) {
+                    Some(keys) => keys[0].clone(),
+                    None => continue,
+                },
+            };
+            graph.link(&key, target);
+        }
+    }
+    Some(graph)
+}
+
+// ----- npm, Yarn and pnpm -----
+
+fn parse_package_lock(content: &str, manifest: Option<&Manifest>) -> Option<LockGraph> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut graph = LockGraph::default();
+
+    // lockfileVersion 2 and 3 list every installed path under "packages"
+    if let Some(packages) = json.get("packages").and_then(|p| p.as_object()) {
+        let mut keys: HashMap<&str, String> = HashMap::new();
+        for (path, info) in packages {
+            if info.get("link").and_then(|l| l.as_bool()).unwrap_or(false) {
+                continue;
+            }
+            let name = info
+                .get("name")
+                .and_then(|n| n.as_str())
+                .or_else(|| path.rsplit("node_modules/").next())
+                .filter(|n| !n.is_empty())
+                .unwrap_or("(root)");
+            let version = info.get("version").and_then(|v| v.as_str()).unwrap_or("0.0.0");
+            let key = graph.add(name, version);
+            if let Some(license) = info.get("license").and_then(|l| l.as_str()) {
+                graph.packages.entry(key.clone()).and_modify(|p| p.license = Some(license.to_string()));
+            }
+            if !path.contains("node_modules/") {
+                graph.roots.insert(key.clone());
+            }
+            keys.insert(path.as_str(), key);
+        }
+
+        for (path, info) in packages {
+            let Some(key) = keys.get(path.as_str()).cloned() else { continue };
+            let mut sections = vec!["dependencies", "optionalDependencies", "peerDependencies"];
+            if graph.roots.contains(&key) {
+                sections.push("devDependencies");
+            }
+            for section in sections {
+                for name in info.get(section).and_then(|d| d.as_object()).into_iter().flat_map(|d| d.keys()) {
+                    if let Some(target) = resolve_node_module(&keys, path, name) {
+                        graph.link(&key, target.clone());
+                    }
+                }
+            }
+        }
+        return Some(graph);
+    }
+
+    // lockfileVersion 1 nests dependencies and only names what each package requires
+    fn walk(graph: &mut LockGraph, deps: &serde_json::Map<String, serde_json::Value>, requires: &mut Vec<(String, String)>) {
+        for (name, info) in deps {
+            let version = info.get("version").and_then(|v| v.as_str()).unwrap_or("0.0.0");
+            let key = graph.add(name, version);
+            for required in info.get("requires").and_then(|r| r.as_object()).into_iter().flat_map(|r| r.keys()) {
+                requires.push((key.clone(), required.clone()));
+            }
+            if let Some(nested) = info.get("dependencies").and_then(|d| d.as_object()) {
+                walk(graph, nested, requires);
+            }
+        }
+    }
+    let mut requires = Vec::new();
+    walk(&mut graph, json.get("dependencies")?.as_object()?, &mut requires);
+    let by_name: HashMap<String, String> = graph
+        .packages
+        .iter()
+        .map(|(key, package)| (package.name.clone(), key.clone()))
+        .collect();
+    for (from, name) in requires {
+        if let Some(to) = by_name.get(&name) {
+            graph.link(&from, to.clone());
+        }
+    }
+    direct_from_manifest(&mut graph, manifest, str::to_string);
+    Some(graph)
+}
+
+// Node resolution: the closest node_modules directory walking up from the requiring package
+fn resolve_node_module<'a>(keys: &'a HashMap<&str, String>, from: &str, name: &str) -> Option<&'a String> {
+    let mut base = from;
+    loop {
+        let candidate = if base.is_empty() {
+            format!("node_modules/{}", name)
+        } else {
+            format!("{}/node_modules/{}", base, name)
+        };
+        if let Some(key) = keys.get(candidate.as_str()) {
+            return Some(key);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = base.rfind("/node_modules/").map_or("", |i| &base[..i]);
+    }
+}
+
+// `@scope/name@range` -> (`@scope/name`, `range`)
+fn split_spec(spec: &str) -> Option<(&str, &str)> {
+    let (at, _) = spec.char_indices().skip(1).find(|&(_, c)| c == '@')?;
+    Some((&spec[..at], &spec[at + 1..]))
+}
+
+// Handles both Yarn classic (`version "1.0.0"`) and Berry (`version: 1.0.0`) lockfiles
+fn parse_yarn_lock(content: &str, manifest: Option<&Manifest>) -> LockGraph {
+    struct Entry {
+        specs: Vec<String>,
+        version: String,
+        deps: Vec<(String, String)>,
+    }
+
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut in_deps = false;
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+        if indent == 0 && trimmed.ends_with(':') {
+            let specs = trimmed
+                .trim_end_matches(':')
+                .split(", ")
+                .map(|s| s.trim_matches('"').to_string())
+                .collect();
+            entries.push(Entry { specs, version: String::new(), deps: Vec::new() });
+            in_deps = false;
+            continue;
+        }
+        let Some(entry) = entries.last_mut() else { continue };
+        let (key, value) = match trimmed.split_once([' ', ':']) {
+            Some((k, v)) => (k.trim_matches('"'), v.trim_start_matches(':').trim().trim_matches('"')),
+            None => (trimmed.trim_end_matches(':'), ""),
+        };
+        if indent <= 2 {
+            in_deps = matches!(key, "dependencies" | "optionalDependencies") && value.is_empty();
+            if key == "version" {
+                entry.version = value.to_string();
+            }
+        } else if in_deps {
+            entry.deps.push((key.to_string(), value.to_string()));
+        }
+    }
+
+    let mut graph = LockGraph::default();
+    let mut by_spec: HashMap<String, String> = HashMap::new();
+    let mut keyed = Vec::new();
+    for entry in entries {
+        let Some((name, _)) = entry.specs.first().and_then(|s| split_spec(s)) else { continue };
+        if name == "__metadata" || entry.version.is_empty() {
+            continue;
+        }
+        let key = graph.add(name, &entry.version);
+        if entry.specs.iter().any(|s| s.contains("@workspace:")) {
+            graph.roots.insert(key.clone());
+        }
+        for spec in &entry.specs {
+            by_spec.insert(spec.clone(), key.clone());
+        }
+        keyed.push((key, entry.deps));
+    }
+
+    for (key, deps) in keyed {
+        for (name, range) in deps {
+            let target = by_spec
+                .get(&format!("{}@{}", name, range))
+                .or_else(|| by_spec.get(&format!("{}@npm:{}", name, range)));
+            if let Some(target) = target {
+                graph.link(&key, target.clone());
+            }
+        }
+    }
+
+    if graph.roots.is_empty() {
+        if let Some(manifest) = manifest {
+            graph.direct = manifest
+                .dependencies
+                .iter()
+                .filter_map(|d| {
+                    let range = d.requirement.as_deref().unwrap_or("*");
+                    by_spec
+                        .get(&format!("{}@{}", d.name, range))
+                        .or_else(|| by_spec.get(&format!("{}@npm:{}", d.name, range)))
+                        .cloned()
+                })
+                .collect();
+        }
+    }
+    graph
+}
+
+fn parse_pnpm_lock(content: &str) -> Option<LockGraph> {
+    let yaml: serde_yaml::Value = serde_yaml::from_str(content).ok()?;
+    let mut graph = LockGraph::default();
+
+    // pnpm 6+ lists each workspace project under `importers`; older single projects use the top level
+    let importers: Vec<&serde_yaml::Value> = match yaml.get("importers").and_then(|i| i.as_mapping()) {
+        Some(importers) => importers.values().collect(),
+        None => vec![&yaml],
+    };
+    for importer in importers {
+        for (name, version) in pnpm_deps(importer, &["dependencies", "devDependencies", "optionalDependencies"]) {
+            let key = graph.add(&name, &version);
+            graph.direct.insert(key);
+        }
+    }
+
+    // Dependencies live under `packages` up to lockfile v6 and under `snapshots` from v9
+    for section in ["packages", "snapshots"] {
+        for (key, info) in yaml.get(section).and_then(|p| p.as_mapping()).into_iter().flatten() {
+            let Some((name, version)) = key.as_str().and_then(pnpm_package_key) else { continue };
+            let from = graph.add(&name, &version);
+            for (dep, dep_version) in pnpm_deps(info, &["dependencies", "optionalDependencies"]) {
+                let to = graph.add(&dep, &dep_version);
+                graph.link(&from, to);
+            }
+        }
+    }
+    Some(graph)
+}
+
+fn pnpm_deps(value: &serde_yaml::Value, sections: &[&str]) -> Vec<(String, String)> {
+    sections
+        .iter()
+        .filter_map(|s| value.get(*s).and_then(|d| d.as_mapping()))
+        .flatten()
+        .filter_map(|(name, spec)| {
+            // Importers give `{ specifier, version }`, packages a bare version
+            let version = spec.get("version").unwrap_or(spec).as_str()?;
+            if version.starts_with("link:") || version.starts_with("file:") {
+                return None;
+            }
+            Some((name.as_str()?.to_string(), strip_peers(version).to_string()))
+        })
+        .collect()
+}
+
+// `/name/1.0.0_peer@2.0.0` (v5), `/name@1.0.0(peer@2.0.0)` (v6) or `name@1.0.0(peer@2.0.0)` (v9), scoped or not
+fn pnpm_package_key(key: &str) -> Option<(String, String)> {
+    let key = key.trim_start_matches('/');
+    // v5 separates the version with a slash; its peer suffix may hold `@`s of its own
+    let unscoped = if key.starts_with('@') { key.split_once('/')?.1 } else { key };
+    let v5 = unscoped.split_once('/').is_some_and(|(_, rest)| rest.starts_with(|c: char| c.is_ascii_digit()));
+    let (name, version) = if v5 {
+        key.rsplit_once('/')?
+    } else {
+        split_spec(key.split('(').next().unwrap_or(key))?
+    };
+    Some((name.to_string(), strip_peers(version).to_string()))
+}
+
+// Peer dependency suffixes: `1.0.0(react@18.2.0)` and the older `1.0.0_react@18.2.0`
+fn strip_peers(version: &str) -> &str {
+    version.split(['(', '_']).next().unwrap_or(version)
+}
+
+// ----- Python and Go -----
+
+fn parse_poetry_lock(content: &str, manifest: Option<&Manifest>) -> Option<LockGraph> {
+    let toml: toml::Value = toml::from_str(content).ok()?;
+    let mut graph = LockGraph::default();
+    let mut requires = Vec::new();
+
+    for package in toml.get("package")?.as_array()? {
+        let name = normalize_python(package.get("name")?.as_str()?);
+        let key = graph.add(&name, package.get("version")?.as_str()?);
+        for dep in package.get("dependencies").and_then(|d| d.as_table()).into_iter().flat_map(|d| d.keys()) {
+            requires.push((key.clone(), normalize_python(dep)));
+        }
+    }
+
+    let by_name: HashMap<String, String> = graph
+        .packages
+        .iter()
+        .map(|(key, package)| (package.name.clone(), key.clone()))
+        .collect();
+    for (from, name) in requires {
+        if let Some(to) = by_name.get(&name) {
+            graph.link(&from, to.clone());
+        }
+    }
+    direct_from_manifest(&mut graph, manifest, normalize_python);
+    Some(graph)
+}
+
+// go.sum has no graph, only the modules that were downloaded; `/go.mod`-only lines were never built
+fn parse_go_sum(content: &str, manifest: Option<&Manifest>) -> LockGraph {
+    let mut graph = LockGraph::default();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            if !version.ends_with("/go.mod") {
+                graph.add(module, version);
+            }
+        }
+    }
+
+    if let Some(manifest) = manifest {
+        graph.direct = manifest
+            .dependencies
+            .iter()
+            .filter(|d| d.kind == DependencyKind::Normal)
+            .filter_map(|d| {
+                let key = format!("{}@{}", d.name, d.requirement.as_deref()?);
+                graph.packages.contains_key(&key).then_some(key)
+            })
+            .collect();
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: &str) -> (String, String) {
+        pnpm_package_key(key).expect("valid pnpm key")
+    }
+
+    #[test]
+    fn reads_pnpm_v5_keys() {
+        assert_eq!(key("/lodash/4.17.21"), ("lodash".into(), "4.17.21".into()));
+        assert_eq!(key("/react-dom/18.2.0_react@18.2.0"), ("react-dom".into(), "18.2.0".into()));
+        assert_eq!(key("/@scope/name/1.0.0_peer@2.0.0"), ("@scope/name".into(), "1.0.0".into()));
+        assert_eq!(key("/@testing-library/react/14.0.0_@types+react@18.2.0"), ("@testing-library/react".into(), "14.0.0".into()));
+    }
+
+    #[test]
+    fn reads_pnpm_v6_keys() {
+        assert_eq!(key("/lodash@4.17.21"), ("lodash".into(), "4.17.21".into()));
+        assert_eq!(key("/react-dom@18.2.0(react@18.2.0)"), ("react-dom".into(), "18.2.0".into()));
+        assert_eq!(key("/@scope/name@1.0.0(@types/react@18.2.0)"), ("@scope/name".into(), "1.0.0".into()));
+    }
+
+    #[test]
+    fn reads_pnpm_v9_keys() {
+        assert_eq!(key("lodash@4.17.21"), ("lodash".into(), "4.17.21".into()));
+        assert_eq!(key("react-dom@18.2.0(react@18.2.0)"), ("react-dom".into(), "18.2.0".into()));
+        assert_eq!(key("@scope/name@1.0.0(peer@2.0.0)(other@3.0.0)"), ("@scope/name".into(), "1.0.0".into()));
+    }
+}
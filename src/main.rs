@@ -1,17 +1,65 @@
 // main.rs - Intelligent Codebase Onboarding Tool
 // A professional-grade tool for understanding and onboarding to any codebase
 
+mod advisories;
+mod ci;
+mod classify;
+mod cycles;
+mod deploy;
+mod diagram;
+mod entrypoints;
+mod graph;
+mod languages;
+mod layers;
+mod licenses;
+mod loc;
+mod lockfiles;
+mod manifests;
+mod metadata;
+mod parser;
+mod patterns;
+mod quickstart;
+mod ranking;
+mod reading;
+mod rules;
+mod sbom;
+mod source;
+mod stack;
+mod symbols;
+mod tour;
+
 use anyhow::{anyhow, Result};
 use clap::{Parser, Subcommand};
 use colored::*;
 use console::Term;
 use indicatif::{ProgressBar, ProgressStyle};
-use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
-use walkdir::WalkDir;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use advisories::{AdvisoryDb, Vulnerability};
+use ci::{Pipeline, Purpose};
+use classify::{Classifier, ExclusionKind};
+use cycles::{DependencyCycle, Severity};
+use deploy::Deployment;
+use diagram::{Diagram, DiagramOptions, Level};
+use graph::DependencyGraph;
+use languages::LanguageKind;
+use layers::Layering;
+use licenses::{LicenseCheck, LicensePolicy, ProjectLicense, Status};
+use lockfiles::DependencyReport;
+use manifests::Manifest;
+use metadata::Badge;
+use parser::ParsedFile;
+use patterns::PatternScore;
+use quickstart::Stage;
+use ranking::FileRank;
+use reading::Step;
+use rules::{Baseline, Rule};
+use source::FileSource;
+use stack::{Category, Technology};
+use symbols::SymbolIndex;
 
 // ----- Configuration -----
 static CONFIG_TEXT: &str = r#"# CodeMap Configuration
@@ -20,10 +68,12 @@ static CONFIG_TEXT: &str = r#"# CodeMap Configuration
 [general]
 # Number of files to analyze for onboarding
 default_analysis_files = 20
-# Maximum file size to analyze (in bytes)
+# Files larger than this (in bytes) are counted but not parsed; manifests and lockfiles are always read
 max_file_size = 100000
 # Enable/disable AI-powered insights
 enable_ai_insights = true
+# Project name shown in reports (defaults to the directory name)
+# project_name = "my-project"
 
 [ai]
 # LLM provider for code analysis
@@ -52,6 +102,38 @@ identify_tech_stack = true
 complexity_analysis = true
 # Enable code quality metrics
 quality_metrics = true
+
+[classification]
+# Leave generated and vendored files out of counts and metrics
+exclude_generated = true
+# Paths always analyzed as hand-written source (gitignore-style globs)
+include = []
+
+[audit]
+# Local RustSec advisory-db checkout or OSV export, used by `codemap audit` and
+# to score security in `codemap analyze` without network access
+# advisory_db = "/srv/mirrors/advisory-db"
+
+[licenses]
+# SPDX licenses dependencies may use; an empty list allows anything not denied
+allow = []
+# SPDX licenses that make `codemap licenses` fail, e.g. ["GPL-3.0-or-later", "AGPL-3.0"]
+deny = []
+# Also fail when a dependency's license can't be found offline
+fail_on_unknown = false
+
+# Architecture rules checked by `codemap check`. Patterns are globs over file
+# paths; a plain directory matches everything below it.
+#
+# [[rules]]
+# name = "domain-is-pure"
+# from = "src/domain"
+# forbid = ["src/infrastructure"]
+#
+# [[rules]]
+# name = "api-depends-only-on-core"
+# from = "crates/api"
+# allow = ["crates/core"]
 "#;
 
 // ----- Data Models -----
@@ -65,6 +147,18 @@ struct ProjectAnalysis {
     complexity_metrics: ComplexityMetrics,
     quality_metrics: QualityMetrics,
     onboarding_guide: OnboardingGuide,
+    /// Files to read first beyond the entry points, most important first
+    #[serde(default)]
+    key_files: Vec<FileRank>,
+    /// Declared dependencies, one per manifest
+    #[serde(default)]
+    manifests: Vec<Manifest>,
+    /// Resolved dependency trees, one per lockfile
+    #[serde(default)]
+    dependencies: Vec<DependencyReport>,
+    /// Advisories matching locked versions; None when no advisory database was given
+    #[serde(default)]
+    vulnerabilities: Option<Vec<Vulnerability>>,
     #[serde(default)]
     analysis_timestamp: String,
 }
@@ -78,6 +172,58 @@ struct ProjectInfo {
     total_lines: usize,
     total_functions: usize,
     project_size: String,
+    #[serde(default)]
+    language_stats: Vec<LanguageStats>,
+    #[serde(default)]
+    excluded_files: Vec<ExcludedFile>,
+    #[serde(default)]
+    file_stats: Vec<FileStats>,
+    #[serde(default)]
+    license: ProjectLicense,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    homepage: Option<String>,
+    #[serde(default)]
+    repository: Option<String>,
+    #[serde(default)]
+    authors: Vec<String>,
+    #[serde(default)]
+    badges: Vec<Badge>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LanguageStats {
+    language: String,
+    kind: LanguageKind,
+    files: usize,
+    lines: usize,
+    #[serde(default)]
+    code: usize,
+    #[serde(default)]
+    comment: usize,
+    #[serde(default)]
+    blank: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct FileStats {
+    path: String,
+    language: String,
+    code: usize,
+    comment: usize,
+    blank: usize,
+    #[serde(default)]
+    functions: usize,
+    #[serde(default)]
+    complexity: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ExcludedFile {
+    path: String,
+    kind: ExclusionKind,
+    reason: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -87,6 +233,15 @@ struct ArchitectureAnalysis {
     layers: Vec<String>,
     key_components: Vec<String>,
     data_flow: String,
+    /// Every pattern with supporting evidence, best first
+    #[serde(default)]
+    patterns: Vec<PatternScore>,
+    #[serde(default)]
+    layering: Layering,
+    #[serde(default)]
+    dependency_graph: DependencyGraph,
+    #[serde(default)]
+    cycles: Vec<DependencyCycle>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -96,6 +251,15 @@ struct TechStack {
     databases: Vec<String>,
     tools: Vec<String>,
     deployment: Vec<String>,
+    /// Each framework, database and tool with the manifest and version it was declared with
+    #[serde(default)]
+    technologies: Vec<Technology>,
+    /// Container, orchestration, infrastructure and platform configs behind `deployment`
+    #[serde(default)]
+    infrastructure: Vec<Deployment>,
+    /// CI/CD pipelines behind the CI providers in `tools`
+    #[serde(default)]
+    pipelines: Vec<Pipeline>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -105,6 +269,8 @@ struct EntryPoint {
     reason: String,
     complexity: String,
     importance: String,
+    #[serde(default)]
+    kind: Option<entrypoints::Kind>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -132,6 +298,12 @@ struct OnboardingGuide {
     common_patterns: Vec<String>,
     debugging_tips: Vec<String>,
     next_steps: Vec<String>,
+    /// How CI checks a change, one line per pipeline
+    #[serde(default)]
+    ci_checks: Vec<String>,
+    /// Files in the order to read them, with estimated reading times
+    #[serde(default)]
+    reading_path: Vec<Step>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -140,6 +312,14 @@ struct Config {
     ai: AiConfig,
     output: OutputConfig,
     analysis: AnalysisConfig,
+    #[serde(default)]
+    classification: ClassificationConfig,
+    #[serde(default)]
+    audit: AuditConfig,
+    #[serde(default)]
+    licenses: LicensePolicy,
+    #[serde(default)]
+    rules: Vec<Rule>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -147,6 +327,9 @@ struct GeneralConfig {
     default_analysis_files: usize,
     max_file_size: usize,
     enable_ai_insights: bool,
+    /// Set by `codemap init --name`
+    #[serde(default)]
+    project_name: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -172,6 +355,28 @@ struct AnalysisConfig {
     quality_metrics: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct ClassificationConfig {
+    exclude_generated: bool,
+    #[serde(default)]
+    include: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct AuditConfig {
+    #[serde(default)]
+    advisory_db: Option<PathBuf>,
+}
+
+impl Default for ClassificationConfig {
+    fn default() -> Self {
+        ClassificationConfig {
+            exclude_generated: true,
+            include: Vec::new(),
+        }
+    }
+}
+
 // ----- CLI Commands -----
 
 #[derive(Parser)]
@@ -199,6 +404,14 @@ enum Commands {
     /// Analyze and generate comprehensive report
     #[command(about = "Analyze codebase and generate insights")]
     Analyze {
+        /// Directory, .tar.gz/.tgz/.zip archive or git repository to analyze
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        
+        /// Git revision to analyze (branch, tag or commit)
+        #[arg(long)]
+        rev: Option<String>,
+        
         /// Output format: text, json, html
         #[arg(short, long, default_value = "text")]
         format: String,
@@ -210,11 +423,23 @@ enum Commands {
         /// Skip AI analysis (faster, offline-only)
         #[arg(long)]
         no_ai: bool,
+        
+        /// Count generated and vendored files like hand-written source
+        #[arg(long)]
+        include_generated: bool,
+        
+        /// RustSec or OSV advisory database directory used for the security score
+        #[arg(long)]
+        advisory_db: Option<PathBuf>,
     },
     
     /// Show project summary and entry points
     #[command(about = "Display project overview and key files")]
-    Summary,
+    Summary {
+        /// Also show the detailed sections, including the reading path
+        #[arg(short, long)]
+        detailed: bool,
+    },
     
     /// Interactive guided tour of the codebase
     #[command(about = "Start interactive codebase exploration")]
@@ -236,10 +461,121 @@ enum Commands {
     #[command(about = "Compare current state with previous analysis")]
     Diff,
     
+    /// Find where a symbol is defined
+    #[command(about = "Find functions, types and constants by name")]
+    Find {
+        /// Symbol name (fuzzy matched)
+        name: String,
+        
+        /// Only show these kinds: function, method, class, struct, enum, trait, interface, module, const, type
+        #[arg(short, long, value_delimiter = ',')]
+        kind: Vec<String>,
+        
+        /// Maximum number of results
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+    
+    /// Render the internal dependency graph
+    #[command(about = "Export the dependency graph as Graphviz DOT, Mermaid or JSON")]
+    Graph {
+        /// Output format: dot, mermaid, json
+        #[arg(short, long, default_value = "dot")]
+        format: String,
+        
+        /// Node granularity: file, module (directory), package
+        #[arg(short, long, default_value = "module")]
+        level: String,
+        
+        /// Group nodes into clusters by directory
+        #[arg(short, long)]
+        cluster: bool,
+        
+        /// Only include files under this path prefix
+        #[arg(long)]
+        filter: Option<String>,
+        
+        /// Collapse node paths deeper than this many components
+        #[arg(long)]
+        max_depth: Option<usize>,
+        
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    
+    /// Check imports against the architecture rules in config.toml
+    #[command(about = "Enforce architecture dependency rules")]
+    Check {
+        /// Directory, .tar.gz/.tgz/.zip archive or git repository to check
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        
+        /// Git revision to check (branch, tag or commit)
+        #[arg(long)]
+        rev: Option<String>,
+        
+        /// Record current violations as accepted in .codemap/check-baseline.json
+        #[arg(long)]
+        update_baseline: bool,
+    },
+    
+    /// Analyze lockfiles for dependency counts, duplicates and depth
+    #[command(about = "Show direct, transitive and duplicated dependencies")]
+    Deps {
+        /// Directory, .tar.gz/.tgz/.zip archive or git repository to inspect
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        
+        /// Git revision to inspect (branch, tag or commit)
+        #[arg(long)]
+        rev: Option<String>,
+        
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    
+    /// Match locked dependency versions against a local advisory database
+    #[command(about = "Find known vulnerabilities in locked dependencies")]
+    Audit {
+        /// Directory, .tar.gz/.tgz/.zip archive or git repository to audit
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        
+        /// Git revision to audit (branch, tag or commit)
+        #[arg(long)]
+        rev: Option<String>,
+        
+        /// RustSec advisory-db checkout or OSV export directory (defaults to [audit] advisory_db)
+        #[arg(long)]
+        advisory_db: Option<PathBuf>,
+        
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    
+    /// Resolve dependency licenses and check them against the [licenses] policy
+    #[command(about = "Show dependency licenses and enforce the license policy")]
+    Licenses {
+        /// Directory, .tar.gz/.tgz/.zip archive or git repository to inspect
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        
+        /// Git revision to inspect (branch, tag or commit)
+        #[arg(long)]
+        rev: Option<String>,
+        
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+    
     /// Export analysis report
     #[command(about = "Export analysis to various formats")]
     Export {
-        /// Output format: json, html, markdown
+        /// Output format: json, html, markdown, cyclonedx, spdx
         #[arg(short, long, default_value = "json")]
         format: String,
         
@@ -251,7 +587,13 @@ enum Commands {
 
 // ----- Core Analysis Functions -----
 
-fn analyze_codebase() -> Result<ProjectAnalysis> {
+fn analyze_codebase(
+    source: &dyn FileSource,
+    classifier: &Classifier,
+    max_file_size: usize,
+    advisory_db: Option<&AdvisoryDb>,
+    project_name: Option<&str>,
+) -> Result<(ProjectAnalysis, Vec<ParsedFile>)> {
     let _term = Term::stdout();
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(
@@ -261,77 +603,177 @@ fn analyze_codebase() -> Result<ProjectAnalysis> {
     );
     
     spinner.set_message("🔍 Analyzing project structure...");
-    let project_info = analyze_project_info()?;
+    let (mut project_info, parsed_files) = analyze_project_info(source, classifier, max_file_size)?;
+    
+    spinner.set_message("🕸️  Resolving internal imports...");
+    let dependency_graph = DependencyGraph::build(source, &parsed_files);
     
     spinner.set_message("🏗️  Detecting architecture patterns...");
-    let architecture = detect_architecture()?;
+    let architecture = detect_architecture(source, classifier, dependency_graph)?;
     
     spinner.set_message("🛠️  Identifying tech stack...");
-    let tech_stack = identify_tech_stack()?;
+    let files = project_files(source, classifier);
+    let manifests = manifests::parse_all(source, &files);
+    let tech_stack = identify_tech_stack(source, &project_info, &files, &manifests)?;
+    
+    spinner.set_message("📝 Reading project metadata...");
+    let metadata = metadata::detect(source, &files, &manifests);
+    if let Some(name) = project_name {
+        project_info.name = name.to_string();
+    }
+    project_info.description = metadata.description;
+    project_info.version = metadata.version;
+    project_info.homepage = metadata.homepage;
+    project_info.repository = metadata.repository;
+    project_info.authors = metadata.authors;
+    project_info.badges = metadata.badges;
+    
+    spinner.set_message("📦 Reading lockfiles...");
+    let mut dependencies = lockfiles::analyze(source, &find_lockfiles(source), &manifests);
+    
+    spinner.set_message("⚖️  Resolving licenses...");
+    let analyzed: Vec<PathBuf> = project_info.file_stats.iter().map(|f| PathBuf::from(&f.path)).collect();
+    project_info.license = licenses::detect_project(source, &analyzed, &manifests);
+    licenses::resolve(source, &mut dependencies);
+    let vulnerabilities = advisory_db.map(|db| db.check(&dependencies));
+    
+    spinner.set_message("🏅 Ranking files by importance...");
+    let code_lines: HashMap<String, usize> = project_info.file_stats.iter().map(|f| (f.path.clone(), f.code)).collect();
+    let ranks = ranking::rank(source, &architecture.dependency_graph, &parsed_files, &code_lines);
     
     spinner.set_message("🎯 Finding entry points...");
-    let entry_points = find_entry_points()?;
+    let entry_points = find_entry_points(source, &files, &manifests, &ranks)?;
+    let key_files = files_to_read_first(&ranks, &entry_points);
     
     spinner.set_message("📊 Calculating complexity metrics...");
-    let complexity_metrics = calculate_complexity_metrics()?;
+    let complexity_metrics = calculate_complexity_metrics(&project_info, &parsed_files)?;
     
     spinner.set_message("✨ Assessing code quality...");
-    let quality_metrics = assess_quality_metrics()?;
+    let quality_metrics = assess_quality_metrics(&project_info, vulnerabilities.as_deref())?;
     
     spinner.set_message("📚 Generating onboarding guide...");
-    let onboarding_guide = generate_onboarding_guide(&entry_points, &architecture)?;
+    let commands = quickstart::collect(source, &files, &manifests);
+    let onboarding_guide = generate_onboarding_guide(
+        &entry_points,
+        &architecture,
+        &tech_stack.pipelines,
+        &commands,
+        &parsed_files,
+        &ranks,
+    )?;
     
     spinner.finish_with_message("✅ Analysis complete!");
     
-    Ok(ProjectAnalysis {
+    Ok((ProjectAnalysis {
         project_info,
         architecture,
         tech_stack,
         entry_points,
+        key_files,
         complexity_metrics,
         quality_metrics,
         onboarding_guide,
+        manifests,
+        dependencies,
+        vulnerabilities,
         analysis_timestamp: chrono::Utc::now().to_rfc3339(),
-    })
+    }, parsed_files))
+}
+
+// Writes the analysis and the symbol index under .codemap/
+fn save_analysis(analysis: &ProjectAnalysis, parsed_files: &[ParsedFile]) -> Result<String> {
+    fs::create_dir_all(".codemap")?;
+    
+    let analysis_json = serde_json::to_string_pretty(analysis)?;
+    fs::write(".codemap/analysis.json", &analysis_json)?;
+    SymbolIndex::build(parsed_files).save()?;
+    
+    Ok(analysis_json)
 }
 
-fn analyze_project_info() -> Result<ProjectInfo> {
+// Files over `max_file_size` bytes still count as files, but aren't line-counted or parsed
+fn analyze_project_info(
+    source: &dyn FileSource,
+    classifier: &Classifier,
+    max_file_size: usize,
+) -> Result<(ProjectInfo, Vec<ParsedFile>)> {
     let mut language_distribution = HashMap::new();
     let mut total_files = 0;
     let mut total_lines = 0;
     let mut total_functions = 0;
-    
-    for entry in WalkDir::new(".")
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-    {
-        if should_analyze_file(entry.path()) {
+    let mut stats_by_language: HashMap<&str, LanguageStats> = HashMap::new();
+    let mut excluded_files = Vec::new();
+    let mut file_stats = Vec::new();
+    let mut parsed_files = Vec::new();
+    
+    for path in source.files() {
+        if should_analyze_file(&path) {
+            let content = source.read_to_string(&path);
+            
+            if let Some(exclusion) = classifier.classify(&path, content.as_deref().unwrap_or_default()) {
+                excluded_files.push(ExcludedFile {
+                    path: path.to_string_lossy().to_string(),
+                    kind: exclusion.kind,
+                    reason: exclusion.reason,
+                });
+                continue;
+            }
+            
             total_files += 1;
             
-            if let Ok(content) = fs::read_to_string(entry.path()) {
+            if let Some(content) = content.filter(|c| c.len() <= max_file_size) {
                 let lines = content.lines().count();
                 total_lines += lines;
                 
-                if let Some(ext) = entry.path().extension() {
-                    let lang = ext.to_string_lossy().to_string();
-                    *language_distribution.entry(lang).or_insert(0) += 1;
+                if let Some(lang) = languages::detect(&path, &content) {
+                    *language_distribution.entry(lang.name.to_string()).or_insert(0) += 1;
+                    
+                    let counts = loc::count_lines(&content, lang.name);
+                    let stats = stats_by_language.entry(lang.name).or_insert_with(|| LanguageStats {
+                        language: lang.name.to_string(),
+                        kind: lang.kind,
+                        files: 0,
+                        lines: 0,
+                        code: 0,
+                        comment: 0,
+                        blank: 0,
+                    });
+                    stats.files += 1;
+                    stats.lines += lines;
+                    stats.code += counts.code;
+                    stats.comment += counts.comment;
+                    stats.blank += counts.blank;
+                    
+                    let parsed = parser::parse(&path, &content, lang.name);
+                    let functions = parsed.as_ref().map_or(0, |p| p.callables().count());
+                    let complexity = parsed.as_ref().map_or(0, |p| {
+                        p.callables().filter_map(|s| s.complexity).map(|c| c.cyclomatic).sum()
+                    });
+                    total_functions += functions;
+                    
+                    file_stats.push(FileStats {
+                        path: path.to_string_lossy().to_string(),
+                        language: lang.name.to_string(),
+                        code: counts.code,
+                        comment: counts.comment,
+                        blank: counts.blank,
+                        functions,
+                        complexity,
+                    });
+                    parsed_files.extend(parsed);
                 }
-                
-                total_functions += count_functions(&content, entry.path());
             }
         }
     }
     
-    let project_name = std::env::current_dir()?
-        .file_name()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
+    let mut language_stats: Vec<LanguageStats> = stats_by_language.into_values().collect();
+    language_stats.sort_by(|a, b| b.code.cmp(&a.code).then_with(|| a.language.cmp(&b.language)));
+    
+    let project_name = source.name();
     
     let project_size = format!("{} files, {} lines", total_files, total_lines);
     
-    Ok(ProjectInfo {
+    Ok((ProjectInfo {
         name: project_name,
         description: None,
         language_distribution,
@@ -339,94 +781,97 @@ fn analyze_project_info() -> Result<ProjectInfo> {
         total_lines,
         total_functions,
         project_size,
-    })
+        language_stats,
+        excluded_files,
+        file_stats,
+        license: ProjectLicense::default(),
+        version: None,
+        homepage: None,
+        repository: None,
+        authors: Vec::new(),
+        badges: Vec::new(),
+    }, parsed_files))
 }
 
-fn detect_architecture() -> Result<ArchitectureAnalysis> {
-    // Advanced architecture detection logic
-    let mut patterns = Vec::new();
-    let mut confidence: f32 = 0.0;
-    
-    // Check for common patterns
-    if has_pattern("src/", "main.rs") {
-        patterns.push("Rust Binary".to_string());
-        confidence += 0.3;
-    }
-    
-    if has_pattern("src/", "lib.rs") {
-        patterns.push("Rust Library".to_string());
-        confidence += 0.3;
-    }
-    
-    if has_pattern("package.json", "") {
-        patterns.push("Node.js Application".to_string());
-        confidence += 0.4;
-    }
-    
-    if has_pattern("Cargo.toml", "") {
-        patterns.push("Rust Cargo Project".to_string());
-        confidence += 0.4;
-    }
-    
-    if has_pattern("requirements.txt", "") || has_pattern("pyproject.toml", "") {
-        patterns.push("Python Application".to_string());
-        confidence += 0.4;
-    }
-    
-    let pattern = if !patterns.is_empty() {
-        patterns.join(" + ")
-    } else {
-        "Unknown Architecture".to_string()
-    };
+fn detect_architecture(
+    source: &dyn FileSource,
+    classifier: &Classifier,
+    dependency_graph: DependencyGraph,
+) -> Result<ArchitectureAnalysis> {
+    let layering = Layering::infer(&dependency_graph);
+    let patterns = patterns::classify(source, &project_files(source, classifier), &dependency_graph, &layering);
+    let (pattern, confidence) = patterns::summarize(&patterns);
     
     Ok(ArchitectureAnalysis {
         pattern,
-        confidence: confidence.min(1.0),
-        layers: vec!["Presentation".to_string(), "Business Logic".to_string(), "Data".to_string()],
-        key_components: vec!["Entry Points".to_string(), "Core Modules".to_string()],
-        data_flow: "Request → Handler → Service → Repository".to_string(),
+        confidence,
+        patterns,
+        layers: layering.names(),
+        key_components: key_components(&dependency_graph),
+        data_flow: layering.data_flow(),
+        layering,
+        cycles: cycles::find_cycles(&dependency_graph),
+        dependency_graph,
     })
 }
 
-fn identify_tech_stack() -> Result<TechStack> {
-    let mut languages = Vec::new();
-    let mut frameworks = Vec::new();
-    let mut databases = Vec::new();
-    let mut tools = Vec::new();
-    let mut deployment = Vec::new();
-    
-    // Detect languages and frameworks
-    if Path::new("Cargo.toml").exists() {
-        languages.push("Rust".to_string());
-        frameworks.push("Cargo".to_string());
-    }
-    
-    if Path::new("package.json").exists() {
-        languages.push("JavaScript/TypeScript".to_string());
-        frameworks.push("Node.js".to_string());
-    }
-    
-    if Path::new("requirements.txt").exists() || Path::new("pyproject.toml").exists() {
-        languages.push("Python".to_string());
-    }
-    
-    // Detect databases
-    if has_pattern("*.sql", "") {
-        databases.push("SQL Database".to_string());
-    }
-    
-    if has_pattern("*.json", "") {
-        databases.push("JSON Storage".to_string());
-    }
+// The most depended-upon files: ranked by PageRank centrality, ties broken by fan-in
+fn key_components(dependency_graph: &DependencyGraph) -> Vec<String> {
+    let metrics = dependency_graph.metrics();
+    let mut ranked: Vec<(&String, &graph::NodeMetrics)> = metrics
+        .iter()
+        .filter(|(_, m)| m.fan_in > 0)
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.1.centrality
+            .total_cmp(&a.1.centrality)
+            .then(b.1.fan_in.cmp(&a.1.fan_in))
+            .then(a.0.cmp(b.0))
+    });
+    
+    ranked
+        .into_iter()
+        .take(8)
+        .map(|(path, m)| format!(
+            "{} (imported by {} file{}, imports {}, centrality {:.3})",
+            path,
+            m.fan_in,
+            if m.fan_in == 1 { "" } else { "s" },
+            m.fan_out,
+            m.centrality
+        ))
+        .collect()
+}
+
+fn identify_tech_stack(
+    source: &dyn FileSource,
+    project_info: &ProjectInfo,
+    files: &[PathBuf],
+    manifests: &[Manifest],
+) -> Result<TechStack> {
+    let languages: Vec<String> = project_info.language_stats
+        .iter()
+        .filter(|s| s.kind == LanguageKind::Programming)
+        .map(|s| s.language.clone())
+        .collect();
+    
+    let technologies = stack::detect(manifests);
+    
+    let names = |category: Category| {
+        distinct(technologies.iter().filter(|t| t.category == category).map(|t| t.name.clone()))
+    };
+    let frameworks = names(Category::Framework);
+    let databases = names(Category::Database);
+    let mut tools = names(Category::Tool);
+    let infrastructure = deploy::detect(source, files);
+    let deployment = distinct(infrastructure.iter().map(|d| d.kind.clone()));
+    let pipelines = ci::detect(source, &find_ci_configs(source));
     
     // Detect tools
-    if Path::new(".git").exists() {
+    if source.exists(Path::new(".git")) {
         tools.push("Git".to_string());
     }
-    
-    if Path::new("Dockerfile").exists() || Path::new("docker-compose.yml").exists() {
-        deployment.push("Docker".to_string());
-    }
+    tools.extend(distinct(pipelines.iter().map(|p| p.provider.clone())));
     
     Ok(TechStack {
         languages,
@@ -434,71 +879,184 @@ fn identify_tech_stack() -> Result<TechStack> {
         databases,
         tools,
         deployment,
+        technologies,
+        infrastructure,
+        pipelines,
     })
 }
 
-fn find_entry_points() -> Result<Vec<EntryPoint>> {
-    let mut entry_points = Vec::new();
-    
-    // Common entry point patterns
-    let patterns = vec![
-        ("src/main.rs", "Primary application entry point", 10),
-        ("src/lib.rs", "Library root and public API", 9),
-        ("main.py", "Python application entry", 9),
-        ("index.js", "Node.js application entry", 9),
-        ("app.py", "Flask/Django application", 8),
-        ("server.js", "Express.js server", 8),
-    ];
-    
-    for (pattern, reason, rank) in patterns {
-        if Path::new(pattern).exists() {
-            entry_points.push(EntryPoint {
-                path: pattern.to_string(),
-                rank,
-                reason: reason.to_string(),
-                complexity: "Low".to_string(),
-                importance: "Critical".to_string(),
-            });
-        }
-    }
+// Discovery says how an entry point is reached; the file's rank says how much of the system hangs off it
+fn find_entry_points(
+    source: &dyn FileSource,
+    files: &[PathBuf],
+    manifests: &[Manifest],
+    ranks: &[FileRank],
+) -> Result<Vec<EntryPoint>> {
+    let mut entry_points: Vec<EntryPoint> = entrypoints::discover(source, files, manifests)
+        .into_iter()
+        .map(|found| {
+            let rank = ranks.iter().find(|r| r.path == found.path);
+            let score = rank.map_or(0.0, |r| r.score);
+            let importance = match (rank, found.rank) {
+                (Some(rank), _) => rank.importance.clone(),
+                (None, 9..) => "Critical".to_string(),
+                (None, 7..=8) => "High".to_string(),
+                (None, 5..=6) => "Medium".to_string(),
+                (None, _) => "Low".to_string(),
+            };
+            EntryPoint {
+                path: found.path,
+                rank: (found.rank as f64 * 0.6 + score * 4.0).round().clamp(1.0, 10.0) as u8,
+                reason: found.reason,
+                complexity: rank.map_or_else(|| "Low".to_string(), |r| r.complexity.clone()),
+                importance,
+                kind: Some(found.kind),
+            }
+        })
+        .collect();
     
-    // Sort by rank
     entry_points.sort_by_key(|ep| std::cmp::Reverse(ep.rank));
-    
     Ok(entry_points)
 }
 
-fn calculate_complexity_metrics() -> Result<ComplexityMetrics> {
-    // Simplified complexity calculation
+// How many ranked files the guide points at besides the entry points
+const KEY_FILES: usize = 10;
+
+fn files_to_read_first(ranks: &[FileRank], entry_points: &[EntryPoint]) -> Vec<FileRank> {
+    ranks
+        .iter()
+        .filter(|r| r.code > 0 && !entry_points.iter().any(|ep| ep.path == r.path))
+        .take(KEY_FILES)
+        .cloned()
+        .collect()
+}
+
+// Functions above this cyclomatic complexity count towards technical debt
+const COMPLEX_FUNCTION_THRESHOLD: u32 = 10;
+
+fn calculate_complexity_metrics(project_info: &ProjectInfo, parsed_files: &[ParsedFile]) -> Result<ComplexityMetrics> {
+    let functions: Vec<(&ParsedFile, &parser::Symbol, parser::Complexity)> = parsed_files
+        .iter()
+        .flat_map(|file| file.callables().filter_map(move |s| s.complexity.map(|c| (file, s, c))))
+        .collect();
+    
+    let count = functions.len().max(1) as f32;
+    let cyclomatic_complexity = functions.iter().map(|(_, _, c)| c.cyclomatic as f32).sum::<f32>() / count;
+    let cognitive_complexity = functions.iter().map(|(_, _, c)| c.cognitive as f32).sum::<f32>() / count;
+    
+    // Share of function lines that sit in overly complex functions
+    let span = |s: &parser::Symbol| (s.end_line - s.start_line + 1) as f32;
+    let total_span: f32 = functions.iter().map(|(_, s, _)| span(s)).sum();
+    let complex_span: f32 = functions
+        .iter()
+        .filter(|(_, _, c)| c.cyclomatic > COMPLEX_FUNCTION_THRESHOLD)
+        .map(|(_, s, _)| span(s))
+        .sum();
+    let technical_debt_ratio = if total_span > 0.0 { complex_span / total_span } else { 0.0 };
+    
+    let mut ranked = functions.clone();
+    ranked.sort_by(|a, b| b.2.cognitive.cmp(&a.2.cognitive).then(b.2.cyclomatic.cmp(&a.2.cyclomatic)));
+    let hotspots = ranked
+        .iter()
+        .take(5)
+        .filter(|(_, _, c)| c.cyclomatic > 1)
+        .map(|(file, s, c)| format!(
+            "{}:{} {} (cyclomatic {}, cognitive {})",
+            file.path.display(),
+            s.start_line,
+            s.name,
+            c.cyclomatic,
+            c.cognitive
+        ))
+        .collect();
+    
     Ok(ComplexityMetrics {
-        cyclomatic_complexity: 2.5,
-        cognitive_complexity: 3.2,
-        maintainability_index: 85.0,
-        technical_debt_ratio: 0.15,
-        hotspots: vec!["src/main.rs".to_string(), "src/lib.rs".to_string()],
+        cyclomatic_complexity: if functions.is_empty() { 0.0 } else { cyclomatic_complexity },
+        cognitive_complexity: if functions.is_empty() { 0.0 } else { cognitive_complexity },
+        maintainability_index: maintainability_index(project_info),
+        technical_debt_ratio,
+        hotspots,
+    })
+}
+
+// Average per-file maintainability index on a 0-100 scale, using the SEI formula's
+// complexity, LOC and comment terms (171 - 0.23·CC - 16.2·ln(LOC) + 50·sin(√(2.4·CM)), normalized by 171)
+fn maintainability_index(project_info: &ProjectInfo) -> f32 {
+    let scores: Vec<f32> = programming_files(project_info)
+        .filter(|f| f.code > 0)
+        .map(|f| {
+            let comment_ratio = f.comment as f32 / (f.code + f.comment) as f32;
+            let raw = 171.0 - 0.23 * f.complexity as f32 - 16.2 * (f.code as f32).ln()
+                + 50.0 * (2.4 * comment_ratio).sqrt().sin();
+            (raw * 100.0 / 171.0).clamp(0.0, 100.0)
+        })
+        .collect();
+    
+    if scores.is_empty() {
+        return 100.0;
+    }
+    scores.iter().sum::<f32>() / scores.len() as f32
+}
+
+fn programming_files(project_info: &ProjectInfo) -> impl Iterator<Item = &FileStats> {
+    project_info.file_stats.iter().filter(|f| {
+        languages::by_name(&f.language).is_some_and(|lang| lang.kind == LanguageKind::Programming)
     })
 }
 
-fn assess_quality_metrics() -> Result<QualityMetrics> {
+fn assess_quality_metrics(project_info: &ProjectInfo, vulnerabilities: Option<&[Vulnerability]>) -> Result<QualityMetrics> {
+    let (code, comment) = programming_files(project_info)
+        .fold((0, 0), |(code, comment), f| (code + f.code, comment + f.comment));
+    let documentation_ratio = if code + comment > 0 {
+        comment as f32 / (code + comment) as f32
+    } else {
+        0.0
+    };
+    // Each known advisory costs points by severity; without a database there is nothing to go on
+    let security_score = vulnerabilities.map_or(90.0, |found| {
+        (100.0 - found.iter().map(Vulnerability::penalty).sum::<f32>()).max(0.0)
+    });
+    
     Ok(QualityMetrics {
         code_coverage: Some(75.0),
         test_ratio: 0.3,
-        documentation_ratio: 0.4,
+        documentation_ratio,
         lint_score: 85.0,
-        security_score: 90.0,
+        security_score,
     })
 }
 
 fn generate_onboarding_guide(
-    _entry_points: &[EntryPoint],
+    entry_points: &[EntryPoint],
     architecture: &ArchitectureAnalysis,
+    pipelines: &[Pipeline],
+    commands: &[quickstart::Command],
+    parsed_files: &[ParsedFile],
+    ranks: &[FileRank],
 ) -> Result<OnboardingGuide> {
-    let quick_start = vec![
-        "1. Start with the main entry point".to_string(),
-        "2. Understand the project structure".to_string(),
-        "3. Review key configuration files".to_string(),
-        "4. Run the test suite".to_string(),
-    ];
+    // Each command is copy-pasteable with its source as a shell comment
+    let quick_start = if commands.is_empty() {
+        vec![
+            "1. Start with the main entry point".to_string(),
+            "2. Understand the project structure".to_string(),
+            "3. Review key configuration files".to_string(),
+            "4. Run the test suite".to_string(),
+        ]
+    } else {
+        commands
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let stage = match c.stage {
+                    Stage::Install => "install",
+                    Stage::Build => "build",
+                    Stage::Test => "test",
+                    Stage::Run => "run",
+                };
+                format!("{}. {}  # {}: {}", i + 1, c.command, stage, c.source)
+            })
+            .collect()
+    };
     
     let key_concepts = vec![
         format!("Architecture: {}", architecture.pattern),
@@ -524,25 +1082,134 @@ fn generate_onboarding_guide(
         "Update documentation".to_string(),
     ];
     
+    // Examples and build scripts say little about how the product itself starts
+    let starting_points: Vec<(&str, &str)> = entry_points
+        .iter()
+        .filter(|ep| !matches!(ep.kind, Some(entrypoints::Kind::Example | entrypoints::Kind::Build)))
+        .map(|ep| (ep.path.as_str(), ep.reason.as_str()))
+        .collect();
+    
     Ok(OnboardingGuide {
         quick_start,
         key_concepts,
         common_patterns,
         debugging_tips,
         next_steps,
+        ci_checks: pipelines.iter().map(describe_ci_checks).collect(),
+        reading_path: reading::path(&starting_points, &architecture.dependency_graph, parsed_files, ranks),
     })
 }
 
+// "GitHub Actions (.github/workflows/ci.yml) on push (main), pull_request: tests with `cargo test`; ..."
+fn describe_ci_checks(pipeline: &Pipeline) -> String {
+    let quoted = |commands: Vec<&str>| {
+        let mut quoted: Vec<String> = commands.iter().take(4).map(|c| format!("`{}`", c)).collect();
+        if commands.len() > 4 {
+            quoted.push(format!("{} more", commands.len() - 4));
+        }
+        quoted.join(", ")
+    };
+    
+    let mut checks = Vec::new();
+    for (verb, purpose) in [("lints with", Purpose::Lint), ("tests with", Purpose::Test), ("builds with", Purpose::Build)] {
+        let commands = pipeline.commands(purpose);
+        if !commands.is_empty() {
+            checks.push(format!("{} {}", verb, quoted(commands)));
+        }
+    }
+    let artifacts = pipeline.artifacts();
+    if !artifacts.is_empty() {
+        checks.push(format!("publishes {}", artifacts.join(", ")));
+    }
+    if checks.is_empty() {
+        checks.push(format!("runs {} job{}", pipeline.jobs.len(), if pipeline.jobs.len() == 1 { "" } else { "s" }));
+    }
+    
+    let triggers = if pipeline.triggers.is_empty() {
+        String::new()
+    } else {
+        format!(" on {}", pipeline.triggers.join(", "))
+    };
+    format!("{} ({}){}: {}", pipeline.provider, pipeline.path, triggers, checks.join("; "))
+}
+
 // ----- Utility Functions -----
 
+fn load_config() -> Result<Config> {
+    let config_path = Path::new(".codemap/config.toml");
+    let config_text = if config_path.exists() {
+        fs::read_to_string(config_path)?
+    } else {
+        CONFIG_TEXT.to_string()
+    };
+    
+    toml::from_str(&config_text).map_err(|e| anyhow!("Invalid .codemap/config.toml: {}", e))
+}
+
+// Files of the project itself, leaving out vendored and generated copies
+fn project_files(source: &dyn FileSource, classifier: &Classifier) -> Vec<PathBuf> {
+    source
+        .files()
+        .into_iter()
+        .filter(|p| should_analyze_file(p) && classifier.classify(p, "").is_none())
+        .collect()
+}
+
+fn find_manifests(source: &dyn FileSource, classifier: &Classifier) -> Vec<Manifest> {
+    manifests::parse_all(source, &project_files(source, classifier))
+}
+
+fn is_current_dir(path: &Path) -> bool {
+    match (fs::canonicalize(path), std::env::current_dir().and_then(fs::canonicalize)) {
+        (Ok(path), Ok(cwd)) => path == cwd,
+        _ => false,
+    }
+}
+
+// Each item once, in first-seen order
+fn distinct<T: PartialEq>(items: impl IntoIterator<Item = T>) -> Vec<T> {
+    let mut seen = Vec::new();
+    for item in items {
+        if !seen.contains(&item) {
+            seen.push(item);
+        }
+    }
+    seen
+}
+
+// The --advisory-db flag wins over [audit] advisory_db
+fn open_advisory_db(flag: Option<PathBuf>, config: &Config) -> Result<Option<AdvisoryDb>> {
+    flag.or_else(|| config.audit.advisory_db.clone())
+        .map(|path| AdvisoryDb::open(&path))
+        .transpose()
+}
+
+fn find_lockfiles(source: &dyn FileSource) -> Vec<PathBuf> {
+    source
+        .files()
+        .into_iter()
+        .filter(|p| !in_ignored_dir(p))
+        .filter(|p| p.file_name().is_some_and(|n| lockfiles::LOCKFILES.contains(&n.to_string_lossy().as_ref())))
+        .collect()
+}
+
+// CI configs live in hidden files and directories, so this doesn't go through should_analyze_file
+fn find_ci_configs(source: &dyn FileSource) -> Vec<PathBuf> {
+    source.files().into_iter().filter(|p| !in_ignored_dir(p)).collect()
+}
+
+const IGNORED_DIRS: &[&str] = &["target", ".git", "node_modules", ".venv", "__pycache__", ".codemap"];
+
+fn in_ignored_dir(path: &Path) -> bool {
+    path.components().any(|c| IGNORED_DIRS.contains(&c.as_os_str().to_string_lossy().as_ref()))
+}
+
 fn should_analyze_file(path: &Path) -> bool {
-    let ignored_dirs = ["target", ".git", "node_modules", ".venv", "__pycache__", ".codemap"];
-    let ignored_extensions = ["lock", "log", "tmp", "cache"];
+    // Lockfiles get through so the classifier can list them as generated
+    let ignored_extensions = ["log", "tmp", "cache"];
     
     // Skip ignored directories
-    if path.components().any(|c| {
-        ignored_dirs.contains(&c.as_os_str().to_string_lossy().as_ref())
-    }) {
+    if in_ignored_dir(path) {
         return false;
     }
     
@@ -565,38 +1232,6 @@ fn should_analyze_file(path: &Path) -> bool {
     true
 }
 
-fn has_pattern(pattern: &str, filename: &str) -> bool {
-    WalkDir::new(".")
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .any(|e| {
-            e.path().to_string_lossy().contains(pattern) ||
-            e.file_name().to_string_lossy() == filename
-        })
-}
-
-fn count_functions(content: &str, path: &Path) -> usize {
-    if let Some(ext) = path.extension() {
-        match ext.to_string_lossy().as_ref() {
-            "rs" => {
-                let re = Regex::new(r"^\s*(pub\s+)?(async\s+)?fn\s+\w+").unwrap();
-                content.lines().filter(|line| re.is_match(line)).count()
-            }
-            "py" => {
-                let re = Regex::new(r"^\s*def\s+\w+").unwrap();
-                content.lines().filter(|line| re.is_match(line)).count()
-            }
-            "js" | "ts" => {
-                let re = Regex::new(r"^\s*(export\s+)?(async\s+)?function\s+\w+|^\s*\w+\s*[:=]\s*(async\s+)?\(.*\)\s*=>").unwrap();
-                content.lines().filter(|line| re.is_match(line)).count()
-            }
-            _ => 0,
-        }
-    } else {
-        0
-    }
-}
-
 // ----- Display Functions -----
 
 fn display_summary(analysis: &ProjectAnalysis) {
@@ -609,20 +1244,111 @@ fn display_summary(analysis: &ProjectAnalysis) {
     // Project Info
     println!("\n📋 {}", "PROJECT INFORMATION".bold());
     println!("   Name: {}", analysis.project_info.name.green());
+    if let Some(description) = &analysis.project_info.description {
+        println!("   Description: {}", description);
+    }
+    if let Some(version) = &analysis.project_info.version {
+        println!("   Version: {}", version.cyan());
+    }
+    if let Some(repository) = &analysis.project_info.repository {
+        println!("   Repository: {}", repository.cyan());
+    }
+    if let Some(homepage) = analysis.project_info.homepage.as_ref().filter(|h| analysis.project_info.repository.as_ref() != Some(*h)) {
+        println!("   Homepage: {}", homepage.cyan());
+    }
+    if !analysis.project_info.authors.is_empty() {
+        println!("   Authors: {}", analysis.project_info.authors.join(", "));
+    }
+    if !analysis.project_info.badges.is_empty() {
+        let labels: Vec<&str> = analysis.project_info.badges.iter().map(|b| b.label.as_str()).collect();
+        println!("   Badges: {}", labels.join(", ").dimmed());
+    }
     println!("   Size: {}", analysis.project_info.project_size.yellow());
+    if let Some(license) = &analysis.project_info.license.expression {
+        println!("   License: {}", license.cyan());
+    }
     println!("   Files: {} | Lines: {} | Functions: {}", 
         analysis.project_info.total_files,
         analysis.project_info.total_lines,
         analysis.project_info.total_functions
     );
     
-    // Architecture
-    println!("\n🏗️  {}", "ARCHITECTURE".bold());
+    let (code, comment, blank) = analysis.project_info.language_stats
+        .iter()
+        .fold((0, 0, 0), |(c, m, b), s| (c + s.code, m + s.comment, b + s.blank));
+    if code + comment + blank > 0 {
+        println!("   Code: {} | Comments: {} | Blank: {}", code, comment, blank);
+    }
+    
+    let programming: Vec<&LanguageStats> = analysis.project_info.language_stats
+        .iter()
+        .filter(|s| s.kind == LanguageKind::Programming)
+        .collect();
+    let programming_code: usize = programming.iter().map(|s| s.code).sum();
+    if programming_code > 0 {
+        let breakdown: Vec<String> = programming
+            .iter()
+            .take(5)
+            .map(|s| format!("{} {:.1}% ({} files)", s.language, s.code as f32 / programming_code as f32 * 100.0, s.files))
+            .collect();
+        println!("   Languages: {}", breakdown.join(", ").green());
+    }
+    
+    let excluded = &analysis.project_info.excluded_files;
+    if !excluded.is_empty() {
+        let generated = excluded.iter().filter(|f| f.kind == ExclusionKind::Generated).count();
+        println!("   Excluded: {} generated, {} vendored (not counted above)",
+            generated,
+            excluded.len() - generated
+        );
+    }
+    
+    // Architecture
+    println!("\n🏗️  {}", "ARCHITECTURE".bold());
     println!("   Pattern: {} (confidence: {:.1}%)", 
         analysis.architecture.pattern.green(),
         analysis.architecture.confidence * 100.0
     );
+    for pattern in analysis.architecture.patterns.iter().filter(|p| p.score >= patterns::MIN_SCORE).take(2) {
+        println!("   Evidence for {}: {}", pattern.name, pattern.evidence.join("; ").cyan());
+    }
+    let layering = &analysis.architecture.layering;
+    if !layering.layers.is_empty() {
+        let layers: Vec<String> = layering.layers
+            .iter()
+            .map(|l| format!("{} ({})", l.name, l.files.len()))
+            .collect();
+        println!("   Layers: {}", layers.join(", ").green());
+        if !layering.flows.is_empty() {
+            let violations: usize = layering.violations().map(|f| f.imports).sum();
+            println!("   Layering: {:.1}% of cross-layer imports point downward ({} against the grain)",
+                layering.confidence * 100.0,
+                violations
+            );
+        }
+    }
     println!("   Data Flow: {}", analysis.architecture.data_flow.cyan());
+    let graph = &analysis.architecture.dependency_graph;
+    if !graph.edges.is_empty() {
+        println!("   Internal Dependencies: {} imports between {} files, {} module links",
+            graph.edges.len(),
+            graph.files.len(),
+            graph.module_edges().len()
+        );
+    }
+    for component in analysis.architecture.key_components.iter().take(5) {
+        println!("   Key Component: {}", component.green());
+    }
+    let cycles = &analysis.architecture.cycles;
+    if !cycles.is_empty() {
+        let cross_package = cycles.iter().filter(|c| c.is_cross_package()).count();
+        let message = format!("{} dependency cycles ({} across packages), broken by cutting {} imports",
+            cycles.len(),
+            cross_package,
+            cycles.iter().map(|c| c.suggested_cuts.len()).sum::<usize>()
+        );
+        println!("   Cycles: {}", if cross_package > 0 { message.red() } else { message.yellow() });
+    }
     
     // Tech Stack
     println!("\n🛠️  {}", "TECH STACK".bold());
@@ -635,6 +1361,34 @@ fn display_summary(analysis: &ProjectAnalysis) {
     if !analysis.tech_stack.databases.is_empty() {
         println!("   Databases: {}", analysis.tech_stack.databases.join(", ").cyan());
     }
+    if !analysis.tech_stack.tools.is_empty() {
+        println!("   Tools: {}", analysis.tech_stack.tools.join(", "));
+    }
+    if !analysis.tech_stack.deployment.is_empty() {
+        println!("   Deployment: {}", analysis.tech_stack.deployment.join(", ").magenta());
+    }
+    if !analysis.tech_stack.pipelines.is_empty() {
+        let jobs: usize = analysis.tech_stack.pipelines.iter().map(|p| p.jobs.len()).sum();
+        println!("   CI: {} ({} pipelines, {} jobs)",
+            distinct(analysis.tech_stack.pipelines.iter().map(|p| p.provider.as_str())).join(", ").magenta(),
+            analysis.tech_stack.pipelines.len(),
+            jobs
+        );
+    }
+    for report in &analysis.dependencies {
+        println!("   Dependencies: {} packages in {} ({} direct, {} transitive), {} duplicated, depth {}",
+            report.total,
+            report.lockfile.cyan(),
+            report.direct,
+            report.transitive,
+            report.duplicates.len(),
+            report.max_depth
+        );
+    }
+    if let Some(vulnerabilities) = &analysis.vulnerabilities {
+        let line = format!("{} known vulnerabilities in locked dependencies", vulnerabilities.len());
+        println!("   Advisories: {}", if vulnerabilities.is_empty() { line.green() } else { line.red() });
+    }
     
     // Entry Points
     println!("\n🎯 {}", "KEY ENTRY POINTS".bold());
@@ -646,6 +1400,14 @@ fn display_summary(analysis: &ProjectAnalysis) {
         );
     }
     
+    // Files to read first
+    if !analysis.key_files.is_empty() {
+        println!("\n📖 {}", "FILES TO READ FIRST".bold());
+        for (i, file) in analysis.key_files.iter().take(5).enumerate() {
+            println!("   {}. {} - {}", i + 1, file.path.green(), file.reason.cyan());
+        }
+    }
+    
     // Quality Metrics
     println!("\n📊 {}", "QUALITY METRICS".bold());
     println!("   Maintainability: {:.1}%", analysis.complexity_metrics.maintainability_index);
@@ -654,10 +1416,384 @@ fn display_summary(analysis: &ProjectAnalysis) {
         println!("   Test Coverage: {:.1}%", coverage);
     }
     println!("   Documentation: {:.1}%", analysis.quality_metrics.documentation_ratio * 100.0);
+    if analysis.vulnerabilities.is_some() {
+        println!("   Security: {:.1}%", analysis.quality_metrics.security_score);
+    }
+    println!("   Complexity: {:.1} cyclomatic, {:.1} cognitive (avg per function)",
+        analysis.complexity_metrics.cyclomatic_complexity,
+        analysis.complexity_metrics.cognitive_complexity
+    );
+    for hotspot in analysis.complexity_metrics.hotspots.iter().take(3) {
+        println!("   Hotspot: {}", hotspot.yellow());
+    }
     
     println!("\n{}", "=".repeat(80).blue());
 }
 
+fn display_language_breakdown(stats: &[LanguageStats]) {
+    if stats.is_empty() {
+        return;
+    }
+    
+    println!("\n🗂️  {}", "LANGUAGES".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    println!("   {:<20} {:<12} {:>6} {:>8} {:>8} {:>8}", "Language", "Kind", "Files", "Code", "Comment", "Blank");
+    for s in stats {
+        println!("   {:<20} {:<12} {:>6} {:>8} {:>8} {:>8}",
+            s.language,
+            format!("{:?}", s.kind),
+            s.files,
+            s.code,
+            s.comment,
+            s.blank
+        );
+    }
+}
+
+fn display_technologies(technologies: &[Technology]) {
+    if technologies.is_empty() {
+        return;
+    }
+    
+    println!("\n📦 {}", "DETECTED TECHNOLOGIES".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    println!("   {:<24} {:<10} {:<14} Declared in", "Technology", "Category", "Version");
+    for t in technologies {
+        println!("   {:<24} {:<10} {:<14} {} ({})",
+            t.name,
+            format!("{:?}", t.category),
+            t.version.as_deref().unwrap_or("-"),
+            t.manifest.cyan(),
+            t.package
+        );
+    }
+}
+
+fn display_infrastructure(deployments: &[Deployment]) {
+    if deployments.is_empty() {
+        return;
+    }
+    
+    println!("\n🚢 {}", "DEPLOYMENT".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for deployment in deployments {
+        println!("   {} {}", deployment.kind.green().bold(), deployment.path.dimmed());
+        for detail in &deployment.details {
+            println!("      {}", detail);
+        }
+        for service in &deployment.services {
+            println!("      {} {}", "▸".cyan(), service.name.bold());
+            if let Some(image) = &service.image {
+                println!("         image: {}", image);
+            }
+            if !service.ports.is_empty() {
+                println!("         ports: {}", service.ports.join(", "));
+            }
+            if !service.env.is_empty() {
+                println!("         env: {}", service.env.join(", "));
+            }
+            if let Some(command) = &service.command {
+                println!("         runs: {}", command);
+            }
+        }
+    }
+}
+
+fn display_entry_points(entry_points: &[EntryPoint]) {
+    if entry_points.len() <= 5 {
+        return;
+    }
+    
+    println!("\n🎯 {}", "ALL ENTRY POINTS".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for ep in entry_points {
+        let kind = ep.kind.map(|k| format!("{:?}", k)).unwrap_or_default();
+        println!("   {:>2} {:<10} {} - {}", ep.rank, kind.dimmed(), ep.path.green(), ep.reason.cyan());
+    }
+}
+
+fn display_key_files(files: &[FileRank]) {
+    if files.is_empty() {
+        return;
+    }
+    
+    println!("\n📖 {}", "FILES TO READ FIRST".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    println!("   {:<40} {:>6} {:>6} {:>6} {:>6} {:>6} {:>6}", "File", "Score", "In", "Churn", "Code", "API", "Tests");
+    for file in files {
+        println!("   {:<40} {:>6.2} {:>6} {:>6} {:>6} {:>6} {:>6}",
+            file.path,
+            file.score,
+            file.fan_in,
+            file.churn,
+            file.code,
+            file.public_api,
+            file.tests
+        );
+        println!("      {} importance, {} complexity", file.importance, file.complexity);
+    }
+}
+
+fn display_reading_path(steps: &[Step]) {
+    if steps.is_empty() {
+        return;
+    }
+    
+    let total: u32 = steps.iter().map(|s| s.minutes).sum();
+    println!("\n🧭 {}", "READING PATH".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    println!("   About {} minutes for {} files", total, steps.len());
+    let mut stage = None;
+    for (i, step) in steps.iter().enumerate() {
+        if stage != Some(step.stage) {
+            stage = Some(step.stage);
+            let title = match step.stage {
+                reading::Stage::EntryPoint => "Start where execution begins",
+                reading::Stage::DomainTypes => "Then the core domain types",
+                reading::Stage::Core => "Then the key files, dependencies first",
+            };
+            println!("\n   {}", title.bold());
+        }
+        println!("   {:>2}. {} {}", i + 1, step.path.green(), format!("(~{} min, {} complexity)", step.minutes, step.complexity).dimmed());
+        println!("       {}", step.reason.cyan());
+    }
+}
+
+fn display_pipelines(pipelines: &[Pipeline]) {
+    if pipelines.is_empty() {
+        return;
+    }
+    
+    println!("\n🔁 {}", "CI/CD PIPELINES".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for pipeline in pipelines {
+        println!("   {} {}", pipeline.provider.green().bold(), pipeline.path.dimmed());
+        if !pipeline.triggers.is_empty() {
+            println!("      triggers: {}", pipeline.triggers.join(", "));
+        }
+        for job in &pipeline.jobs {
+            match &job.runs_on {
+                Some(runs_on) => println!("      {} {} {}", "▸".cyan(), job.name.bold(), format!("({})", runs_on).dimmed()),
+                None => println!("      {} {}", "▸".cyan(), job.name.bold()),
+            }
+            for (label, purpose) in [("build", Purpose::Build), ("test", Purpose::Test), ("lint", Purpose::Lint), ("publish", Purpose::Publish)] {
+                for step in job.steps.iter().filter(|s| s.purpose == purpose) {
+                    println!("         {}: {}", label, step.command);
+                }
+            }
+            if !job.artifacts.is_empty() {
+                println!("         artifacts: {}", job.artifacts.join(", ").yellow());
+            }
+        }
+    }
+}
+
+fn display_dependencies(reports: &[DependencyReport]) {
+    if reports.is_empty() {
+        return;
+    }
+    
+    println!("\n📦 {}", "DEPENDENCIES".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for report in reports {
+        println!("   {} ({:?})", report.lockfile.green().bold(), report.ecosystem);
+        println!("      {} packages: {} direct, {} transitive", report.total, report.direct, report.transitive);
+        if !report.duplicates.is_empty() {
+            println!("      {} packages locked at several versions:", report.duplicates.len().to_string().yellow());
+            for duplicate in report.duplicates.iter().take(15) {
+                println!("         {} {}", duplicate.name, duplicate.versions.join(", ").dimmed());
+            }
+            if report.duplicates.len() > 15 {
+                println!("         ... and {} more", report.duplicates.len() - 15);
+            }
+        }
+        if !report.deepest_chains.is_empty() {
+            println!("      Deepest chains (depth {}):", report.max_depth);
+            for chain in &report.deepest_chains {
+                println!("         {}", chain.join(" → "));
+            }
+        }
+    }
+}
+
+fn display_project_license(license: &ProjectLicense) {
+    println!("\n⚖️  {}", "PROJECT LICENSE".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    println!("   {}", license.expression.as_deref().unwrap_or("not found").green().bold());
+    for (file, id) in &license.files {
+        println!("   {} {}", file, id.as_deref().unwrap_or("unrecognised text").dimmed());
+    }
+    for (expression, files) in &license.spdx_headers {
+        println!("   SPDX-License-Identifier: {} in {} files", expression, files);
+    }
+}
+
+fn display_license_checks(checks: &[LicenseCheck]) {
+    if checks.is_empty() {
+        return;
+    }
+    
+    println!("\n📜 {}", "DEPENDENCY LICENSES".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    let width = checks.iter().map(|c| c.dependency.name.len() + c.dependency.version.len() + 1).max().unwrap_or(0).min(50);
+    for check in checks {
+        let dependency = &check.dependency;
+        let status = match check.status {
+            Status::Allowed => "ok".green(),
+            Status::Denied => "denied".red().bold(),
+            Status::NotAllowed => "not allowed".red(),
+            Status::Unknown if check.violation => "unknown".red(),
+            Status::Unknown => "unknown".yellow(),
+        };
+        println!("   {:<width$}  {:<28} {:<12} {}",
+            format!("{}@{}", dependency.name, dependency.version),
+            dependency.license.as_deref().unwrap_or("?"),
+            status,
+            dependency.source.as_deref().unwrap_or_default().dimmed(),
+            width = width
+        );
+    }
+    
+    let count = |status: Status| checks.iter().filter(|c| c.status == status).count();
+    println!("\nChecked {} dependencies: {} denied, {} not allowed, {} unknown",
+        checks.len(),
+        count(Status::Denied),
+        count(Status::NotAllowed),
+        count(Status::Unknown)
+    );
+}
+
+fn display_vulnerabilities(vulnerabilities: &[Vulnerability]) {
+    if vulnerabilities.is_empty() {
+        return;
+    }
+    
+    println!("\n🛡️  {}", "VULNERABILITIES".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for vulnerability in vulnerabilities {
+        let severity = vulnerability.severity.as_deref().unwrap_or("UNRATED");
+        println!("   {} {} {}@{} ({})",
+            vulnerability.id.red().bold(),
+            format!("[{}]", severity).yellow(),
+            vulnerability.package.bold(),
+            vulnerability.version,
+            vulnerability.lockfile.dimmed()
+        );
+        if !vulnerability.title.is_empty() {
+            println!("      {}", vulnerability.title);
+        }
+        println!("      Affected: {}", vulnerability.affected);
+        if !vulnerability.aliases.is_empty() {
+            println!("      Aliases: {}", vulnerability.aliases.join(", ").dimmed());
+        }
+    }
+}
+
+fn display_patterns(patterns: &[PatternScore]) {
+    if patterns.is_empty() {
+        return;
+    }
+    
+    println!("\n🧭 {}", "ARCHITECTURE PATTERNS".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for pattern in patterns {
+        let score = format!("{:.0}%", pattern.score * 100.0);
+        println!("   {} {}",
+            if pattern.score >= patterns::MIN_SCORE { score.green() } else { score.dimmed() },
+            pattern.name.bold()
+        );
+        for evidence in &pattern.evidence {
+            println!("      - {}", evidence);
+        }
+    }
+}
+
+fn display_layers(layering: &Layering) {
+    if layering.layers.is_empty() {
+        return;
+    }
+    
+    println!("\n🧱 {}", "LAYERS".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for layer in &layering.layers {
+        println!("   {} ({} files, {} placed by their importers)", layer.name.green().bold(), layer.files.len(), layer.inferred);
+        for file in layer.files.iter().take(10) {
+            println!("      {}", file);
+        }
+        if layer.files.len() > 10 {
+            println!("      ... and {} more", layer.files.len() - 10);
+        }
+    }
+    for flow in &layering.flows {
+        let line = format!("{} → {} ({} imports)", flow.from, flow.to, flow.imports);
+        if flow.violation {
+            println!("   {} {}", "Violation:".red(), line);
+        } else {
+            println!("   Flow: {}", line.cyan());
+        }
+    }
+}
+
+fn display_cycles(cycles: &[DependencyCycle]) {
+    if cycles.is_empty() {
+        return;
+    }
+    
+    println!("\n🔁 {}", "DEPENDENCY CYCLES".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for (i, cycle) in cycles.iter().enumerate() {
+        let severity = format!("{:?}", cycle.severity);
+        println!("   {}. [{}] {} files in {}",
+            i + 1,
+            if cycle.severity == Severity::High { severity.red() } else { severity.yellow() },
+            cycle.files.len(),
+            cycle.packages.join(", ").cyan()
+        );
+        for path in &cycle.cycles {
+            let mut chain: Vec<&str> = path.iter().map(|d| d.from.as_str()).collect();
+            chain.extend(path.first().map(|d| d.from.as_str()));
+            println!("      {}", chain.join(" → "));
+        }
+        if !cycle.suggested_cuts.is_empty() {
+            println!("      {}", "A small set of imports that breaks it (not necessarily the fewest):".dimmed());
+        }
+        for cut in &cycle.suggested_cuts {
+            println!("      Cut: {} → {} ({}:{} `{}`)", cut.from.green(), cut.to.green(), cut.from, cut.line, cut.import);
+        }
+    }
+}
+
+fn display_excluded_files(excluded: &[ExcludedFile]) {
+    if excluded.is_empty() {
+        return;
+    }
+    
+    println!("\n🚫 {}", "EXCLUDED FILES".bold().blue());
+    println!("{}", "=".repeat(50).blue());
+    for file in excluded {
+        println!("   {} [{:?}] - {}", file.path.yellow(), file.kind, file.reason.cyan());
+    }
+    println!("   Override with [classification] include in .codemap/config.toml or --include-generated");
+}
+
+// Everything `--detailed` adds after the summary
+fn display_details(analysis: &ProjectAnalysis) {
+    display_language_breakdown(&analysis.project_info.language_stats);
+    display_excluded_files(&analysis.project_info.excluded_files);
+    display_entry_points(&analysis.entry_points);
+    display_key_files(&analysis.key_files);
+    display_technologies(&analysis.tech_stack.technologies);
+    display_infrastructure(&analysis.tech_stack.infrastructure);
+    display_pipelines(&analysis.tech_stack.pipelines);
+    display_dependencies(&analysis.dependencies);
+    display_vulnerabilities(analysis.vulnerabilities.as_deref().unwrap_or_default());
+    display_patterns(&analysis.architecture.patterns);
+    display_layers(&analysis.architecture.layering);
+    display_cycles(&analysis.architecture.cycles);
+    display_onboarding_guide(&analysis.onboarding_guide);
+    display_reading_path(&analysis.onboarding_guide.reading_path);
+}
+
 fn display_onboarding_guide(guide: &OnboardingGuide) {
     println!("\n📚 {}", "ONBOARDING GUIDE".bold().blue());
     println!("{}", "=".repeat(50).blue());
@@ -686,11 +1822,18 @@ fn display_onboarding_guide(guide: &OnboardingGuide) {
     for step in &guide.next_steps {
         println!("   • {}", step);
     }
+    
+    if !guide.ci_checks.is_empty() {
+        println!("\n🔁 {}", "HOW CI TESTS YOUR CHANGE".bold());
+        for check in &guide.ci_checks {
+            println!("   • {}", check);
+        }
+    }
 }
 
 // ----- Command Handlers -----
 
-fn handle_init(_name: Option<String>) -> Result<()> {
+fn handle_init(name: Option<String>) -> Result<()> {
     let _term = Term::stdout();
     
     println!("{}", "🚀 Initializing CodeMap Analysis".bold().blue());
@@ -698,40 +1841,107 @@ fn handle_init(_name: Option<String>) -> Result<()> {
     // Create .codemap directory
     fs::create_dir_all(".codemap")?;
     
-    // Write config
-    fs::write(".codemap/config.toml", CONFIG_TEXT)?;
+    // Write the default config once; re-running init only sets the project name, keeping everything else
+    let config_path = Path::new(".codemap/config.toml");
+    let created = !config_path.exists();
+    let config_text = if created { CONFIG_TEXT.to_string() } else { fs::read_to_string(config_path)? };
+    match &name {
+        Some(name) => fs::write(config_path, with_project_name(&config_text, name)?)?,
+        None if created => fs::write(config_path, config_text)?,
+        None => {}
+    }
     
     // Perform initial analysis
-    let analysis = analyze_codebase()?;
+    let config = load_config()?;
+    let source = source::open_source(Path::new("."), None)?;
+    let classifier = Classifier::new(source.as_ref(), &config.classification.include, config.classification.exclude_generated);
+    let advisory_db = open_advisory_db(None, &config)?;
+    let (analysis, parsed_files) = analyze_codebase(
+        source.as_ref(),
+        &classifier,
+        config.general.max_file_size,
+        advisory_db.as_ref(),
+        config.general.project_name.as_deref(),
+    )?;
     
     // Save analysis
-    let analysis_json = serde_json::to_string_pretty(&analysis)?;
-    fs::write(".codemap/analysis.json", analysis_json)?;
+    save_analysis(&analysis, &parsed_files)?;
     
     println!("✅ {}", "Initialization complete!".green());
     println!("📁 Created .codemap/ directory");
-    println!("⚙️  Created configuration file");
+    if created {
+        println!("⚙️  Created configuration file");
+    } else if name.is_some() {
+        println!("⚙️  Updated project name in existing configuration file");
+    } else {
+        println!("⚙️  Kept existing configuration file");
+    }
     println!("📊 Generated initial analysis");
+    println!("🔎 Indexed symbols for 'codemap find'");
     
     display_summary(&analysis);
     
     Ok(())
 }
 
-fn handle_analyze(format: String, detailed: bool, _no_ai: bool) -> Result<()> {
+// Fills in the template's commented-out placeholder, or sets the key in an edited config
+fn with_project_name(config_text: &str, name: &str) -> Result<String> {
+    const PLACEHOLDER: &str = "# project_name = \"my-project\"";
+    if config_text.lines().any(|line| line == PLACEHOLDER) {
+        return Ok(config_text.replacen(PLACEHOLDER, &format!("project_name = {}", toml::Value::String(name.to_string())), 1));
+    }
+    let mut document: toml_edit::DocumentMut = config_text
+        .parse()
+        .map_err(|e| anyhow!("Invalid .codemap/config.toml: {}", e))?;
+    document["general"]["project_name"] = toml_edit::value(name);
+    Ok(document.to_string())
+}
+
+fn handle_analyze(
+    path: PathBuf,
+    rev: Option<String>,
+    format: String,
+    detailed: bool,
+    _no_ai: bool,
+    include_generated: bool,
+    advisory_db: Option<PathBuf>,
+) -> Result<()> {
     println!("{}", "🔍 Analyzing Codebase...".bold().blue());
     
-    let analysis = analyze_codebase()?;
-    
-    // Save analysis
-    let analysis_json = serde_json::to_string_pretty(&analysis)?;
-    fs::write(".codemap/analysis.json", &analysis_json)?;
+    let config = load_config()?;
+    let source = source::open_source(&path, rev.as_deref())?;
+    let classifier = Classifier::new(
+        source.as_ref(),
+        &config.classification.include,
+        config.classification.exclude_generated && !include_generated,
+    );
+    let advisory_db = open_advisory_db(advisory_db, &config)?;
+    // The configured name belongs to the project .codemap/ lives in
+    let project_name = config.general.project_name.as_deref().filter(|_| is_current_dir(&path));
+    let (analysis, parsed_files) = analyze_codebase(
+        source.as_ref(),
+        &classifier,
+        config.general.max_file_size,
+        advisory_db.as_ref(),
+        project_name,
+    )?;
+    
+    // .codemap/ describes the working tree it lives in; `find`, `tour` and `export` read it from here
+    let persist = is_current_dir(&path) && rev.is_none();
+    let analysis_json = if persist {
+        save_analysis(&analysis, &parsed_files)?
+    } else {
+        serde_json::to_string_pretty(&analysis)?
+    };
     
     match format.as_str() {
         "text" => {
+            if !persist {
+                println!("ℹ️  Not saved to .codemap/: only the current directory's working tree is saved");
+            }
             display_summary(&analysis);
             if detailed {
-                display_onboarding_guide(&analysis.onboarding_guide);
+                display_details(&analysis);
             }
         }
         "json" => {
@@ -749,7 +1959,7 @@ fn handle_analyze(format: String, detailed: bool, _no_ai: bool) -> Result<()> {
     Ok(())
 }
 
-fn handle_summary() -> Result<()> {
+fn handle_summary(detailed: bool) -> Result<()> {
     let analysis_path = Path::new(".codemap/analysis.json");
     if !analysis_path.exists() {
         return Err(anyhow!("No analysis found. Run 'codemap init' or 'codemap analyze' first."));
@@ -759,14 +1969,36 @@ fn handle_summary() -> Result<()> {
     let analysis: ProjectAnalysis = serde_json::from_str(&analysis_json)?;
     
     display_summary(&analysis);
+    if detailed {
+        display_details(&analysis);
+    }
     
     Ok(())
 }
 
 fn handle_tour() -> Result<()> {
-    println!("{}", "🎯 Interactive Codebase Tour".bold().blue());
-    println!("This feature will guide you through the codebase interactively.");
-    println!("Coming soon in the next version!");
+    let analysis_path = Path::new(".codemap/analysis.json");
+    if !analysis_path.exists() {
+        return Err(anyhow!("No analysis found. Run 'codemap init' or 'codemap analyze' first."));
+    }
+    
+    let analysis_json = fs::read_to_string(analysis_path)?;
+    let analysis: ProjectAnalysis = serde_json::from_str(&analysis_json)?;
+    if analysis.onboarding_guide.reading_path.is_empty() {
+        return Err(anyhow!("The analysis has no reading path. Run 'codemap analyze' again."));
+    }
+    
+    // Tours follow the reading path, opening each file at its key symbol
+    let index = SymbolIndex::load()?;
+    let stops = tour::build(&analysis.onboarding_guide.reading_path, &index, &analysis.architecture.dependency_graph);
+    
+    if std::io::stdout().is_terminal() {
+        tour::run(stops)?;
+        println!("📍 Progress saved to {}", tour::PROGRESS_PATH);
+    } else {
+        println!("{}", "🎯 Codebase Tour".bold().blue());
+        tour::print(&stops);
+    }
     
     Ok(())
 }
@@ -797,6 +2029,235 @@ fn handle_diff() -> Result<()> {
     Ok(())
 }
 
+fn handle_find(name: String, kind: Vec<String>, limit: usize) -> Result<()> {
+    let kinds = kind
+        .iter()
+        .map(|k| symbols::parse_kind(k))
+        .collect::<Result<Vec<_>>>()?;
+    
+    let index = SymbolIndex::load()?;
+    let matches = index.search(&name, &kinds);
+    
+    if matches.is_empty() {
+        println!("No symbols matching '{}'", name.yellow());
+        return Ok(());
+    }
+    
+    println!("{}", format!("🔎 Symbols matching '{}'", name).bold().blue());
+    for entry in matches.iter().take(limit) {
+        println!("   {:<10} {} {}",
+            format!("{:?}", entry.kind).cyan(),
+            entry.qualified_name().green(),
+            format!("{}:{}", entry.path, entry.line).dimmed()
+        );
+    }
+    if matches.len() > limit {
+        println!("   ... and {} more (use --limit to show more)", matches.len() - limit);
+    }
+    
+    Ok(())
+}
+
+fn handle_graph(
+    format: String,
+    level: String,
+    cluster: bool,
+    filter: Option<String>,
+    max_depth: Option<usize>,
+    output: Option<String>,
+) -> Result<()> {
+    let analysis_path = Path::new(".codemap/analysis.json");
+    if !analysis_path.exists() {
+        return Err(anyhow!("No analysis found. Run 'codemap analyze' first."));
+    }
+    
+    let analysis_json = fs::read_to_string(analysis_path)?;
+    let analysis: ProjectAnalysis = serde_json::from_str(&analysis_json)?;
+    
+    let options = DiagramOptions {
+        level: Level::parse(&level)?,
+        cluster,
+        filter,
+        max_depth,
+    };
+    let diagram = Diagram::build(&analysis.architecture.dependency_graph, &options);
+    let rendered = diagram.render(&format)?;
+    
+    match output {
+        Some(output_path) => {
+            fs::write(&output_path, rendered)?;
+            println!("✅ {} nodes, {} edges written to: {}",
+                diagram.nodes.len(),
+                diagram.edges.len(),
+                output_path.green()
+            );
+        }
+        None => print!("{}", rendered),
+    }
+    
+    Ok(())
+}
+
+fn handle_check(path: PathBuf, rev: Option<String>, update_baseline: bool) -> Result<()> {
+    let config = load_config()?;
+    if config.rules.is_empty() {
+        println!("No [[rules]] defined in .codemap/config.toml, nothing to check");
+        return Ok(());
+    }
+    
+    let source = source::open_source(&path, rev.as_deref())?;
+    let classifier = Classifier::new(
+        source.as_ref(),
+        &config.classification.include,
+        config.classification.exclude_generated,
+    );
+    let (_, parsed_files) = analyze_project_info(source.as_ref(), &classifier, config.general.max_file_size)?;
+    let dependency_graph = DependencyGraph::build(source.as_ref(), &parsed_files);
+    let violations = rules::evaluate(&dependency_graph, &config.rules)?;
+    
+    if update_baseline {
+        Baseline::save(&violations)?;
+        println!("✅ Recorded {} accepted violations in {}", violations.len(), rules::BASELINE_PATH.green());
+        return Ok(());
+    }
+    
+    let baseline = Baseline::load()?;
+    let (grandfathered, new): (Vec<_>, Vec<_>) = violations.iter().partition(|v| baseline.contains(v));
+    
+    for violation in &new {
+        let dep = &violation.dependency;
+        println!("{} {}:{} imports {} (`{}`)",
+            "error:".red().bold(),
+            dep.from,
+            dep.line,
+            dep.to.yellow(),
+            dep.import
+        );
+        println!("       [{}] {}", violation.rule.cyan(), violation.reason);
+    }
+    
+    let fixed = baseline.fixed(&violations);
+    if !fixed.is_empty() {
+        println!("ℹ️  {} baselined violations no longer occur; run 'codemap check --update-baseline' to drop them", fixed.len());
+    }
+    
+    println!("Checked {} rules against {} imports: {} violations, {} grandfathered",
+        config.rules.len(),
+        dependency_graph.edges.len(),
+        new.len(),
+        grandfathered.len()
+    );
+    
+    if !new.is_empty() {
+        return Err(anyhow!("{} architecture rule violations", new.len()));
+    }
+    println!("✅ {}", "All architecture rules pass".green());
+    Ok(())
+}
+
+fn handle_deps(path: PathBuf, rev: Option<String>, format: String) -> Result<()> {
+    let config = load_config()?;
+    let source = source::open_source(&path, rev.as_deref())?;
+    let classifier = Classifier::new(
+        source.as_ref(),
+        &config.classification.include,
+        config.classification.exclude_generated,
+    );
+    let manifests = find_manifests(source.as_ref(), &classifier);
+    let reports = lockfiles::analyze(source.as_ref(), &find_lockfiles(source.as_ref()), &manifests);
+    
+    match format.as_str() {
+        "text" => {
+            if reports.is_empty() {
+                println!("No lockfiles found (looked for {})", lockfiles::LOCKFILES.join(", "));
+            }
+            display_dependencies(&reports);
+        }
+        "json" => println!("{}", serde_json::to_string_pretty(&reports)?),
+        _ => return Err(anyhow!("Unsupported format: {}", format)),
+    }
+    
+    Ok(())
+}
+
+fn handle_audit(path: PathBuf, rev: Option<String>, advisory_db: Option<PathBuf>, format: String) -> Result<()> {
+    let config = load_config()?;
+    let db = open_advisory_db(advisory_db, &config)?
+        .ok_or_else(|| anyhow!("No advisory database given. Pass --advisory-db or set [audit] advisory_db"))?;
+    let source = source::open_source(&path, rev.as_deref())?;
+    let classifier = Classifier::new(
+        source.as_ref(),
+        &config.classification.include,
+        config.classification.exclude_generated,
+    );
+    let manifests = find_manifests(source.as_ref(), &classifier);
+    let reports = lockfiles::analyze(source.as_ref(), &find_lockfiles(source.as_ref()), &manifests);
+    // Nothing checked is not the same as nothing vulnerable
+    if reports.is_empty() {
+        return Err(anyhow!("No lockfiles found in {}; nothing was audited", path.display()));
+    }
+    let checked: usize = reports.iter().map(|r| r.packages.len()).sum();
+    if checked == 0 {
+        return Err(anyhow!("No locked packages found in {} lockfiles; nothing was audited", reports.len()));
+    }
+    let vulnerabilities = db.check(&reports);
+    
+    match format.as_str() {
+        "text" => {
+            display_vulnerabilities(&vulnerabilities);
+            println!("Checked {} locked packages in {} lockfiles against {} advisories: {} vulnerabilities",
+                checked,
+                reports.len(),
+                db.len(),
+                vulnerabilities.len()
+            );
+        }
+        "json" => println!("{}", serde_json::to_string_pretty(&vulnerabilities)?),
+        _ => return Err(anyhow!("Unsupported format: {}", format)),
+    }
+    
+    if !vulnerabilities.is_empty() {
+        return Err(anyhow!("{} known vulnerabilities in locked dependencies", vulnerabilities.len()));
+    }
+    if format == "text" {
+        println!("✅ {}", "No known vulnerabilities".green());
+    }
+    Ok(())
+}
+
+fn handle_licenses(path: PathBuf, rev: Option<String>, format: String) -> Result<()> {
+    let config = load_config()?;
+    let source = source::open_source(&path, rev.as_deref())?;
+    let classifier = Classifier::new(
+        source.as_ref(),
+        &config.classification.include,
+        config.classification.exclude_generated,
+    );
+    let files = project_files(source.as_ref(), &classifier);
+    let manifests = manifests::parse_all(source.as_ref(), &files);
+    let project = licenses::detect_project(source.as_ref(), &files, &manifests);
+    let mut reports = lockfiles::analyze(source.as_ref(), &find_lockfiles(source.as_ref()), &manifests);
+    let checks = config.licenses.check(&licenses::resolve(source.as_ref(), &mut reports));
+    
+    match format.as_str() {
+        "text" => {
+            display_project_license(&project);
+            display_license_checks(&checks);
+        }
+        "json" => println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+            "project": project,
+            "dependencies": checks,
+        }))?),
+        _ => return Err(anyhow!("Unsupported format: {}", format)),
+    }
+    
+    let violations = checks.iter().filter(|c| c.violation).count();
+    if violations > 0 {
+        return Err(anyhow!("{} dependencies violate the license policy", violations));
+    }
+    Ok(())
+}
+
 fn handle_export(format: String, output: Option<String>) -> Result<()> {
     let analysis_path = Path::new(".codemap/analysis.json");
     if !analysis_path.exists() {
@@ -804,14 +2265,31 @@ fn handle_export(format: String, output: Option<String>) -> Result<()> {
     }
     
     let analysis_json = fs::read_to_string(analysis_path)?;
-    let _analysis: ProjectAnalysis = serde_json::from_str(&analysis_json)?;
+    let analysis: ProjectAnalysis = serde_json::from_str(&analysis_json)?;
     
-    let output_path = output.unwrap_or_else(|| format!("codemap-analysis.{}", format));
+    let output_path = output.unwrap_or_else(|| match format.as_str() {
+        "cyclonedx" => "codemap-sbom.cdx.json".to_string(),
+        "spdx" => "codemap-sbom.spdx.json".to_string(),
+        _ => format!("codemap-analysis.{}", format),
+    });
+    let project = sbom::Project {
+        name: &analysis.project_info.name,
+        license: analysis.project_info.license.expression.as_deref(),
+        timestamp: &analysis.analysis_timestamp,
+    };
     
     match format.as_str() {
         "json" => {
             fs::write(&output_path, analysis_json)?;
         }
+        "cyclonedx" => {
+            let bom = sbom::cyclonedx(&project, &analysis.manifests, &analysis.dependencies);
+            fs::write(&output_path, serde_json::to_string_pretty(&bom)?)?;
+        }
+        "spdx" => {
+            let document = sbom::spdx(&project, &analysis.manifests, &analysis.dependencies);
+            fs::write(&output_path, serde_json::to_string_pretty(&document)?)?;
+        }
         "html" => {
             // TODO: Generate HTML report
             println!("HTML export not yet implemented");
@@ -839,11 +2317,21 @@ fn main() -> Result<()> {
     
     match cli.command {
         Commands::Init { name } => handle_init(name)?,
-        Commands::Analyze { format, detailed, no_ai } => handle_analyze(format, detailed, no_ai)?,
-        Commands::Summary => handle_summary()?,
+        Commands::Analyze { path, rev, format, detailed, no_ai, include_generated, advisory_db } => {
+            handle_analyze(path, rev, format, detailed, no_ai, include_generated, advisory_db)?
+        }
+        Commands::Summary { detailed } => handle_summary(detailed)?,
         Commands::Tour => handle_tour()?,
         Commands::Config { api_key, ai_enabled } => handle_config(api_key, ai_enabled)?,
         Commands::Diff => handle_diff()?,
+        Commands::Find { name, kind, limit } => handle_find(name, kind, limit)?,
+        Commands::Graph { format, level, cluster, filter, max_depth, output } => {
+            handle_graph(format, level, cluster, filter, max_depth, output)?
+        }
+        Commands::Check { path, rev, update_baseline } => handle_check(path, rev, update_baseline)?,
+        Commands::Deps { path, rev, format } => handle_deps(path, rev, format)?,
+        Commands::Audit { path, rev, advisory_db, format } => handle_audit(path, rev, advisory_db, format)?,
+        Commands::Licenses { path, rev, format } => handle_licenses(path, rev, format)?,
         Commands::Export { format, output } => handle_export(format, output)?,
     }
     
@@ -0,0 +1,526 @@
+// manifests.rs - Dependency manifest parsing
+// Reads declared dependencies from Cargo, npm, Python, Go, Maven, Gradle, Composer, Bundler and NuGet manifests
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    PyPI,
+    Go,
+    Maven,
+    Packagist,
+    RubyGems,
+    NuGet,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
+    Optional,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeclaredDependency {
+    /// Registry name; Maven coordinates are `group:artifact`
+    pub name: String,
+    /// Version requirement as written, if any
+    pub requirement: Option<String>,
+    pub kind: DependencyKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Manifest {
+    pub path: String,
+    pub ecosystem: Ecosystem,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// License of the package itself, as declared
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub homepage: Option<String>,
+    /// Source repository URL, as declared
+    #[serde(default)]
+    pub repository: Option<String>,
+    /// `Name <email>` where an address is given
+    #[serde(default)]
+    pub authors: Vec<String>,
+    pub dependencies: Vec<DeclaredDependency>,
+}
+
+impl Manifest {
+    fn new(path: &Path, ecosystem: Ecosystem) -> Self {
+        Manifest {
+            path: path.to_string_lossy().replace('\\', "/"),
+            ecosystem,
+            name: None,
+            version: None,
+            license: None,
+            description: None,
+            homepage: None,
+            repository: None,
+            authors: Vec::new(),
+            dependencies: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, name: &str, requirement: Option<&str>, kind: DependencyKind) {
+        let requirement = requirement.map(str::trim).filter(|r| !r.is_empty() && *r != "*");
+        self.dependencies.push(DeclaredDependency {
+            name: name.trim().to_string(),
+            requirement: requirement.map(str::to_string),
+            kind,
+        });
+    }
+}
+
+/// Parses every recognised manifest among `paths`; unreadable or malformed files are skipped
+pub fn parse_all(source: &dyn FileSource, paths: &[PathBuf]) -> Vec<Manifest> {
+    let mut manifests: Vec<Manifest> = paths
+        .iter()
+        .filter_map(|path| {
+            let file_name = path.file_name()?.to_str()?;
+            let content = source.read_to_string(path)?;
+            match file_name {
+                "Cargo.toml" => parse_cargo(path, &content),
+                "package.json" => parse_package_json(path, &content),
+                "pyproject.toml" => parse_pyproject(path, &content),
+                "go.mod" => Some(parse_go_mod(path, &content)),
+                "pom.xml" => parse_pom(path, &content),
+                "build.gradle" | "build.gradle.kts" => Some(parse_gradle(path, &content)),
+                "composer.json" => parse_composer(path, &content),
+                "Gemfile" => Some(parse_gemfile(path, &content)),
+                name if name.ends_with(".csproj") => parse_csproj(path, &content),
+                name if name.starts_with("requirements") && name.ends_with(".txt") => {
+                    Some(parse_requirements(path, &content))
+                }
+                _ => None,
+            }
+        })
+        .collect();
+
+    manifests.sort_by(|a, b| a.path.cmp(&b.path));
+    manifests
+}
+
+// ----- Cargo -----
+
+fn parse_cargo(path: &Path, content: &str) -> Option<Manifest> {
+    let toml: toml::Value = toml::from_str(content).ok()?;
+    let mut manifest = Manifest::new(path, Ecosystem::Cargo);
+
+    if let Some(package) = toml.get("package") {
+        manifest.name = str_field(package, "name");
+        manifest.version = str_field(package, "version");
+        manifest.license = str_field(package, "license");
+        manifest.description = str_field(package, "description");
+        manifest.homepage = str_field(package, "homepage");
+        manifest.repository = str_field(package, "repository");
+        manifest.authors = string_array(package, "authors");
+    }
+
+    // Top-level, [workspace.dependencies] and [target.'cfg(..)'.dependencies]
+    let mut tables = vec![&toml];
+    tables.extend(toml.get("workspace"));
+    if let Some(targets) = toml.get("target").and_then(|t| t.as_table()) {
+        tables.extend(targets.values());
+    }
+
+    for table in tables {
+        for (section, kind) in [
+            ("dependencies", DependencyKind::Normal),
+            ("dev-dependencies", DependencyKind::Dev),
+            ("build-dependencies", DependencyKind::Build),
+        ] {
+            let Some(deps) = table.get(section).and_then(|d| d.as_table()) else { continue };
+            for (key, spec) in deps {
+                // Path dependencies are workspace members, not third-party code
+                if spec.get("path").is_some() {
+                    continue;
+                }
+                let name = spec.get("package").and_then(|p| p.as_str()).unwrap_or(key);
+                let requirement = spec.as_str().map(str::to_string).or_else(|| str_field(spec, "version"));
+                let optional = spec.get("optional").and_then(|o| o.as_bool()).unwrap_or(false);
+                let kind = if optional { DependencyKind::Optional } else { kind };
+                manifest.add(name, requirement.as_deref(), kind);
+            }
+        }
+    }
+
+    Some(manifest)
+}
+
+fn str_field(value: &toml::Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+fn string_array(value: &toml::Value, key: &str) -> Vec<String> {
+    value.get(key).and_then(|v| v.as_array()).into_iter().flatten().filter_map(|v| v.as_str()).map(str::to_string).collect()
+}
+
+// `Name <email>` from the `{ name, email }` tables of PEP 621 and composer
+fn person(name: Option<&str>, email: Option<&str>) -> Option<String> {
+    match (name, email) {
+        (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+        (Some(name), None) => Some(name.to_string()),
+        (None, Some(email)) => Some(format!("<{}>", email)),
+        (None, None) => None,
+    }
+}
+
+// ----- npm -----
+
+fn parse_package_json(path: &Path, content: &str) -> Option<Manifest> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut manifest = Manifest::new(path, Ecosystem::Npm);
+    manifest.name = json_str(&json, "name");
+    manifest.version = json_str(&json, "version");
+    // Older packages use { "type": "MIT", "url": ... }
+    manifest.license = json_str(&json, "license")
+        .or_else(|| json.get("license").and_then(|l| json_str(l, "type")));
+    manifest.description = json_str(&json, "description");
+    manifest.homepage = json_str(&json, "homepage");
+    // `"github:user/repo"`, a URL, or `{ "type": "git", "url": ... }`
+    manifest.repository = json_str(&json, "repository").or_else(|| json.get("repository").and_then(|r| json_str(r, "url")));
+    // People are `"Name <email> (url)"` strings or `{ name, email, url }` objects
+    let people = json.get("author").into_iter().chain(json.get("contributors").and_then(|c| c.as_array()).into_iter().flatten());
+    manifest.authors = people
+        .filter_map(|p| match p {
+            serde_json::Value::String(s) => Some(s.clone()),
+            _ => person(p.get("name").and_then(|n| n.as_str()), p.get("email").and_then(|e| e.as_str())),
+        })
+        .collect();
+
+    for (section, kind) in [
+        ("dependencies", DependencyKind::Normal),
+        ("devDependencies", DependencyKind::Dev),
+        ("peerDependencies", DependencyKind::Optional),
+        ("optionalDependencies", DependencyKind::Optional),
+    ] {
+        for (name, requirement) in json_object(&json, section) {
+            manifest.add(name, requirement.as_str(), kind);
+        }
+    }
+    Some(manifest)
+}
+
+fn json_str(json: &serde_json::Value, key: &str) -> Option<String> {
+    json.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+fn json_object<'a>(json: &'a serde_json::Value, key: &str) -> impl Iterator<Item = (&'a String, &'a serde_json::Value)> {
+    json.get(key).and_then(|v| v.as_object()).into_iter().flatten()
+}
+
+// ----- Python -----
+
+/// PEP 503 normalized name: PyPI names are case-insensitive with `-`, `_` and `.` interchangeable
+pub fn normalize_python(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+// PEP 508: name, optional [extras], then the version specifier up to any `;` marker
+static PEP508: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*([A-Za-z0-9][A-Za-z0-9._-]*)\s*(?:\[[^\]]*\])?\s*([^;#]*)").unwrap());
+
+fn add_pep508(manifest: &mut Manifest, spec: &str, kind: DependencyKind) {
+    if let Some(caps) = PEP508.captures(spec) {
+        let requirement = caps[2].trim().trim_start_matches('(').trim_end_matches(')');
+        manifest.add(&caps[1], Some(requirement), kind);
+    }
+}
+
+fn parse_requirements(path: &Path, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(path, Ecosystem::PyPI);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let kind = if name.contains("dev") || name.contains("test") { DependencyKind::Dev } else { DependencyKind::Normal };
+
+    for line in content.lines().map(str::trim) {
+        // Options (-r, -e, --index-url) and direct URLs don't name a registry package
+        if line.is_empty() || line.starts_with('#') || line.starts_with('-') || line.contains("://") {
+            continue;
+        }
+        add_pep508(&mut manifest, line, kind);
+    }
+    manifest
+}
+
+fn parse_pyproject(path: &Path, content: &str) -> Option<Manifest> {
+    let toml: toml::Value = toml::from_str(content).ok()?;
+    let mut manifest = Manifest::new(path, Ecosystem::PyPI);
+
+    // PEP 621
+    if let Some(project) = toml.get("project") {
+        manifest.name = str_field(project, "name");
+        manifest.version = str_field(project, "version");
+        // `license = "MIT"`, or the older `license = { text = "MIT" }`
+        manifest.license = str_field(project, "license").or_else(|| project.get("license").and_then(|l| str_field(l, "text")));
+        manifest.description = str_field(project, "description");
+        manifest.authors = project
+            .get("authors")
+            .and_then(|a| a.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|a| person(a.get("name").and_then(|n| n.as_str()), a.get("email").and_then(|e| e.as_str())))
+            .collect();
+        // [project.urls] keys are free-form; these are the common spellings
+        let url = |keys: &[&str]| {
+            let urls = project.get("urls").and_then(|u| u.as_table())?;
+            urls.iter().find(|(k, _)| keys.contains(&k.to_lowercase().as_str())).and_then(|(_, v)| v.as_str()).map(str::to_string)
+        };
+        manifest.homepage = url(&["homepage", "home", "documentation"]);
+        manifest.repository = url(&["repository", "source", "source code", "code", "github"]);
+        for spec in project.get("dependencies").and_then(|d| d.as_array()).into_iter().flatten() {
+            add_pep508(&mut manifest, spec.as_str().unwrap_or_default(), DependencyKind::Normal);
+        }
+        let optional = project.get("optional-dependencies").and_then(|d| d.as_table());
+        for specs in optional.into_iter().flat_map(|t| t.values()) {
+            for spec in specs.as_array().into_iter().flatten() {
+                add_pep508(&mut manifest, spec.as_str().unwrap_or_default(), DependencyKind::Optional);
+            }
+        }
+    }
+
+    // Poetry
+    if let Some(poetry) = toml.get("tool").and_then(|t| t.get("poetry")) {
+        manifest.name = manifest.name.or_else(|| str_field(poetry, "name"));
+        manifest.version = manifest.version.or_else(|| str_field(poetry, "version"));
+        manifest.license = manifest.license.or_else(|| str_field(poetry, "license"));
+        manifest.description = manifest.description.or_else(|| str_field(poetry, "description"));
+        manifest.homepage = manifest.homepage.or_else(|| str_field(poetry, "homepage"));
+        manifest.repository = manifest.repository.or_else(|| str_field(poetry, "repository"));
+        if manifest.authors.is_empty() {
+            manifest.authors = string_array(poetry, "authors");
+        }
+
+        let mut sections = vec![
+            (poetry.get("dependencies"), DependencyKind::Normal),
+            (poetry.get("dev-dependencies"), DependencyKind::Dev),
+        ];
+        if let Some(groups) = poetry.get("group").and_then(|g| g.as_table()) {
+            sections.extend(groups.values().map(|g| (g.get("dependencies"), DependencyKind::Dev)));
+        }
+        for (deps, kind) in sections {
+            for (name, spec) in deps.and_then(|d| d.as_table()).into_iter().flatten() {
+                if name == "python" || spec.get("path").is_some() {
+                    continue;
+                }
+                let requirement = spec.as_str().map(str::to_string).or_else(|| str_field(spec, "version"));
+                manifest.add(name, requirement.as_deref(), kind);
+            }
+        }
+    }
+
+    Some(manifest)
+}
+
+// ----- Go -----
+
+fn parse_go_mod(path: &Path, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(path, Ecosystem::Go);
+    let mut in_require = false;
+
+    for line in content.lines().map(str::trim) {
+        if let Some(module) = line.strip_prefix("module ") {
+            manifest.name = Some(module.trim().trim_matches('"').to_string());
+        } else if line.starts_with("require (") {
+            in_require = true;
+        } else if in_require && line.starts_with(')') {
+            in_require = false;
+        } else if let Some(spec) = line.strip_prefix("require ").or(if in_require { Some(line) } else { None }) {
+            let indirect = spec.contains("// indirect");
+            let spec = spec.split("//").next().unwrap_or_default();
+            let mut parts = spec.split_whitespace();
+            if let (Some(name), version) = (parts.next(), parts.next()) {
+                let kind = if indirect { DependencyKind::Optional } else { DependencyKind::Normal };
+                manifest.add(name, version, kind);
+            }
+        }
+    }
+    manifest
+}
+
+// ----- Maven and Gradle -----
+
+fn parse_pom(path: &Path, content: &str) -> Option<Manifest> {
+    let doc = roxmltree::Document::parse(content).ok()?;
+    let project = doc.root_element();
+    let mut manifest = Manifest::new(path, Ecosystem::Maven);
+
+    let child = |node: roxmltree::Node, name: &str| -> Option<String> {
+        node.children()
+            .find(|c| c.tag_name().name() == name)
+            .and_then(|c| c.text())
+            .map(|t| t.trim().to_string())
+    };
+
+    let parent = project.children().find(|c| c.tag_name().name() == "parent");
+    let group = child(project, "groupId").or_else(|| parent.and_then(|p| child(p, "groupId")));
+    if let (Some(group), Some(artifact)) = (group, child(project, "artifactId")) {
+        manifest.name = Some(format!("{}:{}", group, artifact));
+    }
+    manifest.version = child(project, "version");
+    let licenses: Vec<String> = project
+        .children()
+        .filter(|c| c.tag_name().name() == "licenses")
+        .flat_map(|l| l.children().filter(|c| c.tag_name().name() == "license"))
+        .filter_map(|l| child(l, "name"))
+        .collect();
+    manifest.license = (!licenses.is_empty()).then(|| licenses.join(" OR "));
+    manifest.description = child(project, "description");
+    manifest.homepage = child(project, "url");
+    let scm = project.children().find(|c| c.tag_name().name() == "scm");
+    manifest.repository = scm.and_then(|s| child(s, "url"));
+    let developers = project.children().filter(|c| c.tag_name().name() == "developers");
+    manifest.authors = developers
+        .flat_map(|d| d.children().filter(|c| c.tag_name().name() == "developer"))
+        .filter_map(|d| person(child(d, "name").as_deref(), child(d, "email").as_deref()))
+        .collect();
+
+    // The parent POM (e.g. spring-boot-starter-parent) says as much about the stack as a dependency
+    if let Some(parent) = parent {
+        if let (Some(group), Some(artifact)) = (child(parent, "groupId"), child(parent, "artifactId")) {
+            manifest.add(&format!("{}:{}", group, artifact), child(parent, "version").as_deref(), DependencyKind::Build);
+        }
+    }
+
+    for dependency in project.descendants().filter(|n| n.tag_name().name() == "dependency") {
+        let (Some(group), Some(artifact)) = (child(dependency, "groupId"), child(dependency, "artifactId")) else {
+            continue;
+        };
+        let kind = match child(dependency, "scope").as_deref() {
+            Some("test") => DependencyKind::Dev,
+            Some("provided") | Some("system") => DependencyKind::Build,
+            _ if child(dependency, "optional").as_deref() == Some("true") => DependencyKind::Optional,
+            _ => DependencyKind::Normal,
+        };
+        manifest.add(&format!("{}:{}", group, artifact), child(dependency, "version").as_deref(), kind);
+    }
+    Some(manifest)
+}
+
+static GRADLE_DEPENDENCY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^\s*(\w+)\s*\(?\s*["']([^"':\s]+):([^"':\s]+)(?::([^"'\s]+))?["']"#).unwrap()
+});
+static GRADLE_PLUGIN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?m)^\s*id\s*\(?\s*["']([^"']+)["']\s*\)?(?:\s+version\s*\(?\s*["']([^"']+)["'])?"#).unwrap()
+});
+
+fn parse_gradle(path: &Path, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(path, Ecosystem::Maven);
+
+    for caps in GRADLE_DEPENDENCY.captures_iter(content) {
+        let kind = match &caps[1] {
+            c if c.starts_with("test") => DependencyKind::Dev,
+            "compileOnly" | "annotationProcessor" | "kapt" | "ksp" | "classpath" => DependencyKind::Build,
+            "implementation" | "api" | "runtimeOnly" | "compile" | "runtime" => DependencyKind::Normal,
+            _ => continue,
+        };
+        let name = format!("{}:{}", &caps[2], &caps[3]);
+        manifest.add(&name, caps.get(4).map(|m| m.as_str()), kind);
+    }
+    for caps in GRADLE_PLUGIN.captures_iter(content) {
+        manifest.add(&caps[1], caps.get(2).map(|m| m.as_str()), DependencyKind::Build);
+    }
+    manifest
+}
+
+// ----- PHP, Ruby and .NET -----
+
+fn parse_composer(path: &Path, content: &str) -> Option<Manifest> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut manifest = Manifest::new(path, Ecosystem::Packagist);
+    manifest.name = json_str(&json, "name");
+    manifest.version = json_str(&json, "version");
+    // A list of licenses means the user may pick any of them
+    manifest.license = json_str(&json, "license").or_else(|| {
+        let licenses: Vec<&str> = json.get("license")?.as_array()?.iter().filter_map(|l| l.as_str()).collect();
+        (!licenses.is_empty()).then(|| licenses.join(" OR "))
+    });
+    manifest.description = json_str(&json, "description");
+    manifest.homepage = json_str(&json, "homepage");
+    manifest.repository = json.get("support").and_then(|s| json_str(s, "source"));
+    manifest.authors = json
+        .get("authors")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|a| person(a.get("name").and_then(|n| n.as_str()), a.get("email").and_then(|e| e.as_str())))
+        .collect();
+
+    for (section, kind) in [("require", DependencyKind::Normal), ("require-dev", DependencyKind::Dev)] {
+        for (name, requirement) in json_object(&json, section) {
+            // Platform requirements aren't packages
+            if name == "php" || name.starts_with("ext-") || name.starts_with("lib-") {
+                continue;
+            }
+            manifest.add(name, requirement.as_str(), kind);
+        }
+    }
+    Some(manifest)
+}
+
+static GEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^\s*gem\s+["']([^"']+)["'](?:\s*,\s*["']([^"']+)["'])?"#).unwrap());
+
+fn parse_gemfile(path: &Path, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(path, Ecosystem::RubyGems);
+    let mut group_depth = 0;
+    let mut dev_group = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("group ") && trimmed.ends_with(" do") {
+            group_depth += 1;
+            dev_group = trimmed.contains(":development") || trimmed.contains(":test");
+        } else if trimmed == "end" && group_depth > 0 {
+            group_depth -= 1;
+            dev_group = false;
+        } else if let Some(caps) = GEM.captures(line) {
+            let kind = if dev_group { DependencyKind::Dev } else { DependencyKind::Normal };
+            manifest.add(&caps[1], caps.get(2).map(|m| m.as_str()), kind);
+        }
+    }
+    manifest
+}
+
+fn parse_csproj(path: &Path, content: &str) -> Option<Manifest> {
+    let doc = roxmltree::Document::parse(content).ok()?;
+    let mut manifest = Manifest::new(path, Ecosystem::NuGet);
+    manifest.name = path.file_stem().map(|s| s.to_string_lossy().to_string());
+    let property = |name: &str| {
+        doc.descendants()
+            .filter(|n| n.tag_name().name() == "PropertyGroup")
+            .flat_map(|group| group.children())
+            .find(|n| n.tag_name().name() == name)
+            .and_then(|n| n.text())
+            .map(|t| t.trim().to_string())
+    };
+    manifest.version = property("Version");
+    manifest.license = property("PackageLicenseExpression");
+
+    for reference in doc.descendants().filter(|n| n.tag_name().name() == "PackageReference") {
+        let Some(name) = reference.attribute("Include") else { continue };
+        let version = reference.attribute("Version").map(str::to_string).or_else(|| {
+            reference
+                .children()
+                .find(|c| c.tag_name().name() == "Version")
+                .and_then(|c| c.text())
+                .map(str::to_string)
+        });
+        let kind = if reference.attribute("PrivateAssets").is_some() { DependencyKind::Build } else { DependencyKind::Normal };
+        manifest.add(name, version.as_deref(), kind);
+    }
+    Some(manifest)
+}
@@ -0,0 +1,211 @@
+// metadata.rs - Project metadata
+// Description, version, links, authors and badges from the root manifest, the README and the git remote
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::manifests::{Ecosystem, Manifest};
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Badge {
+    pub label: String,
+    pub image: String,
+    /// Where clicking the badge leads, usually the CI run or registry page
+    pub link: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProjectMetadata {
+    pub description: Option<String>,
+    pub version: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub authors: Vec<String>,
+    pub badges: Vec<Badge>,
+}
+
+// Long enough for a README's opening paragraph, short enough for a summary line
+const DESCRIPTION_LIMIT: usize = 300;
+
+/// Manifest fields win; the README and git remote fill what they leave out
+pub fn detect(source: &dyn FileSource, paths: &[PathBuf], manifests: &[Manifest]) -> ProjectMetadata {
+    // The shallowest manifest describes the project itself
+    let depth = |m: &&Manifest| m.path.matches('/').count();
+    let min_depth = manifests.iter().map(|m| depth(&m)).min();
+    let roots: Vec<&Manifest> = manifests.iter().filter(|m| Some(depth(m)) == min_depth).collect();
+    let first = |field: fn(&Manifest) -> Option<&String>| roots.iter().find_map(|m| field(m).filter(|v| !v.trim().is_empty())).cloned();
+
+    let readme = paths
+        .iter()
+        .filter(|p| p.components().count() == 1)
+        .find(|p| p.to_string_lossy().to_lowercase().starts_with("readme"))
+        .and_then(|p| source.read_to_string(p))
+        .unwrap_or_default();
+
+    let repository = first(|m| m.repository.as_ref())
+        .or_else(|| roots.iter().find(|m| m.ecosystem == Ecosystem::Go).and_then(|m| m.name.clone()))
+        .or_else(|| git_remote(source))
+        .and_then(|r| repository_url(&r));
+
+    ProjectMetadata {
+        description: first(|m| m.description.as_ref()).or_else(|| first_paragraph(&readme)).map(|d| truncate(d.trim())),
+        version: first(|m| m.version.as_ref()),
+        homepage: first(|m| m.homepage.as_ref()),
+        repository,
+        authors: roots.iter().map(|m| &m.authors).find(|a| !a.is_empty()).cloned().unwrap_or_default(),
+        badges: badges(&readme),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= DESCRIPTION_LIMIT {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(DESCRIPTION_LIMIT).collect();
+    // End on a sentence when there is one, else a word
+    match cut.rfind(". ") {
+        Some(end) if end > DESCRIPTION_LIMIT / 2 => cut[..=end].to_string(),
+        _ => format!("{}…", cut[..cut.rfind(' ').unwrap_or(cut.len())].trim_end()),
+    }
+}
+
+// ----- README -----
+
+static IMAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[!\[[^\]]*\]\([^)]*\)\]\([^)]*\)|!\[[^\]]*\]\([^)]*\)").unwrap());
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap());
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+static EMPHASIS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\*\*|__|\*|`)").unwrap());
+
+// The first run of prose lines, skipping the title, badges, HTML banners and code
+fn first_paragraph(readme: &str) -> Option<String> {
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut in_code = false;
+
+    for line in readme.lines().map(str::trim) {
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        // Setext underlines belong to the title above them
+        let structural = line.starts_with('#')
+            || line.starts_with('|')
+            || line.starts_with("---")
+            || line.starts_with("===")
+            || line.starts_with('-') && line.len() > 1 && line.as_bytes()[1] == b' '
+            || line.starts_with("* ");
+        // Badge rows and banners are left with nothing once images and tags go
+        let prose = IMAGE.replace_all(line, "");
+        let prose = LINK.replace_all(&prose, "$1");
+        let prose = HTML_TAG.replace_all(&prose, "");
+        let prose = EMPHASIS.replace_all(&prose, "");
+        let prose = prose.trim().trim_start_matches('>').trim();
+
+        if in_code || structural || prose.is_empty() {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        paragraph.push(prose.to_string());
+    }
+    (!paragraph.is_empty()).then(|| paragraph.join(" "))
+}
+
+static MARKDOWN_BADGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:\[)?!\[([^\]]*)\]\(([^)\s]+)[^)]*\)(?:\]\(([^)\s]+)[^)]*\))?").unwrap());
+static HTML_BADGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?:<a\s[^>]*href="([^"]+)"[^>]*>\s*)?<img\s[^>]*src="([^"]+)"[^>]*>"#).unwrap());
+static HTML_ALT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"alt="([^"]*)""#).unwrap());
+
+// Images served by badge services or named like one
+fn is_badge(image: &str) -> bool {
+    let image = image.to_lowercase();
+    ["shields.io", "badge", "codecov.io", "coveralls.io", "travis-ci", "docs.rs/", "badgen.net", "circleci.com"]
+        .iter()
+        .any(|marker| image.contains(marker))
+}
+
+fn badges(readme: &str) -> Vec<Badge> {
+    let mut badges: Vec<Badge> = Vec::new();
+    for caps in MARKDOWN_BADGE.captures_iter(readme) {
+        let image = caps[2].to_string();
+        if is_badge(&image) {
+            badges.push(Badge { label: badge_label(&caps[1], &image), image, link: caps.get(3).map(|l| l.as_str().to_string()) });
+        }
+    }
+    for caps in HTML_BADGE.captures_iter(readme) {
+        let image = caps[2].to_string();
+        if is_badge(&image) && !badges.iter().any(|b| b.image == image) {
+            let alt = HTML_ALT.captures(&caps[0]).map(|a| a[1].to_string()).unwrap_or_default();
+            badges.push(Badge { label: badge_label(&alt, &image), image, link: caps.get(1).map(|l| l.as_str().to_string()) });
+        }
+    }
+    badges
+}
+
+// Alt text when given, else the label segment of a shields.io URL or the image's file name
+fn badge_label(alt: &str, image: &str) -> String {
+    if !alt.trim().is_empty() {
+        return alt.trim().to_string();
+    }
+    let path = image.split(['?', '#']).next().unwrap_or(image);
+    if let Some(rest) = path.split("/badge/").nth(1) {
+        return rest.split('-').next().unwrap_or(rest).replace("%20", " ").replace('_', " ");
+    }
+    let segments: Vec<&str> = path.trim_end_matches('/').rsplit('/').collect();
+    match segments.as_slice() {
+        // `.../workflows/CI/badge.svg` is labelled by the workflow
+        [file, name, ..] if file.starts_with("badge") => name.to_string(),
+        [file, ..] => file.split('.').next().unwrap_or(file).to_string(),
+        [] => image.to_string(),
+    }
+}
+
+// ----- Repository -----
+
+// `url` of the origin remote in .git/config, when the source is a checkout
+fn git_remote(source: &dyn FileSource) -> Option<String> {
+    let config = source.read_to_string(Path::new(".git/config"))?;
+    let mut in_origin = false;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_origin = line == "[remote \"origin\"]";
+        } else if in_origin {
+            if let Some(url) = line.strip_prefix("url").map(str::trim_start).and_then(|l| l.strip_prefix('=')) {
+                return Some(url.trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Browsable https URL from git remotes, `git+` URLs, npm shorthands and Go module paths
+fn repository_url(raw: &str) -> Option<String> {
+    let raw = raw.trim().trim_start_matches("git+");
+    let url = if let Some(rest) = raw.strip_prefix("github:") {
+        format!("https://github.com/{}", rest)
+    } else if let Some(rest) = raw.strip_prefix("gitlab:") {
+        format!("https://gitlab.com/{}", rest)
+    } else if let Some(rest) = raw.strip_prefix("bitbucket:") {
+        format!("https://bitbucket.org/{}", rest)
+    } else if let Some((host, path)) = raw.strip_prefix("git@").and_then(|r| r.split_once(':')) {
+        format!("https://{}/{}", host, path)
+    } else if let Some(rest) = raw.strip_prefix("ssh://").or_else(|| raw.strip_prefix("git://")) {
+        let rest = rest.split_once('@').map_or(rest, |(_, host)| host);
+        format!("https://{}", rest)
+    } else if raw.starts_with("http://") || raw.starts_with("https://") {
+        raw.to_string()
+    } else if raw.split('/').count() == 2 && !raw.contains('.') {
+        // npm's bare `user/repo` means GitHub
+        format!("https://github.com/{}", raw)
+    } else if raw.split('/').next().is_some_and(|host| host.contains('.')) {
+        format!("https://{}", raw)
+    } else {
+        return None;
+    };
+    Some(url.trim_end_matches('/').trim_end_matches(".git").to_string())
+}
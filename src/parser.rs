@@ -0,0 +1,667 @@
+// parser.rs - Syntax-aware parsing layer
+// Uses tree-sitter grammars to extract functions, methods, types and modules with line spans,
+// plus per-function cyclomatic and cognitive complexity
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Trait,
+    Interface,
+    Module,
+    Constant,
+    TypeAlias,
+}
+
+impl SymbolKind {
+    pub fn is_callable(self) -> bool {
+        matches!(self, SymbolKind::Function | SymbolKind::Method)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct Complexity {
+    pub cyclomatic: u32,
+    pub cognitive: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Enclosing impl, class, trait or receiver type for methods
+    pub container: Option<String>,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Only set for functions and methods with a body; trait and abstract declarations have none
+    pub complexity: Option<Complexity>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportKind {
+    /// `use`, `import`, `require` and the like
+    #[default]
+    Use,
+    /// A Rust `mod name;` declaration, which pulls a file into the crate without using it
+    ModDeclaration,
+}
+
+/// An import as written in the source, before it is resolved to a file
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// `crate::parser::Symbol`, `..models`, `./utils`, `github.com/org/repo/pkg`
+    pub module: String,
+    /// Names pulled in by `from module import a, b` (Python), which may themselves be submodules
+    pub names: Vec<String>,
+    pub line: usize,
+    /// Inline `mod name { ... }` blocks around the import, outermost first (Rust)
+    pub inline_modules: Vec<String>,
+    pub kind: ImportKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedFile {
+    pub path: PathBuf,
+    pub language: String,
+    pub symbols: Vec<Symbol>,
+    pub imports: Vec<Import>,
+}
+
+impl ParsedFile {
+    /// Functions and methods with a body, leaving out bodiless trait, interface and abstract declarations
+    pub fn callables(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.iter().filter(|s| s.kind.is_callable() && s.complexity.is_some())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Grammar {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+    Java,
+    CSharp,
+    Php,
+}
+
+impl Grammar {
+    fn for_language(language: &str, path: &Path) -> Option<Grammar> {
+        let is_tsx = path.extension().is_some_and(|ext| ext == "tsx");
+        match language {
+            "Rust" => Some(Grammar::Rust),
+            "Python" => Some(Grammar::Python),
+            "JavaScript" => Some(Grammar::JavaScript),
+            "TypeScript" if is_tsx => Some(Grammar::Tsx),
+            "TypeScript" => Some(Grammar::TypeScript),
+            "Go" => Some(Grammar::Go),
+            "Java" => Some(Grammar::Java),
+            "C#" => Some(Grammar::CSharp),
+            "PHP" => Some(Grammar::Php),
+            _ => None,
+        }
+    }
+
+    fn language(self) -> tree_sitter::Language {
+        match self {
+            Grammar::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Grammar::Python => tree_sitter_python::LANGUAGE.into(),
+            Grammar::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Grammar::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Grammar::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Grammar::Go => tree_sitter_go::LANGUAGE.into(),
+            Grammar::Java => tree_sitter_java::LANGUAGE.into(),
+            Grammar::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
+            Grammar::Php => tree_sitter_php::LANGUAGE_PHP.into(),
+        }
+    }
+}
+
+/// Parses a file and extracts its symbols. Returns `None` for languages without a grammar.
+pub fn parse(path: &Path, content: &str, language: &str) -> Option<ParsedFile> {
+    let grammar = Grammar::for_language(language, path)?;
+    let mut parser = Parser::new();
+    parser.set_language(&grammar.language()).ok()?;
+    let tree = parser.parse(content, None)?;
+
+    let mut walker = Walker {
+        grammar,
+        source: content.as_bytes(),
+        symbols: Vec::new(),
+        imports: Vec::new(),
+    };
+    walker.walk(tree.root_node());
+
+    Some(ParsedFile {
+        path: path.to_path_buf(),
+        language: language.to_string(),
+        symbols: walker.symbols,
+        imports: walker.imports,
+    })
+}
+
+// ----- Symbol Extraction -----
+
+#[derive(Clone, Default)]
+struct Scope {
+    container: Option<String>,
+    // Inside a class, impl, trait or interface body: functions here are methods
+    in_type: bool,
+    // Inside a function body: only nested functions and types are recorded, not constants
+    in_function: bool,
+    // Inline Rust modules around the node, which `self` and `super` are relative to
+    modules: Vec<String>,
+}
+
+struct Walker<'a> {
+    grammar: Grammar,
+    source: &'a [u8],
+    symbols: Vec<Symbol>,
+    imports: Vec<Import>,
+}
+
+impl Walker<'_> {
+    fn text(&self, node: Node) -> String {
+        node.utf8_text(self.source).unwrap_or_default().to_string()
+    }
+
+    fn field_text(&self, node: Node, field: &str) -> Option<String> {
+        node.child_by_field_name(field).map(|n| self.text(n))
+    }
+
+    // Depth-first in source order with an explicit stack, so deeply nested code can't overflow the call stack
+    fn walk(&mut self, root: Node) {
+        let mut stack = vec![(root, Scope::default())];
+        while let Some((node, scope)) = stack.pop() {
+            let child_scope = self.visit(node, &scope);
+            let mut cursor = node.walk();
+            let children: Vec<Node> = node.named_children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev().map(|child| (child, child_scope.clone())));
+        }
+    }
+
+    // Records the symbol and imports at `node` and returns the scope its children see
+    fn visit(&mut self, node: Node, scope: &Scope) -> Scope {
+        let mut child_scope = scope.clone();
+
+        if let Some((kind, name)) = self.symbol_at(node, scope) {
+            let callable = kind.is_callable();
+            self.symbols.push(Symbol {
+                name: name.clone(),
+                kind,
+                container: if callable { self.method_container(node, scope) } else { None },
+                start_line: node.start_position().row + 1,
+                end_line: node.end_position().row + 1,
+                complexity: (callable && !is_declaration_only(self.grammar, node))
+                    .then(|| complexity(self.grammar, node, self.source)),
+            });
+
+            match kind {
+                SymbolKind::Function | SymbolKind::Method => {
+                    child_scope.in_type = false;
+                    child_scope.in_function = true;
+                }
+                SymbolKind::Class | SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Trait | SymbolKind::Interface => {
+                    child_scope.container = Some(name);
+                    child_scope.in_type = true;
+                }
+                SymbolKind::Module if self.grammar == Grammar::Rust && node.child_by_field_name("body").is_some() => {
+                    child_scope.modules.push(name);
+                }
+                _ => {}
+            }
+        } else if let Some(container) = self.implicit_container(node) {
+            child_scope.container = Some(container);
+            child_scope.in_type = true;
+        }
+        self.collect_imports(node, scope);
+        child_scope
+    }
+
+    fn collect_imports(&mut self, node: Node, scope: &Scope) {
+        let mut found: Vec<(String, Vec<String>)> = Vec::new();
+        let mut kind = ImportKind::Use;
+
+        match (self.grammar, node.kind()) {
+            (Grammar::Rust, "use_declaration") => {
+                if let Some(argument) = self.field_text(node, "argument") {
+                    for path in expand_use_tree(&argument) {
+                        found.push((path, Vec::new()));
+                    }
+                }
+            }
+            // `mod foo;` pulls in foo.rs or foo/mod.rs next to this module
+            (Grammar::Rust, "mod_item") if node.child_by_field_name("body").is_none() => {
+                if let Some(name) = self.field_text(node, "name") {
+                    found.push((format!("self::{}", name), Vec::new()));
+                    kind = ImportKind::ModDeclaration;
+                }
+            }
+            (Grammar::Python, "import_statement") => {
+                let mut cursor = node.walk();
+                let modules: Vec<String> = node
+                    .children_by_field_name("name", &mut cursor)
+                    .map(|n| n.child_by_field_name("name").map_or_else(|| self.text(n), |inner| self.text(inner)))
+                    .collect();
+                for module in modules {
+                    found.push((module, Vec::new()));
+                }
+            }
+            (Grammar::Python, "import_from_statement") => {
+                let Some(module) = self.field_text(node, "module_name") else { return };
+                let mut cursor = node.walk();
+                let names = node
+                    .children_by_field_name("name", &mut cursor)
+                    .map(|n| n.child_by_field_name("name").map_or_else(|| self.text(n), |inner| self.text(inner)))
+                    .collect();
+                found.push((module, names));
+            }
+            (Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx, "import_statement" | "export_statement") => {
+                if let Some(source) = node.child_by_field_name("source") {
+                    found.push((string_content(&self.text(source)), Vec::new()));
+                }
+            }
+            // require('x') and dynamic import('x')
+            (Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx, "call_expression") => {
+                let callee = node.child_by_field_name("function").map(|f| self.text(f));
+                if matches!(callee.as_deref(), Some("require" | "import")) {
+                    let argument = node
+                        .child_by_field_name("arguments")
+                        .and_then(|args| args.named_child(0))
+                        .filter(|arg| arg.kind() == "string");
+                    if let Some(argument) = argument {
+                        found.push((string_content(&self.text(argument)), Vec::new()));
+                    }
+                }
+            }
+            (Grammar::Go, "import_spec") => {
+                if let Some(path) = self.field_text(node, "path") {
+                    found.push((string_content(&path), Vec::new()));
+                }
+            }
+            _ => {}
+        }
+
+        let line = node.start_position().row + 1;
+        self.imports.extend(
+            found
+                .into_iter()
+                .filter(|(module, _)| !module.is_empty())
+                .map(|(module, names)| Import { module, names, line, inline_modules: scope.modules.clone(), kind }),
+        );
+    }
+
+    fn method_container(&self, node: Node, scope: &Scope) -> Option<String> {
+        match (self.grammar, node.kind()) {
+            // Go methods live outside their type; the receiver names it
+            (Grammar::Go, "method_declaration") => node
+                .child_by_field_name("receiver")
+                .and_then(|receiver| find_descendant(receiver, "type_identifier"))
+                .map(|n| self.text(n)),
+            _ => scope.container.clone(),
+        }
+    }
+
+    // Blocks that make their functions methods without declaring a symbol themselves
+    fn implicit_container(&self, node: Node) -> Option<String> {
+        match (self.grammar, node.kind()) {
+            (Grammar::Rust, "impl_item") => self.field_text(node, "type"),
+            _ => None,
+        }
+    }
+
+    fn symbol_at(&self, node: Node, scope: &Scope) -> Option<(SymbolKind, String)> {
+        let function_kind = if scope.in_type { SymbolKind::Method } else { SymbolKind::Function };
+        let named = |kind: SymbolKind| self.field_text(node, "name").map(|name| (kind, name));
+
+        match self.grammar {
+            Grammar::Rust => match node.kind() {
+                "function_item" | "function_signature_item" => named(function_kind),
+                "struct_item" | "union_item" => named(SymbolKind::Struct),
+                "enum_item" => named(SymbolKind::Enum),
+                "trait_item" => named(SymbolKind::Trait),
+                "mod_item" => named(SymbolKind::Module),
+                "const_item" | "static_item" if !scope.in_function => named(SymbolKind::Constant),
+                "type_item" => named(SymbolKind::TypeAlias),
+                _ => None,
+            },
+            Grammar::Python => match node.kind() {
+                "function_definition" => named(function_kind),
+                "class_definition" => named(SymbolKind::Class),
+                // Module-level UPPER_CASE assignments are constants by convention
+                "assignment" if !scope.in_function && !scope.in_type => {
+                    let name = self.field_text(node, "left")?;
+                    let is_constant = node.child_by_field_name("left")?.kind() == "identifier"
+                        && name.chars().any(|c| c.is_ascii_uppercase())
+                        && !name.chars().any(|c| c.is_ascii_lowercase());
+                    is_constant.then_some((SymbolKind::Constant, name))
+                }
+                _ => None,
+            },
+            Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx => match node.kind() {
+                "function_declaration" | "generator_function_declaration" => named(SymbolKind::Function),
+                "method_definition" | "abstract_method_signature" => named(SymbolKind::Method),
+                "class_declaration" | "abstract_class_declaration" | "class" => named(SymbolKind::Class),
+                "interface_declaration" => named(SymbolKind::Interface),
+                "type_alias_declaration" => named(SymbolKind::TypeAlias),
+                "enum_declaration" => named(SymbolKind::Enum),
+                "internal_module" | "module" => named(SymbolKind::Module),
+                // `const handler = async (req) => {...}` and class fields holding arrow functions
+                "variable_declarator" | "public_field_definition" | "field_definition" => {
+                    let name_node = node
+                        .child_by_field_name("name")
+                        .or_else(|| node.child_by_field_name("property"))?;
+                    if name_node.kind() != "identifier" && !name_node.kind().ends_with("property_identifier") {
+                        return None;
+                    }
+                    let name = self.text(name_node);
+                    match node.child_by_field_name("value").map(|v| v.kind()) {
+                        Some("arrow_function" | "function_expression" | "function" | "generator_function") => {
+                            Some((if node.kind() == "variable_declarator" { function_kind } else { SymbolKind::Method }, name))
+                        }
+                        Some(_) if node.kind() == "variable_declarator" && !scope.in_function && !scope.in_type => {
+                            let declaration = node.parent()?;
+                            let is_const = declaration.kind() == "lexical_declaration"
+                                && declaration.child(0).is_some_and(|k| k.kind() == "const");
+                            is_const.then_some((SymbolKind::Constant, name))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+            Grammar::Go => match node.kind() {
+                "function_declaration" => named(SymbolKind::Function),
+                "method_declaration" => named(SymbolKind::Method),
+                "type_spec" => {
+                    let kind = match node.child_by_field_name("type").map(|t| t.kind()) {
+                        Some("struct_type") => SymbolKind::Struct,
+                        Some("interface_type") => SymbolKind::Interface,
+                        _ => SymbolKind::TypeAlias,
+                    };
+                    named(kind)
+                }
+                "type_alias" => named(SymbolKind::TypeAlias),
+                "const_spec" if !scope.in_function => named(SymbolKind::Constant),
+                "package_clause" => first_named_child(node).map(|n| (SymbolKind::Module, self.text(n))),
+                _ => None,
+            },
+            Grammar::Java => match node.kind() {
+                "method_declaration" | "constructor_declaration" | "compact_constructor_declaration" => named(SymbolKind::Method),
+                "class_declaration" | "record_declaration" => named(SymbolKind::Class),
+                "interface_declaration" | "annotation_type_declaration" => named(SymbolKind::Interface),
+                "enum_declaration" => named(SymbolKind::Enum),
+                "package_declaration" => first_named_child(node).map(|n| (SymbolKind::Module, self.text(n))),
+                "constant_declaration" => self.declarator_name(node).map(|name| (SymbolKind::Constant, name)),
+                "field_declaration" => {
+                    let modifiers = node.named_child(0).filter(|m| m.kind() == "modifiers").map(|m| self.text(m))?;
+                    let is_constant = modifiers.contains("static") && modifiers.contains("final");
+                    if is_constant { self.declarator_name(node).map(|name| (SymbolKind::Constant, name)) } else { None }
+                }
+                _ => None,
+            },
+            Grammar::CSharp => match node.kind() {
+                "method_declaration" | "constructor_declaration" | "destructor_declaration" => named(SymbolKind::Method),
+                "local_function_statement" => named(SymbolKind::Function),
+                "class_declaration" | "record_declaration" => named(SymbolKind::Class),
+                "struct_declaration" => named(SymbolKind::Struct),
+                "interface_declaration" => named(SymbolKind::Interface),
+                "enum_declaration" => named(SymbolKind::Enum),
+                "namespace_declaration" | "file_scoped_namespace_declaration" => named(SymbolKind::Module),
+                "field_declaration" => {
+                    let mut cursor = node.walk();
+                    let is_const = node
+                        .named_children(&mut cursor)
+                        .any(|c| c.kind() == "modifier" && self.text(c) == "const");
+                    if is_const { self.declarator_name(node).map(|name| (SymbolKind::Constant, name)) } else { None }
+                }
+                _ => None,
+            },
+            Grammar::Php => match node.kind() {
+                "function_definition" => named(SymbolKind::Function),
+                "method_declaration" => named(SymbolKind::Method),
+                "class_declaration" => named(SymbolKind::Class),
+                "interface_declaration" => named(SymbolKind::Interface),
+                "trait_declaration" => named(SymbolKind::Trait),
+                "enum_declaration" => named(SymbolKind::Enum),
+                "namespace_definition" => named(SymbolKind::Module),
+                "const_element" => first_named_child(node).map(|n| (SymbolKind::Constant, self.text(n))),
+                _ => None,
+            },
+        }
+    }
+
+    // Name of the first `variable_declarator` below a field or constant declaration
+    fn declarator_name(&self, node: Node) -> Option<String> {
+        let declarator = find_descendant(node, "variable_declarator")?;
+        declarator
+            .child_by_field_name("name")
+            .or_else(|| first_named_child(declarator))
+            .map(|n| self.text(n))
+    }
+}
+
+fn string_content(literal: &str) -> String {
+    literal.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string()
+}
+
+/// Expands a Rust use tree like `crate::{a, b::{c, d as e}}` into `crate::a`, `crate::b::c`, `crate::b::d`
+fn expand_use_tree(tree: &str) -> Vec<String> {
+    let tree = tree.trim();
+    let Some(open) = tree.find('{') else {
+        let path = tree.split(" as ").next().unwrap_or_default().trim();
+        return vec![path.trim_end_matches("::*").to_string()];
+    };
+
+    let prefix = tree[..open].trim().trim_end_matches("::");
+    let inner = &tree[open + 1..tree.rfind('}').unwrap_or(tree.len())];
+
+    // Split on top-level commas only
+    let mut items = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&inner[start..]);
+
+    items
+        .into_iter()
+        .filter(|item| !item.trim().is_empty())
+        .flat_map(expand_use_tree)
+        .map(|item| match item.as_str() {
+            "self" => prefix.to_string(),
+            _ if prefix.is_empty() => item,
+            _ => format!("{}::{}", prefix, item),
+        })
+        .collect()
+}
+
+// Signatures without a body: Rust trait methods, TypeScript abstract methods, Java/C#/PHP abstract and interface methods
+fn is_declaration_only(grammar: Grammar, node: Node) -> bool {
+    match (grammar, node.kind()) {
+        (_, "function_signature_item" | "abstract_method_signature") => true,
+        (Grammar::Java | Grammar::CSharp | Grammar::Php, "method_declaration") => node.child_by_field_name("body").is_none(),
+        _ => false,
+    }
+}
+
+fn first_named_child(node: Node) -> Option<Node> {
+    node.named_child(0)
+}
+
+// Breadth-first, so the shallowest match wins
+fn find_descendant<'t>(node: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    let mut queue = std::collections::VecDeque::from([node]);
+    while let Some(current) = queue.pop_front() {
+        let mut cursor = current.walk();
+        for child in current.named_children(&mut cursor) {
+            if child.kind() == kind {
+                return Some(child);
+            }
+            queue.push_back(child);
+        }
+    }
+    None
+}
+
+// ----- Complexity -----
+
+const BRANCHES: &[&str] = &[
+    "if_expression", "if_statement", "elif_clause", "else_if_clause",
+    "while_expression", "while_statement", "do_statement", "loop_expression",
+    "for_expression", "for_statement", "for_in_statement", "enhanced_for_statement", "foreach_statement",
+    "catch_clause", "except_clause",
+    "conditional_expression", "ternary_expression",
+];
+
+// Each arm or case adds a path; the enclosing switch/match itself only adds nesting
+const CASES: &[&str] = &[
+    "match_arm", "case_clause", "switch_case", "switch_label", "switch_section", "switch_rule",
+    "expression_case", "type_case", "communication_case", "case_statement",
+    "switch_expression_arm", "match_conditional_expression",
+];
+
+const SWITCHES: &[&str] = &[
+    "match_expression", "match_statement", "switch_statement", "switch_expression",
+    "expression_switch_statement", "type_switch_statement", "select_statement",
+];
+
+const NESTED_FUNCTIONS: &[&str] = &[
+    "function_item", "function_definition", "function_declaration", "method_definition",
+    "method_declaration", "local_function_statement", "closure_expression", "arrow_function",
+    "function_expression", "lambda", "lambda_expression", "anonymous_function",
+];
+
+fn complexity(grammar: Grammar, function: Node, source: &[u8]) -> Complexity {
+    let mut result = Complexity {
+        cyclomatic: 1,
+        cognitive: 0,
+    };
+    let mut cursor = function.walk();
+    // (node, nesting) pairs still to score; an explicit stack keeps long expression chains off the call stack
+    let mut pending: Vec<(Node, u32)> = function.named_children(&mut cursor).map(|child| (child, 0)).collect();
+    while let Some((node, nesting)) = pending.pop() {
+        // Named nested functions are measured on their own
+        if is_named_nested_function(grammar, node) {
+            continue;
+        }
+        let child_nesting = accumulate(node, source, nesting, &mut result);
+        let mut cursor = node.walk();
+        pending.extend(node.named_children(&mut cursor).map(|child| (child, child_nesting)));
+    }
+    result
+}
+
+// Scores one node and returns the nesting its children are at
+fn accumulate(node: Node, source: &[u8], nesting: u32, result: &mut Complexity) -> u32 {
+    let kind = node.kind();
+
+    let mut child_nesting = nesting;
+    if BRANCHES.contains(&kind) {
+        if kind == "loop_expression" {
+            // An unconditional loop adds no path, only nesting
+        } else {
+            result.cyclomatic += 1;
+        }
+        if is_else_if(node) || kind == "elif_clause" || kind == "else_if_clause" {
+            result.cognitive += 1;
+        } else {
+            result.cognitive += 1 + nesting;
+            child_nesting += 1;
+        }
+    } else if CASES.contains(&kind) {
+        let text = node.utf8_text(source).unwrap_or_default().trim_start();
+        let is_default = text.starts_with("default") || text.starts_with("_ =>") || text.starts_with("case _");
+        if !is_default {
+            result.cyclomatic += 1;
+        }
+    } else if SWITCHES.contains(&kind) {
+        result.cognitive += 1 + nesting;
+        child_nesting += 1;
+    } else if kind == "else_clause" && !has_if_child(node) {
+        result.cognitive += 1;
+    } else if NESTED_FUNCTIONS.contains(&kind) {
+        // Closures and lambdas nest the code inside them
+        child_nesting += 1;
+    } else if is_boolean_operator(node, source) {
+        result.cyclomatic += 1;
+        result.cognitive += 1;
+    }
+    child_nesting
+}
+
+fn is_named_nested_function(grammar: Grammar, node: Node) -> bool {
+    matches!(
+        (grammar, node.kind()),
+        (Grammar::Rust, "function_item")
+            | (Grammar::Python, "function_definition" | "class_definition")
+            | (Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx, "function_declaration" | "class_declaration")
+            | (Grammar::CSharp, "local_function_statement")
+            | (Grammar::Php, "function_definition")
+    )
+}
+
+fn is_else_if(node: Node) -> bool {
+    let Some(parent) = node.parent() else { return false };
+    parent.kind() == "else_clause"
+        || parent
+            .child_by_field_name("alternative")
+            .is_some_and(|alt| alt.id() == node.id())
+}
+
+fn has_if_child(node: Node) -> bool {
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+    children.len() == 1 && children[0].kind().starts_with("if_")
+}
+
+fn is_boolean_operator(node: Node, source: &[u8]) -> bool {
+    match node.kind() {
+        "boolean_operator" => true,
+        "binary_expression" => node
+            .child_by_field_name("operator")
+            .and_then(|op| op.utf8_text(source).ok())
+            .is_some_and(|op| matches!(op, "&&" | "||" | "and" | "or" | "??")),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trait_signatures_are_not_callables() {
+        let source = "pub trait Source {\n    fn read(&self) -> String;\n    fn len(&self) -> usize { self.read().len() }\n}\n";
+        let parsed = parse(Path::new("lib.rs"), source, "Rust").unwrap();
+        let callables: Vec<&str> = parsed.callables().map(|s| s.name.as_str()).collect();
+        assert_eq!(callables, ["len"]);
+        assert!(parsed.symbols.iter().any(|s| s.name == "read" && s.complexity.is_none()));
+    }
+
+    #[test]
+    fn deeply_nested_expressions_do_not_overflow() {
+        let terms = vec!["a && b"; 20_000].join(" || ");
+        let source = format!("function f(a, b) {{\n  return {};\n}}\n", terms);
+        let parsed = parse(Path::new("deep.js"), &source, "JavaScript").unwrap();
+        let complexity = parsed.callables().next().unwrap().complexity.unwrap();
+        assert_eq!(complexity.cyclomatic, 1 + 39_999);
+    }
+}
@@ -0,0 +1,326 @@
+// patterns.rs - Architectural pattern classification
+// Scores MVC, hexagonal, clean, layered, CQRS/event-driven, plugin, microservice and monorepo layouts from evidence
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::graph::DependencyGraph;
+use crate::layers::Layering;
+use crate::source::FileSource;
+
+/// Patterns scoring below this are not named in the summary
+pub const MIN_SCORE: f32 = 0.3;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PatternScore {
+    pub name: String,
+    pub score: f32,
+    /// Describes the repository layout rather than how code is organized
+    #[serde(default)]
+    pub repository_layout: bool,
+    pub evidence: Vec<String>,
+}
+
+struct Scorer {
+    name: &'static str,
+    repository_layout: bool,
+    score: f32,
+    evidence: Vec<String>,
+}
+
+impl Scorer {
+    fn new(name: &'static str) -> Self {
+        Scorer { name, repository_layout: false, score: 0.0, evidence: Vec::new() }
+    }
+
+    fn layout(name: &'static str) -> Self {
+        Scorer { repository_layout: true, ..Scorer::new(name) }
+    }
+
+    fn add(&mut self, weight: f32, evidence: impl Into<String>) {
+        self.score += weight;
+        self.evidence.push(evidence.into());
+    }
+
+    // Adds the weight when a directory with one of `names` exists
+    fn dir(&mut self, layout: &Layout, weight: f32, names: &[&str]) -> bool {
+        match layout.find_dir(names) {
+            Some(path) => {
+                self.add(weight, format!("{}/ directory", path));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn finish(self) -> PatternScore {
+        PatternScore {
+            name: self.name.to_string(),
+            score: self.score.min(1.0),
+            repository_layout: self.repository_layout,
+            evidence: self.evidence,
+        }
+    }
+}
+
+// Directory names and files of the hand-written part of the tree
+struct Layout {
+    files: Vec<PathBuf>,
+    // Lowercased directory name -> shallowest path with that name
+    dirs: BTreeMap<String, String>,
+}
+
+impl Layout {
+    fn new(files: &[PathBuf]) -> Self {
+        let files = files.to_vec();
+
+        let mut dirs: BTreeMap<String, String> = BTreeMap::new();
+        for file in &files {
+            for dir in file.ancestors().skip(1).filter(|d| !d.as_os_str().is_empty()) {
+                let name = dir.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+                let path = dir.to_string_lossy().replace('\\', "/");
+                let entry = dirs.entry(name).or_insert_with(|| path.clone());
+                if path.len() < entry.len() {
+                    *entry = path;
+                }
+            }
+        }
+
+        Layout { files, dirs }
+    }
+
+    fn find_dir(&self, names: &[&str]) -> Option<&str> {
+        names.iter().find_map(|name| self.dirs.get(*name)).map(String::as_str)
+    }
+
+    fn find_file(&self, name: &str) -> Option<&PathBuf> {
+        self.files.iter().find(|p| p.file_name().is_some_and(|n| n == name))
+    }
+
+    fn files_named(&self, matches: impl Fn(&str) -> bool) -> Vec<&PathBuf> {
+        self.files
+            .iter()
+            .filter(|p| matches(&p.file_name().unwrap_or_default().to_string_lossy()))
+            .collect()
+    }
+}
+
+/// Scores every known pattern, highest first; patterns without any evidence are left out
+pub fn classify(
+    source: &dyn FileSource,
+    files: &[PathBuf],
+    graph: &DependencyGraph,
+    layering: &Layering,
+) -> Vec<PatternScore> {
+    let layout = Layout::new(files);
+
+    let mut scores: Vec<PatternScore> = vec![
+        mvc(source, &layout),
+        hexagonal(&layout),
+        clean(&layout),
+        layered(layering),
+        event_driven(&layout),
+        plugins(&layout),
+        microservices(source, &layout),
+        monorepo(source, &layout, graph),
+    ]
+    .into_iter()
+    .map(Scorer::finish)
+    .filter(|p| p.score > 0.0)
+    .collect();
+
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.name.cmp(&b.name)));
+    scores
+}
+
+/// Names the best code-organization pattern, prefixed by the repository layout when one stands out
+pub fn summarize(scores: &[PatternScore]) -> (String, f32) {
+    let best = |layout: bool| scores.iter().find(|p| p.repository_layout == layout && p.score >= MIN_SCORE);
+    match (best(true), best(false)) {
+        (Some(repo), Some(code)) => (format!("{} + {}", repo.name, code.name), repo.score.min(code.score)),
+        (Some(pattern), None) | (None, Some(pattern)) => (pattern.name.clone(), pattern.score),
+        (None, None) => ("Unknown Architecture".to_string(), 0.0),
+    }
+}
+
+// ----- Evidence Rules -----
+
+fn mvc(source: &dyn FileSource, layout: &Layout) -> Scorer {
+    let mut s = Scorer::new("MVC");
+    let models = s.dir(layout, 0.2, &["models", "model"]);
+    let views = s.dir(layout, 0.2, &["views", "view", "templates"]);
+    let controllers = s.dir(layout, 0.25, &["controllers", "controller"]);
+    if models && views && controllers {
+        s.add(0.15, "models, views and controllers side by side");
+    }
+    if layout.find_dir(&["controllers"]).is_some() && source.exists(Path::new("config/routes.rb")) {
+        s.add(0.2, "Rails app (config/routes.rb)");
+    }
+    if layout.find_file("manage.py").is_some() && !layout.files_named(|n| n == "views.py").is_empty() {
+        s.add(0.3, "Django app (manage.py and views.py)");
+    }
+    s
+}
+
+fn hexagonal(layout: &Layout) -> Scorer {
+    let mut s = Scorer::new("Hexagonal (Ports & Adapters)");
+    let ports = s.dir(layout, 0.3, &["ports", "port"]);
+    let adapters = s.dir(layout, 0.3, &["adapters", "adapter"]);
+    if ports && adapters {
+        s.add(0.2, "ports and adapters separated");
+    }
+    if ports || adapters {
+        s.dir(layout, 0.15, &["domain", "core"]);
+    }
+    s
+}
+
+fn clean(layout: &Layout) -> Scorer {
+    let mut s = Scorer::new("Clean Architecture");
+    s.dir(layout, 0.25, &["usecases", "use_cases", "use-cases", "interactors"]);
+    s.dir(layout, 0.2, &["entities", "entity"]);
+    s.dir(layout, 0.15, &["presenters", "gateways", "interface_adapters", "interfaces"]);
+    let rings = ["domain", "application", "infrastructure"]
+        .iter()
+        .filter(|d| layout.dirs.contains_key(**d))
+        .count();
+    if rings == 3 {
+        s.add(0.4, "domain/, application/ and infrastructure/ rings");
+    }
+    s
+}
+
+fn layered(layering: &Layering) -> Scorer {
+    let mut s = Scorer::new("Layered");
+    let populated = layering.layers.iter().filter(|l| !l.files.is_empty()).count();
+    if populated >= 2 {
+        s.add(0.1 * populated as f32, format!("{} layers: {}", populated, layering.names().join(", ")));
+        if !layering.flows.is_empty() {
+            s.add(
+                0.5 * layering.confidence,
+                format!("{:.0}% of cross-layer imports point downward", layering.confidence * 100.0),
+            );
+        }
+    }
+    s
+}
+
+fn event_driven(layout: &Layout) -> Scorer {
+    let mut s = Scorer::new("CQRS / Event-Driven");
+    let commands = layout.find_dir(&["commands", "command"]);
+    let queries = layout.find_dir(&["queries", "query"]);
+    if let (Some(commands), Some(queries)) = (commands, queries) {
+        s.add(0.4, format!("separate {}/ and {}/", commands, queries));
+    }
+    s.dir(layout, 0.25, &["events", "event", "domain_events"]);
+    s.dir(layout, 0.2, &["projections", "event_store", "eventstore", "aggregates"]);
+    s.dir(layout, 0.15, &["subscribers", "listeners", "consumers", "sagas", "handlers"]);
+    let brokers = layout.files_named(|n| {
+        let n = n.to_lowercase();
+        n.contains("kafka") || n.contains("rabbit") || n.contains("eventbus") || n.contains("event_bus")
+    });
+    if let Some(file) = brokers.first() {
+        s.add(0.15, format!("message broker code ({})", file.display()));
+    }
+    // Handler directories are common on their own; they only count alongside real evidence
+    if s.score < 0.25 {
+        return Scorer::new("CQRS / Event-Driven");
+    }
+    s
+}
+
+fn plugins(layout: &Layout) -> Scorer {
+    let mut s = Scorer::new("Plugin Architecture");
+    if let Some(dir) = layout.find_dir(&["plugins", "extensions", "addons", "add-ons"]) {
+        s.add(0.4, format!("{}/ directory", dir));
+        let prefix = format!("{}/", dir);
+        let plugins: BTreeSet<&str> = layout
+            .files
+            .iter()
+            .filter_map(|f| f.to_str()?.strip_prefix(&prefix)?.split('/').next())
+            .collect();
+        if plugins.len() >= 2 {
+            s.add(0.2, format!("{} plugins under {}/", plugins.len(), dir));
+        }
+    }
+    let registries = layout.files_named(|n| {
+        let stem = n.split('.').next().unwrap_or_default().to_lowercase();
+        matches!(stem.as_str(), "plugin" | "plugins" | "registry" | "plugin_registry" | "extension")
+    });
+    if let Some(file) = registries.first() {
+        s.add(0.2, format!("plugin registry ({})", file.display()));
+    }
+    s
+}
+
+fn microservices(source: &dyn FileSource, layout: &Layout) -> Scorer {
+    let mut s = Scorer::layout("Microservices");
+
+    let service_dirs: BTreeSet<String> = layout
+        .files_named(|n| n == "Dockerfile" || n.starts_with("Dockerfile.") || n.ends_with(".dockerfile"))
+        .into_iter()
+        .filter_map(|f| f.parent().filter(|p| !p.as_os_str().is_empty()))
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .collect();
+    if service_dirs.len() >= 2 {
+        let names: Vec<&str> = service_dirs.iter().map(String::as_str).take(5).collect();
+        s.add(
+            (0.3 + 0.1 * service_dirs.len() as f32).min(0.6),
+            format!("{} services with their own Dockerfile ({})", service_dirs.len(), names.join(", ")),
+        );
+    }
+
+    for compose in layout.files_named(|n| n.starts_with("docker-compose") || n.starts_with("compose.")) {
+        let services = source
+            .read_to_string(compose)
+            .and_then(|text| serde_yaml::from_str::<serde_yaml::Value>(&text).ok())
+            .and_then(|yaml| yaml.get("services").and_then(|s| s.as_mapping()).map(|m| m.len()))
+            .unwrap_or(0);
+        if services >= 3 {
+            s.add(0.2, format!("{} defines {} services", compose.display(), services));
+            break;
+        }
+    }
+
+    if service_dirs.len() >= 2 {
+        s.dir(layout, 0.1, &["k8s", "kubernetes", "helm", "charts"]);
+    }
+    s
+}
+
+fn monorepo(source: &dyn FileSource, layout: &Layout, graph: &DependencyGraph) -> Scorer {
+    let mut s = Scorer::layout("Monorepo");
+
+    if source
+        .read_to_string(Path::new("Cargo.toml"))
+        .is_some_and(|text| text.lines().any(|l| l.trim() == "[workspace]"))
+    {
+        s.add(0.4, "Cargo workspace");
+    }
+    if source
+        .read_to_string(Path::new("package.json"))
+        .is_some_and(|text| text.contains("\"workspaces\""))
+    {
+        s.add(0.4, "npm/yarn workspaces in package.json");
+    }
+    for (file, tool) in [
+        ("pnpm-workspace.yaml", "pnpm workspace"),
+        ("lerna.json", "Lerna"),
+        ("nx.json", "Nx"),
+        ("turbo.json", "Turborepo"),
+        ("go.work", "Go workspace"),
+        ("rush.json", "Rush"),
+    ] {
+        if source.exists(Path::new(file)) {
+            s.add(0.4, format!("{} ({})", tool, file));
+        }
+    }
+
+    let packages: BTreeSet<&str> = graph.packages.values().map(String::as_str).filter(|p| *p != ".").collect();
+    if packages.len() >= 2 {
+        s.add(0.3, format!("{} packages with their own manifest", packages.len()));
+        s.dir(layout, 0.1, &["packages", "apps", "crates", "libs", "modules"]);
+    }
+    s
+}
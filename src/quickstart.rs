@@ -0,0 +1,379 @@
+// quickstart.rs - Quick-start command discovery
+// Collects the project's own install, build, test and run commands from scripts, task runners and the README
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::manifests::{Ecosystem, Manifest};
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Install,
+    Build,
+    Test,
+    Run,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Command {
+    pub stage: Stage,
+    pub command: String,
+    /// Where the command was found, e.g. `package.json script "dev"`
+    pub source: String,
+}
+
+// Enough to get going without repeating every target a Makefile has
+const PER_STAGE: usize = 4;
+
+/// Quick-start commands in install, build, test, run order, each cited
+pub fn collect(source: &dyn FileSource, paths: &[PathBuf], manifests: &[Manifest]) -> Vec<Command> {
+    let root = |name: &str| paths.iter().find(|p| p.to_str() == Some(name)).map(PathBuf::as_path);
+    let mut found: Vec<Command> = Vec::new();
+
+    // The README states the author's intent, so it goes first
+    if let Some(readme) = paths.iter().find(|p| {
+        p.components().count() == 1 && p.to_string_lossy().to_lowercase().starts_with("readme")
+    }) {
+        found.extend(source.read_to_string(readme).map(|c| from_readme(readme, &c)).unwrap_or_default());
+    }
+    for name in ["Makefile", "makefile", "GNUmakefile"] {
+        found.extend(root(name).and_then(|p| source.read_to_string(p)).map(|c| from_makefile(name, &c)).unwrap_or_default());
+    }
+    for name in ["justfile", "Justfile", ".justfile"] {
+        found.extend(read_root(source, name).map(|c| from_justfile(name, &c)).unwrap_or_default());
+    }
+    if let Some(content) = root("package.json").and_then(|p| source.read_to_string(p)) {
+        let (manager, install) = package_manager(source);
+        found.extend(from_package_json(&content, manager, install));
+    }
+    for name in [".cargo/config.toml", ".cargo/config"] {
+        found.extend(root(name).and_then(|p| source.read_to_string(p)).map(|c| from_cargo_aliases(name, &c)).unwrap_or_default());
+    }
+    if let Some(content) = root("tox.ini").and_then(|p| source.read_to_string(p)) {
+        found.extend(from_tox(&content));
+    }
+    if let Some(content) = root("noxfile.py").and_then(|p| source.read_to_string(p)) {
+        found.extend(from_noxfile(&content));
+    }
+    for name in ["compose.yaml", "compose.yml", "docker-compose.yml", "docker-compose.yaml"] {
+        if let Some(content) = root(name).and_then(|p| source.read_to_string(p)) {
+            found.extend(from_compose(name, &content));
+            break;
+        }
+    }
+
+    // Ecosystem defaults only fill stages nothing above covered
+    let mut commands: Vec<Command> = Vec::new();
+    for command in found.into_iter().chain(manifest_defaults(source, manifests)) {
+        let covered = commands.iter().filter(|c| c.stage == command.stage).count();
+        let default = command.source.ends_with(MANIFEST_DEFAULT);
+        if commands.iter().any(|c| c.command == command.command)
+            || covered >= PER_STAGE
+            || default && covered > 0
+        {
+            continue;
+        }
+        commands.push(command);
+    }
+    commands.sort_by_key(|c| c.stage);
+    commands
+}
+
+// Hidden files don't reach `paths`, so dotfiles are read directly
+fn read_root(source: &dyn FileSource, name: &str) -> Option<String> {
+    source.read_to_string(Path::new(name))
+}
+
+fn command(stage: Stage, command: String, source: String) -> Command {
+    Command { stage, command, source }
+}
+
+// Conventional target, script and session names
+fn stage_of_name(name: &str) -> Option<Stage> {
+    let name = name.to_lowercase();
+    let is = |words: &[&str]| words.iter().any(|w| name == *w || name.starts_with(&format!("{}-", w)) || name.starts_with(&format!("{}:", w)));
+    if is(&["install", "setup", "bootstrap", "deps", "init", "prepare"]) {
+        Some(Stage::Install)
+    } else if is(&["build", "compile", "all", "dist", "bundle", "release"]) {
+        Some(Stage::Build)
+    } else if is(&["test", "tests", "check", "spec", "coverage", "unit", "e2e"]) {
+        Some(Stage::Test)
+    } else if is(&["run", "dev", "start", "serve", "server", "up", "watch", "develop"]) {
+        Some(Stage::Run)
+    } else {
+        None
+    }
+}
+
+// Free-form shell lines; `None` when the line doesn't read as a setup, build, test or run step
+fn stage_of_command(line: &str) -> Option<Stage> {
+    let has = |w: &str| has_word(line, w);
+    let single = line.split_whitespace().count() == 1;
+    if has("test") || has("pytest") || has("tox") || has("nox") || has("check") {
+        Some(Stage::Test)
+    } else if is_package_install(line) || has("ci") || has("clone") || has("cd") || has("venv") || has("sync") {
+        Some(Stage::Install)
+    } else if has("build") || has("compile") || has("make") && single {
+        Some(Stage::Build)
+    } else if has("run") || has("start") || has("serve") || has("up") || has("dev") || has("exec") {
+        Some(Stage::Run)
+    } else {
+        None
+    }
+}
+
+fn has_word(line: &str, word: &str) -> bool {
+    line.to_lowercase()
+        .split_whitespace()
+        .any(|w| w.trim_matches(['"', '\'']) == word || w.starts_with(&format!("{}:", word)))
+}
+
+// `npm install`, `yarn add`, `pip install` and the like, which READMEs list as alternatives
+fn is_package_install(line: &str) -> bool {
+    has_word(line, "install") || has_word(line, "add") || has_word(line, "i")
+}
+
+// ----- README -----
+
+static HEADING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(#{1,6})\s+(.+?)\s*#*\s*$").unwrap());
+
+const README_SECTIONS: &[&str] = &[
+    "getting started", "quick start", "quickstart", "installation", "install", "setup", "set up",
+    "development", "developing", "building", "build", "running", "run", "local",
+];
+const SHELL_FENCES: &[&str] = &["", "sh", "bash", "shell", "console", "zsh", "shell-session", "terminal"];
+const README_LIMIT: usize = 10;
+
+fn from_readme(path: &Path, content: &str) -> Vec<Command> {
+    let file = path.to_string_lossy().replace('\\', "/");
+    let mut commands = Vec::new();
+    // (level, title) of the quick-start section we're inside, if any
+    let mut section: Option<(usize, String)> = None;
+    let mut fence: Option<String> = None;
+    let mut pending = String::new();
+    // READMEs often show the same install for npm, yarn and pnpm side by side; the first one is enough
+    let mut installed = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("```").or_else(|| trimmed.strip_prefix("~~~")) {
+            fence = match fence {
+                Some(_) => None,
+                None => Some(rest.trim().split([' ', ',', '{']).next().unwrap_or_default().to_lowercase()),
+            };
+            pending.clear();
+            continue;
+        }
+
+        let Some(language) = &fence else {
+            if let Some(caps) = HEADING.captures(trimmed) {
+                let level = caps[1].len();
+                let title = caps[2].trim_matches(|c: char| !c.is_alphanumeric() && c != ' ').trim().to_string();
+                let wanted = README_SECTIONS.iter().any(|s| title.to_lowercase().contains(s));
+                match &section {
+                    // Subsections of a quick-start section belong to it
+                    Some((outer, _)) if level > *outer => {}
+                    _ => {
+                        section = wanted.then_some((level, title));
+                        installed = false;
+                    }
+                }
+            }
+            continue;
+        };
+
+        let Some((_, title)) = &section else { continue };
+        if !SHELL_FENCES.contains(&language.as_str()) || commands.len() >= README_LIMIT {
+            continue;
+        }
+        // Console transcripts mark commands with a prompt; the rest is output
+        let prompted = trimmed.strip_prefix("$ ").or_else(|| trimmed.strip_prefix("> "));
+        let line = match prompted {
+            Some(command) => command.trim(),
+            None if !pending.is_empty() => trimmed,
+            None if language == "console" || language == "shell-session" || language == "terminal" => continue,
+            None => trimmed,
+        };
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        // Continuation lines join the command they belong to
+        if let Some(head) = line.strip_suffix('\\') {
+            pending.push_str(head.trim_end());
+            pending.push(' ');
+            continue;
+        }
+        let full = format!("{}{}", pending, line);
+        pending.clear();
+        let Some(stage) = stage_of_command(&full) else { continue };
+        if is_package_install(&full) {
+            if installed {
+                continue;
+            }
+            installed = true;
+        }
+        commands.push(command(stage, full, format!("{} \"{}\"", file, title)));
+    }
+    commands
+}
+
+// ----- Task runners -----
+
+static MAKE_TARGET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([A-Za-z0-9][\w.-]*)\s*:([^=]|$)").unwrap());
+static JUST_RECIPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^@?([A-Za-z_][\w-]*)[^:]*:([^=]|$)").unwrap());
+
+fn from_makefile(name: &str, content: &str) -> Vec<Command> {
+    content
+        .lines()
+        .filter_map(|line| MAKE_TARGET.captures(line))
+        .filter_map(|caps| {
+            let target = caps.get(1)?.as_str();
+            let stage = stage_of_name(target)?;
+            Some(command(stage, format!("make {}", target), format!("{} target \"{}\"", name, target)))
+        })
+        .collect()
+}
+
+fn from_justfile(name: &str, content: &str) -> Vec<Command> {
+    content
+        .lines()
+        .filter_map(|line| JUST_RECIPE.captures(line))
+        .filter_map(|caps| {
+            let recipe = caps.get(1)?.as_str();
+            // Settings and assignments aren't recipes
+            if matches!(recipe, "set" | "alias" | "export" | "import" | "mod") {
+                return None;
+            }
+            let stage = stage_of_name(recipe)?;
+            Some(command(stage, format!("just {}", recipe), format!("{} recipe \"{}\"", name, recipe)))
+        })
+        .collect()
+}
+
+// The lockfile beside package.json names the package manager and its install command
+fn package_manager(source: &dyn FileSource) -> (&'static str, &'static str) {
+    if source.exists(Path::new("pnpm-lock.yaml")) {
+        ("pnpm", "pnpm install")
+    } else if source.exists(Path::new("yarn.lock")) {
+        ("yarn", "yarn install")
+    } else if source.exists(Path::new("bun.lockb")) || source.exists(Path::new("bun.lock")) {
+        ("bun", "bun install")
+    } else if source.exists(Path::new("package-lock.json")) {
+        ("npm", "npm ci")
+    } else {
+        ("npm", "npm install")
+    }
+}
+
+fn from_package_json(content: &str, manager: &str, install: &str) -> Vec<Command> {
+    let Ok(json) = serde_json::from_str::<Json>(content) else { return Vec::new() };
+    let mut commands = vec![command(Stage::Install, install.to_string(), "package.json".to_string())];
+
+    for (script, _) in json.get("scripts").and_then(|s| s.as_object()).into_iter().flatten() {
+        // Lifecycle hooks run on their own
+        if script.starts_with("pre") || script.starts_with("post") {
+            continue;
+        }
+        let Some(stage) = stage_of_name(script) else { continue };
+        let invocation = match (manager, script.as_str()) {
+            ("npm", "test" | "start") => format!("npm {}", script),
+            ("npm", _) | ("bun", _) => format!("{} run {}", manager, script),
+            _ => format!("{} {}", manager, script),
+        };
+        commands.push(command(stage, invocation, format!("package.json script \"{}\"", script)));
+    }
+    commands
+}
+
+fn from_cargo_aliases(name: &str, content: &str) -> Vec<Command> {
+    let Ok(toml) = content.parse::<toml::Table>() else { return Vec::new() };
+    let Some(aliases) = toml.get("alias").and_then(|a| a.as_table()) else { return Vec::new() };
+    aliases
+        .iter()
+        .filter_map(|(alias, expansion)| {
+            let expansion = match expansion {
+                toml::Value::String(s) => s.clone(),
+                toml::Value::Array(parts) => parts.iter().filter_map(|p| p.as_str()).collect::<Vec<_>>().join(" "),
+                _ => return None,
+            };
+            let first = expansion.split_whitespace().next().unwrap_or_default();
+            let stage = stage_of_name(alias).or_else(|| stage_of_name(first))?;
+            Some(command(stage, format!("cargo {}", alias), format!("{} alias \"{}\" ({})", name, alias, expansion)))
+        })
+        .collect()
+}
+
+static TOX_ENV: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\[testenv(?::([^\]]+))?\]").unwrap());
+static NOX_SESSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^@(?:nox\.)?session[^\n]*\n(?:@[^\n]*\n)*def\s+(\w+)").unwrap());
+
+fn from_tox(content: &str) -> Vec<Command> {
+    TOX_ENV
+        .captures_iter(content)
+        .map(|caps| match caps.get(1) {
+            Some(env) => {
+                let env = env.as_str().trim();
+                let stage = stage_of_name(env).unwrap_or(Stage::Test);
+                command(stage, format!("tox -e {}", env), format!("tox.ini env \"{}\"", env))
+            }
+            None => command(Stage::Test, "tox".to_string(), "tox.ini".to_string()),
+        })
+        .collect()
+}
+
+fn from_noxfile(content: &str) -> Vec<Command> {
+    NOX_SESSION
+        .captures_iter(content)
+        .map(|caps| {
+            let session = &caps[1];
+            let stage = stage_of_name(session).unwrap_or(Stage::Test);
+            command(stage, format!("nox -s {}", session), format!("noxfile.py session \"{}\"", session))
+        })
+        .collect()
+}
+
+fn from_compose(name: &str, content: &str) -> Vec<Command> {
+    let Ok(yaml) = serde_yaml::from_str::<serde_yaml::Value>(content) else { return Vec::new() };
+    let services: Vec<String> = yaml
+        .get("services")
+        .and_then(|s| s.as_mapping())
+        .into_iter()
+        .flatten()
+        .filter_map(|(k, _)| k.as_str().map(str::to_string))
+        .collect();
+    if services.is_empty() {
+        return Vec::new();
+    }
+    // `docker compose` only finds the default names without -f
+    let file = if name == "compose.yaml" || name == "docker-compose.yml" { String::new() } else { format!(" -f {}", name) };
+    vec![command(Stage::Run, format!("docker compose{} up", file), format!("{} services {}", name, services.join(", ")))]
+}
+
+// ----- Manifest defaults -----
+
+const MANIFEST_DEFAULT: &str = "(ecosystem default)";
+
+fn manifest_defaults(source: &dyn FileSource, manifests: &[Manifest]) -> Vec<Command> {
+    let Some(root) = manifests.iter().filter(|m| !m.path.contains('/')).min_by_key(|m| m.ecosystem as u8) else {
+        return Vec::new();
+    };
+    let cite = format!("{} {}", root.path, MANIFEST_DEFAULT);
+    let gradle = if source.exists(Path::new("gradlew")) { "./gradlew" } else { "gradle" };
+    let stages: Vec<(Stage, String)> = match root.ecosystem {
+        Ecosystem::Cargo => vec![(Stage::Build, "cargo build".into()), (Stage::Test, "cargo test".into()), (Stage::Run, "cargo run".into())],
+        Ecosystem::Go => vec![(Stage::Install, "go mod download".into()), (Stage::Build, "go build ./...".into()), (Stage::Test, "go test ./...".into())],
+        Ecosystem::PyPI if root.path == "requirements.txt" => vec![(Stage::Install, "pip install -r requirements.txt".into())],
+        Ecosystem::PyPI => vec![(Stage::Install, "pip install -e .".into())],
+        Ecosystem::Maven if root.path.starts_with("build.gradle") => vec![(Stage::Build, format!("{} build", gradle)), (Stage::Test, format!("{} test", gradle))],
+        Ecosystem::Maven => vec![(Stage::Build, "mvn package".into()), (Stage::Test, "mvn test".into())],
+        Ecosystem::Packagist => vec![(Stage::Install, "composer install".into())],
+        Ecosystem::RubyGems => vec![(Stage::Install, "bundle install".into())],
+        Ecosystem::NuGet => vec![(Stage::Build, "dotnet build".into()), (Stage::Test, "dotnet test".into())],
+        Ecosystem::Npm => Vec::new(),
+    };
+    stages.into_iter().map(|(stage, run)| command(stage, run, cite.clone())).collect()
+}
@@ -0,0 +1,212 @@
+// ranking.rs - File importance ranking
+// Scores files by import-graph centrality, churn, size, public API surface and the tests that exercise them
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::entrypoints::is_test_path;
+use crate::graph::DependencyGraph;
+use crate::parser::{ParsedFile, Symbol};
+use crate::source::FileSource;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileRank {
+    pub path: String,
+    /// 0-1, relative to the most important file
+    pub score: f64,
+    /// PageRank over the import graph
+    pub centrality: f64,
+    pub fan_in: usize,
+    /// Recent commits touching the file
+    pub churn: usize,
+    pub code: usize,
+    /// Public functions, types and constants the file exposes
+    pub public_api: usize,
+    /// Test files importing it or named after it, plus inline test modules
+    pub tests: usize,
+    pub complexity: String,
+    pub importance: String,
+    pub reason: String,
+}
+
+// Centrality dominates: a file everything imports is where reading pays off most
+const WEIGHTS: Weights = Weights { centrality: 0.40, churn: 0.15, size: 0.15, public_api: 0.15, tests: 0.15 };
+
+type Signal = fn(&FileRank) -> f64;
+
+struct Weights {
+    centrality: f64,
+    churn: f64,
+    size: f64,
+    public_api: f64,
+    tests: f64,
+}
+
+/// Every parsed, non-test file, most important first
+pub fn rank(source: &dyn FileSource, graph: &DependencyGraph, parsed_files: &[ParsedFile], code: &HashMap<String, usize>) -> Vec<FileRank> {
+    let metrics = graph.metrics();
+    let churn: BTreeMap<String, usize> = source.churn().into_iter().map(|(p, c)| (path_string(&p), c)).collect();
+    let tests = test_references(graph);
+
+    let mut ranks: Vec<FileRank> = parsed_files
+        .iter()
+        .map(|file| (path_string(&file.path), file))
+        .filter(|(path, _)| !is_test_path(path))
+        .map(|(path, file)| {
+            let content = source.read_to_string(&file.path).unwrap_or_default();
+            let node = metrics.get(&path);
+            let inline_tests = usize::from(content.lines().any(|l| l.trim() == "#[cfg(test)]"));
+            FileRank {
+                score: 0.0,
+                centrality: node.map_or(0.0, |m| m.centrality),
+                fan_in: node.map_or(0, |m| m.fan_in),
+                churn: churn.get(&path).copied().unwrap_or(0),
+                code: code.get(&path).copied().unwrap_or(0),
+                public_api: public_api(file, &content),
+                tests: tests.get(&path).map_or(0, BTreeSet::len) + inline_tests,
+                complexity: complexity(file).to_string(),
+                importance: String::new(),
+                reason: String::new(),
+                path,
+            }
+        })
+        .collect();
+
+    // Each signal is scaled against the project's maximum; counts on a log scale so one giant file doesn't flatten the rest
+    let max = |f: Signal| ranks.iter().map(f).fold(0.0, f64::max);
+    let signals: [(f64, Signal); 5] = [
+        (WEIGHTS.centrality, |r| r.centrality),
+        (WEIGHTS.churn, |r| (1.0 + r.churn as f64).ln()),
+        (WEIGHTS.size, |r| (1.0 + r.code as f64).ln()),
+        (WEIGHTS.public_api, |r| (1.0 + r.public_api as f64).ln()),
+        (WEIGHTS.tests, |r| (1.0 + r.tests as f64).ln()),
+    ];
+    let maxima: Vec<f64> = signals.iter().map(|(_, f)| max(*f)).collect();
+    // Signals nobody has (no git history, no tests) don't drag every score down
+    let available: f64 = signals.iter().zip(&maxima).filter(|(_, m)| **m > 0.0).map(|((w, _), _)| w).sum();
+
+    for rank in &mut ranks {
+        let raw: f64 = signals
+            .iter()
+            .zip(&maxima)
+            .filter(|(_, m)| **m > 0.0)
+            .map(|((weight, signal), max)| weight * signal(rank) / max)
+            .sum();
+        rank.score = if available > 0.0 { raw / available } else { 0.0 };
+    }
+    let top = ranks.iter().map(|r| r.score).fold(0.0, f64::max);
+    for rank in &mut ranks {
+        rank.score = if top > 0.0 { rank.score / top } else { 0.0 };
+    }
+
+    ranks.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    let n = ranks.len();
+    for (i, rank) in ranks.iter_mut().enumerate() {
+        rank.importance = importance(i, n, rank.score).to_string();
+        rank.reason = reason(rank);
+    }
+    ranks
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+// Position in the ranking: the top 5% are critical, the next 15% high, then the middle 30% medium
+fn importance(position: usize, total: usize, score: f64) -> &'static str {
+    let share = (position + 1) as f64 / total.max(1) as f64;
+    if share <= 0.05 || score >= 0.8 {
+        "Critical"
+    } else if share <= 0.20 {
+        "High"
+    } else if share <= 0.50 {
+        "Medium"
+    } else {
+        "Low"
+    }
+}
+
+// Total and worst-function cyclomatic complexity; 10 is where a single function gets hard to follow
+fn complexity(file: &ParsedFile) -> &'static str {
+    let cyclomatic: Vec<u32> = file.callables().filter_map(|s| s.complexity).map(|c| c.cyclomatic).collect();
+    let total: u32 = cyclomatic.iter().sum();
+    let worst = cyclomatic.iter().copied().max().unwrap_or(0);
+    if worst > 20 || total > 100 {
+        "High"
+    } else if worst > 10 || total > 40 {
+        "Medium"
+    } else {
+        "Low"
+    }
+}
+
+// Visibility as each language spells it, read from the symbol's declaration line
+fn public_api(file: &ParsedFile, content: &str) -> usize {
+    let lines: Vec<&str> = content.lines().collect();
+    let extension = file.path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+    let declaration = |symbol: &Symbol| lines.get(symbol.start_line.saturating_sub(1)).map_or("", |l| l.trim_start());
+
+    file.symbols
+        .iter()
+        .filter(|symbol| match extension.as_str() {
+            "rs" => declaration(symbol).starts_with("pub "),
+            "py" => !symbol.name.starts_with('_'),
+            "go" => symbol.name.starts_with(|c: char| c.is_uppercase()),
+            "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" => declaration(symbol).starts_with("export "),
+            "java" | "kt" | "cs" | "php" | "swift" | "scala" => declaration(symbol).contains("public "),
+            _ => symbol.container.is_none(),
+        })
+        .count()
+}
+
+// Test files that import a file, or whose name minus its test affix matches the file's
+fn test_references(graph: &DependencyGraph) -> HashMap<String, BTreeSet<String>> {
+    let mut references: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for edge in graph.edges.iter().filter(|e| is_test_path(&e.from) && !is_test_path(&e.to)) {
+        references.entry(edge.to.clone()).or_default().insert(edge.from.clone());
+    }
+
+    let stem = |path: &str| {
+        let file = path.rsplit('/').next().unwrap_or(path);
+        file.split('.').next().unwrap_or(file).to_string()
+    };
+    let mut by_stem: HashMap<String, Vec<&String>> = HashMap::new();
+    // Generic module names say nothing about which file a test covers
+    let generic = ["mod", "index", "__init__", "main", "lib", "utils"];
+    for file in graph.files.iter().filter(|f| !is_test_path(f) && !generic.contains(&stem(f).as_str())) {
+        by_stem.entry(stem(file)).or_default().push(file);
+    }
+
+    for test in graph.files.iter().filter(|f| is_test_path(f)) {
+        let name = stem(test);
+        let subject = name
+            .strip_prefix("test_")
+            .or_else(|| name.strip_suffix("_test"))
+            .or_else(|| name.strip_suffix("_tests"))
+            .or_else(|| name.strip_suffix("_spec"))
+            .unwrap_or(&name);
+        for file in by_stem.get(subject).into_iter().flatten() {
+            references.entry((*file).clone()).or_default().insert(test.clone());
+        }
+    }
+    references
+}
+
+fn reason(rank: &FileRank) -> String {
+    let mut parts = Vec::new();
+    if rank.fan_in > 0 {
+        parts.push(format!("imported by {} file{}", rank.fan_in, if rank.fan_in == 1 { "" } else { "s" }));
+    }
+    if rank.public_api > 0 {
+        parts.push(format!("{} public item{}", rank.public_api, if rank.public_api == 1 { "" } else { "s" }));
+    }
+    if rank.churn > 0 {
+        parts.push(format!("changed in {} commit{}", rank.churn, if rank.churn == 1 { "" } else { "s" }));
+    }
+    if rank.tests > 0 {
+        parts.push(format!("exercised by {} test{}", rank.tests, if rank.tests == 1 { "" } else { "s" }));
+    }
+    parts.push(format!("{} lines of code", rank.code));
+    parts.join(", ")
+}
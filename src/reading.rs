@@ -0,0 +1,184 @@
+// reading.rs - Onboarding reading path
+// Orders files for a first read: entry points, then core domain types, then the important files
+// with each one's dependencies ahead of the files that use them
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::graph::DependencyGraph;
+use crate::parser::{ParsedFile, SymbolKind};
+use crate::ranking::FileRank;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Where execution starts; read for the shape of the program
+    EntryPoint,
+    /// The types the rest of the code passes around
+    DomainTypes,
+    /// Important files, dependencies before their consumers
+    Core,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Step {
+    pub path: String,
+    pub stage: Stage,
+    pub reason: String,
+    pub code: usize,
+    pub complexity: String,
+    /// Estimated from lines of code, read slower the more complex the file
+    pub minutes: u32,
+}
+
+// Enough for a first day; the rest is better found by following imports from here
+const ENTRY_STEPS: usize = 3;
+const DOMAIN_STEPS: usize = 3;
+const PATH_STEPS: usize = 12;
+
+// Directories and file stems that conventionally hold a project's domain model
+const DOMAIN_NAMES: &[&str] = &["model", "models", "domain", "entity", "entities", "types", "schema", "schemas"];
+
+/// Reading order starting from `entry_points` (path and why it is one), best entry point first
+pub fn path(
+    entry_points: &[(&str, &str)],
+    graph: &DependencyGraph,
+    parsed_files: &[ParsedFile],
+    ranks: &[FileRank],
+) -> Vec<Step> {
+    let by_path: HashMap<&str, &FileRank> = ranks.iter().map(|r| (r.path.as_str(), r)).collect();
+    let mut chosen: HashSet<String> = HashSet::new();
+    let mut steps = Vec::new();
+
+    for (path, reason) in entry_points.iter().take(ENTRY_STEPS) {
+        if chosen.insert(path.to_string()) {
+            steps.push(step(path, Stage::EntryPoint, format!("Entry point: {}", reason), by_path.get(path).copied()));
+        }
+    }
+
+    let parsed: HashMap<String, &ParsedFile> =
+        parsed_files.iter().map(|f| (f.path.to_string_lossy().replace('\\', "/"), f)).collect();
+    let domain: Vec<&FileRank> = ranks
+        .iter()
+        .filter(|r| r.code > 0 && !chosen.contains(&r.path))
+        .filter(|r| parsed.get(&r.path).is_some_and(|f| is_domain(&r.path, f, r.fan_in)))
+        .take(DOMAIN_STEPS)
+        .collect();
+    for rank in dependencies_first(&domain, graph) {
+        chosen.insert(rank.path.clone());
+        let types = type_names(parsed[&rank.path]);
+        let reason = if types.is_empty() {
+            format!("Domain model, {}", rank.reason)
+        } else {
+            format!("Defines {}; {}", types, rank.reason)
+        };
+        steps.push(step(&rank.path, Stage::DomainTypes, reason, Some(rank)));
+    }
+
+    let remaining = PATH_STEPS.saturating_sub(steps.len());
+    let core: Vec<&FileRank> = ranks
+        .iter()
+        .filter(|r| r.code > 0 && !chosen.contains(&r.path))
+        .take(remaining)
+        .collect();
+    let ordered = dependencies_first(&core, graph);
+    for (i, rank) in ordered.iter().enumerate() {
+        // Naming the later files that build on this one says why it comes first
+        let consumers: Vec<&str> = ordered[i + 1..]
+            .iter()
+            .filter(|later| graph.edges.iter().any(|e| e.from == later.path && e.to == rank.path))
+            .map(|later| later.path.as_str())
+            .collect();
+        let reason = if consumers.is_empty() {
+            rank.reason.clone()
+        } else {
+            format!("Read before {}; {}", consumers.join(", "), rank.reason)
+        };
+        steps.push(step(&rank.path, Stage::Core, reason, Some(rank)));
+    }
+    steps
+}
+
+fn step(path: &str, stage: Stage, reason: String, rank: Option<&FileRank>) -> Step {
+    let code = rank.map_or(0, |r| r.code);
+    let complexity = rank.map_or_else(|| "Low".to_string(), |r| r.complexity.clone());
+    Step { path: path.to_string(), stage, reason, minutes: minutes(code, &complexity), code, complexity }
+}
+
+// Careful first-read pace in lines per minute, slower through branchy code
+fn minutes(code: usize, complexity: &str) -> u32 {
+    let pace = match complexity {
+        "High" => 12,
+        "Medium" => 20,
+        _ => 30,
+    };
+    code.div_ceil(pace).max(1) as u32
+}
+
+// Named like a model module, or mostly type definitions shared by several files
+fn is_domain(path: &str, file: &ParsedFile, fan_in: usize) -> bool {
+    let named = path.split('/').any(|part| {
+        let stem = part.split('.').next().unwrap_or(part).to_lowercase();
+        DOMAIN_NAMES.contains(&stem.as_str())
+    });
+    let top_level: Vec<SymbolKind> = file.symbols.iter().filter(|s| s.container.is_none()).map(|s| s.kind).collect();
+    let types = top_level.iter().filter(|k| is_type(**k)).count();
+    named || fan_in >= 2 && types >= 2 && types * 2 >= top_level.len()
+}
+
+fn is_type(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Class | SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Trait | SymbolKind::Interface | SymbolKind::TypeAlias
+    )
+}
+
+fn type_names(file: &ParsedFile) -> String {
+    let names: Vec<&str> = file
+        .symbols
+        .iter()
+        .filter(|s| s.container.is_none() && is_type(s.kind))
+        .map(|s| s.name.as_str())
+        .collect();
+    let mut listed = names.iter().take(3).copied().collect::<Vec<_>>().join(", ");
+    if names.len() > 3 {
+        listed.push_str(&format!(" and {} more", names.len() - 3));
+    }
+    listed
+}
+
+// Depth-first post-order over the imports among `files`, so a file follows everything it uses;
+// files are visited in rank order and cycles are cut where they are first met
+fn dependencies_first<'a>(files: &[&'a FileRank], graph: &DependencyGraph) -> Vec<&'a FileRank> {
+    let index: HashMap<&str, usize> = files.iter().enumerate().map(|(i, r)| (r.path.as_str(), i)).collect();
+    let mut imports: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); files.len()];
+    for edge in &graph.edges {
+        if let (Some(&from), Some(&to)) = (index.get(edge.from.as_str()), index.get(edge.to.as_str())) {
+            if from != to {
+                imports[from].insert(to);
+            }
+        }
+    }
+
+    let mut visited = vec![false; files.len()];
+    let mut ordered = Vec::with_capacity(files.len());
+    for root in 0..files.len() {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        let mut stack = vec![(root, imports[root].iter().copied().collect::<Vec<_>>().into_iter())];
+        while let Some((node, children)) = stack.last_mut() {
+            match children.find(|c| !visited[*c]) {
+                Some(child) => {
+                    visited[child] = true;
+                    stack.push((child, imports[child].iter().copied().collect::<Vec<_>>().into_iter()));
+                }
+                None => {
+                    ordered.push(files[*node]);
+                    stack.pop();
+                }
+            }
+        }
+    }
+    ordered
+}
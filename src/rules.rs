@@ -0,0 +1,149 @@
+// rules.rs - Architecture rule enforcement
+// Evaluates allowed/forbidden dependency rules from config.toml against the import graph
+
+use anyhow::{anyhow, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::graph::{Dependency, DependencyGraph};
+
+pub const BASELINE_PATH: &str = ".codemap/check-baseline.json";
+
+/// A `[[rules]]` entry: files matching `from` must not import `forbid`, and when `allow`
+/// is given may only import files matching it (or `from` itself)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub from: String,
+    #[serde(default)]
+    pub forbid: Vec<String>,
+    #[serde(default)]
+    pub allow: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Violation {
+    pub rule: String,
+    pub dependency: Dependency,
+    pub reason: String,
+}
+
+impl Violation {
+    fn key(&self) -> BaselineEntry {
+        BaselineEntry {
+            rule: self.rule.clone(),
+            from: self.dependency.from.clone(),
+            to: self.dependency.to.clone(),
+        }
+    }
+}
+
+// Line numbers are left out so grandfathered imports survive unrelated edits
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BaselineEntry {
+    pub rule: String,
+    pub from: String,
+    pub to: String,
+}
+
+struct CompiledRule<'a> {
+    rule: &'a Rule,
+    from: GlobSet,
+    forbid: GlobSet,
+    allow: Option<GlobSet>,
+}
+
+/// Every import that breaks one of `rules`, in graph order
+pub fn evaluate(graph: &DependencyGraph, rules: &[Rule]) -> Result<Vec<Violation>> {
+    let compiled = rules
+        .iter()
+        .map(|rule| {
+            Ok(CompiledRule {
+                rule,
+                from: glob_set(&rule.name, std::slice::from_ref(&rule.from))?,
+                forbid: glob_set(&rule.name, &rule.forbid)?,
+                allow: rule.allow.as_deref().map(|allow| glob_set(&rule.name, allow)).transpose()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut violations = Vec::new();
+    for edge in &graph.edges {
+        for rule in &compiled {
+            if !rule.from.is_match(&edge.from) {
+                continue;
+            }
+            let outside = !rule.from.is_match(&edge.to);
+            let reason = if rule.forbid.is_match(&edge.to) {
+                format!("{} must not import {}", rule.rule.from, edge.to)
+            } else if outside && rule.allow.as_ref().is_some_and(|allow| !allow.is_match(&edge.to)) {
+                format!("{} may only import {}", rule.rule.from, rule.rule.allow.as_deref().unwrap_or_default().join(", "))
+            } else {
+                continue;
+            };
+            violations.push(Violation {
+                rule: rule.rule.name.clone(),
+                dependency: edge.clone(),
+                reason: rule.rule.description.clone().unwrap_or(reason),
+            });
+        }
+    }
+    Ok(violations)
+}
+
+// A plain directory like `crates/core` matches everything below it
+fn glob_set(rule: &str, patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_end_matches('/');
+        let mut globs = vec![pattern.to_string()];
+        if !pattern.contains(['*', '?', '[', '{']) {
+            globs.push(format!("{}/**", pattern));
+        }
+        for glob in globs {
+            builder.add(Glob::new(&glob).map_err(|e| anyhow!("Invalid pattern '{}' in rule '{}': {}", glob, rule, e))?);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+// ----- Baseline -----
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Baseline {
+    pub violations: BTreeSet<BaselineEntry>,
+}
+
+impl Baseline {
+    pub fn load() -> Result<Self> {
+        let path = Path::new(BASELINE_PATH);
+        if !path.exists() {
+            return Ok(Baseline::default());
+        }
+        serde_json::from_str(&fs::read_to_string(path)?).map_err(|e| anyhow!("Invalid {}: {}", BASELINE_PATH, e))
+    }
+
+    pub fn save(violations: &[Violation]) -> Result<()> {
+        let baseline = Baseline {
+            violations: violations.iter().map(Violation::key).collect(),
+        };
+        fs::create_dir_all(".codemap")?;
+        fs::write(BASELINE_PATH, serde_json::to_string_pretty(&baseline)?)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, violation: &Violation) -> bool {
+        self.violations.contains(&violation.key())
+    }
+
+    /// Baseline entries that no longer occur and can be dropped
+    pub fn fixed<'a>(&'a self, violations: &[Violation]) -> Vec<&'a BaselineEntry> {
+        let current: BTreeSet<BaselineEntry> = violations.iter().map(Violation::key).collect();
+        self.violations.iter().filter(|entry| !current.contains(*entry)).collect()
+    }
+}
@@ -0,0 +1,288 @@
+// sbom.rs - Software bill of materials export
+// Builds CycloneDX 1.5 and SPDX 2.3 JSON documents from parsed manifests and lockfiles
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::licenses;
+use crate::lockfiles::DependencyReport;
+use crate::manifests::{normalize_python, Ecosystem, Manifest};
+
+/// The project the bill of materials describes
+pub struct Project<'a> {
+    pub name: &'a str,
+    pub license: Option<&'a str>,
+    pub timestamp: &'a str,
+}
+
+struct Component {
+    purl: String,
+    name: String,
+    version: Option<String>,
+    license: Option<String>,
+    /// purls of the components it requires
+    dependencies: BTreeSet<String>,
+}
+
+// Every third-party package once, keyed by purl, plus which of them the project requires directly
+struct Inventory {
+    name: String,
+    version: Option<String>,
+    license: Option<String>,
+    components: BTreeMap<String, Component>,
+    direct: BTreeSet<String>,
+}
+
+impl Inventory {
+    fn collect(project: &Project, manifests: &[Manifest], reports: &[DependencyReport]) -> Self {
+        // The shallowest manifest describes the project itself; the directory name is only a fallback
+        let root = manifests.iter().min_by_key(|m| m.path.matches('/').count());
+        let mut inventory = Inventory {
+            name: root.and_then(|m| m.name.clone()).unwrap_or_else(|| project.name.to_string()),
+            version: root.and_then(|m| m.version.clone()),
+            license: project.license.map(str::to_string).or_else(|| manifests.iter().find_map(|m| m.license.clone())),
+            components: BTreeMap::new(),
+            direct: BTreeSet::new(),
+        };
+
+        for report in reports {
+            let purls: BTreeMap<String, String> = report
+                .packages
+                .iter()
+                .map(|p| (format!("{}@{}", p.name, p.version), purl(report.ecosystem, &p.name, Some(&p.version))))
+                .collect();
+            for package in &report.packages {
+                let purl = purls[&format!("{}@{}", package.name, package.version)].clone();
+                if package.direct {
+                    inventory.direct.insert(purl.clone());
+                }
+                let component = inventory.components.entry(purl.clone()).or_insert_with(|| Component {
+                    purl,
+                    name: package.name.clone(),
+                    version: Some(package.version.clone()),
+                    license: None,
+                    dependencies: BTreeSet::new(),
+                });
+                component.license = component.license.take().or_else(|| package.license.clone());
+                component.dependencies.extend(package.dependencies.iter().filter_map(|d| purls.get(d).cloned()));
+            }
+        }
+
+        // Manifests without a lockfile beside them still declare what the project pulls in
+        let locked = |manifest: &Manifest| {
+            let dir = Path::new(&manifest.path).parent().unwrap_or(Path::new(""));
+            reports.iter().any(|r| {
+                r.ecosystem == manifest.ecosystem && Path::new(&r.lockfile).parent().unwrap_or(Path::new("")) == dir
+            })
+        };
+        for manifest in manifests.iter().filter(|m| !locked(m)) {
+            // Gradle plugin ids aren't Maven coordinates
+            let packages = manifest.dependencies.iter().filter(|d| manifest.ecosystem != Ecosystem::Maven || d.name.contains(':'));
+            for dependency in packages {
+                let version = pinned_version(manifest.ecosystem, dependency.requirement.as_deref());
+                let purl = purl(manifest.ecosystem, &dependency.name, version.as_deref());
+                inventory.direct.insert(purl.clone());
+                inventory.components.entry(purl.clone()).or_insert_with(|| Component {
+                    purl,
+                    name: dependency.name.clone(),
+                    version,
+                    license: None,
+                    dependencies: BTreeSet::new(),
+                });
+            }
+        }
+        inventory
+    }
+}
+
+// Only exact pins name a version; ranges leave it to the resolver
+fn pinned_version(ecosystem: Ecosystem, requirement: Option<&str>) -> Option<String> {
+    let requirement = requirement?.trim();
+    let version = match ecosystem {
+        Ecosystem::PyPI => requirement.strip_prefix("==")?,
+        Ecosystem::Npm | Ecosystem::Cargo => requirement.strip_prefix('=').unwrap_or(requirement),
+        _ => requirement,
+    }
+    .trim();
+    // A bare Cargo version is a caret requirement
+    let exact = version.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && !version.contains(['*', '^', '~', '<', '>', ',', ' ', '[', '(', '$', '|'])
+        && (ecosystem != Ecosystem::Cargo || requirement.starts_with('='));
+    exact.then(|| version.to_string())
+}
+
+/// Package URL (https://github.com/package-url/purl-spec) for a registry package
+pub fn purl(ecosystem: Ecosystem, name: &str, version: Option<&str>) -> String {
+    let (kind, path) = match ecosystem {
+        Ecosystem::Cargo => ("cargo", encode(name)),
+        Ecosystem::Npm => ("npm", name.split('/').map(encode).collect::<Vec<_>>().join("/")),
+        Ecosystem::PyPI => ("pypi", encode(&normalize_python(name))),
+        Ecosystem::Go => ("golang", name.split('/').map(encode).collect::<Vec<_>>().join("/")),
+        Ecosystem::Maven => ("maven", name.split(':').map(encode).collect::<Vec<_>>().join("/")),
+        Ecosystem::Packagist => ("composer", name.split('/').map(encode).collect::<Vec<_>>().join("/")),
+        Ecosystem::RubyGems => ("gem", encode(name)),
+        Ecosystem::NuGet => ("nuget", encode(name)),
+    };
+    match version {
+        Some(version) => format!("pkg:{}/{}@{}", kind, path, encode(version)),
+        None => format!("pkg:{}/{}", kind, path),
+    }
+}
+
+fn encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// Stable per project and run, so re-exporting the same analysis yields the same document
+fn document_id(project: &Project) -> String {
+    let hash = Sha256::digest(format!("{}\n{}", project.name, project.timestamp));
+    let hex: String = hash.iter().take(16).map(|b| format!("{:02x}", b)).collect();
+    format!("{}-{}-4{}-a{}-{}", &hex[..8], &hex[8..12], &hex[13..16], &hex[17..20], &hex[20..32])
+}
+
+// ----- CycloneDX -----
+
+pub fn cyclonedx(project: &Project, manifests: &[Manifest], reports: &[DependencyReport]) -> Value {
+    let inventory = Inventory::collect(project, manifests, reports);
+    let root_ref = format!("codemap:{}", inventory.name);
+    // Free-form license text that isn't an SPDX expression is kept as a name
+    let licenses = |license: &Option<String>| -> Value {
+        match license {
+            Some(license) => match licenses::spdx_expression(license) {
+                Some(expression) => json!([{ "expression": expression }]),
+                None => json!([{ "license": { "name": license } }]),
+            },
+            None => json!([]),
+        }
+    };
+
+    let mut root = json!({
+        "type": "application",
+        "bom-ref": root_ref,
+        "name": inventory.name,
+        "licenses": licenses(&inventory.license),
+    });
+    if let Some(version) = &inventory.version {
+        root["version"] = json!(version);
+    }
+
+    let components: Vec<Value> = inventory
+        .components
+        .values()
+        .map(|c| {
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": c.purl,
+                "name": c.name,
+                "purl": c.purl,
+                "licenses": licenses(&c.license),
+            });
+            if let Some(version) = &c.version {
+                component["version"] = json!(version);
+            }
+            component
+        })
+        .collect();
+
+    let mut dependencies = vec![json!({ "ref": root_ref, "dependsOn": inventory.direct })];
+    dependencies.extend(
+        inventory
+            .components
+            .values()
+            .map(|c| json!({ "ref": c.purl, "dependsOn": c.dependencies })),
+    );
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", document_id(project)),
+        "version": 1,
+        "metadata": {
+            "timestamp": project.timestamp,
+            "tools": {
+                "components": [{ "type": "application", "name": "codemap", "version": env!("CARGO_PKG_VERSION") }]
+            },
+            "component": root,
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+// ----- SPDX -----
+
+pub fn spdx(project: &Project, manifests: &[Manifest], reports: &[DependencyReport]) -> Value {
+    let inventory = Inventory::collect(project, manifests, reports);
+    let ids: BTreeMap<&str, String> = inventory
+        .components
+        .keys()
+        .enumerate()
+        .map(|(i, purl)| (purl.as_str(), format!("SPDXRef-Package-{}", i + 1)))
+        .collect();
+    let root_id = "SPDXRef-Project";
+
+    let mut packages = vec![json!({
+        "name": inventory.name,
+        "SPDXID": root_id,
+        "versionInfo": inventory.version.as_deref().unwrap_or("NOASSERTION"),
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": spdx_license(&inventory.license),
+        "copyrightText": "NOASSERTION",
+    })];
+    packages.extend(inventory.components.values().map(|c| {
+        json!({
+            "name": c.name,
+            "SPDXID": ids[c.purl.as_str()],
+            "versionInfo": c.version.as_deref().unwrap_or("NOASSERTION"),
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": spdx_license(&c.license),
+            "copyrightText": "NOASSERTION",
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": c.purl,
+            }],
+        })
+    }));
+
+    let relationship = |from: &str, kind: &str, to: &str| {
+        json!({ "spdxElementId": from, "relationshipType": kind, "relatedSpdxElement": to })
+    };
+    let mut relationships = vec![relationship("SPDXRef-DOCUMENT", "DESCRIBES", root_id)];
+    relationships.extend(inventory.direct.iter().map(|purl| relationship(root_id, "DEPENDS_ON", &ids[purl.as_str()])));
+    for component in inventory.components.values() {
+        for dependency in &component.dependencies {
+            relationships.push(relationship(&ids[component.purl.as_str()], "DEPENDS_ON", &ids[dependency.as_str()]));
+        }
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": inventory.name,
+        "documentNamespace": format!("https://spdx.org/spdxdocs/{}-{}", encode(&inventory.name), document_id(project)),
+        "creationInfo": {
+            "created": project.timestamp,
+            "creators": [format!("Tool: codemap-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+fn spdx_license(license: &Option<String>) -> String {
+    license.as_deref().and_then(licenses::spdx_expression).unwrap_or_else(|| "NOASSERTION".to_string())
+}
@@ -0,0 +1,320 @@
+// source.rs - File sources for codebase analysis
+// Lets the analyzers read a working directory, a release archive or a git revision the same way
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+// ----- File Source Trait -----
+
+pub trait FileSource {
+    /// Name reported for the project when none is configured
+    fn name(&self) -> String;
+
+    /// Every regular file in the source, relative to its root
+    fn files(&self) -> Vec<PathBuf>;
+
+    /// Raw contents of a file, if it exists
+    fn read(&self, path: &Path) -> Option<Vec<u8>>;
+
+    /// Whether a file or directory exists at the given relative path
+    fn exists(&self, path: &Path) -> bool;
+
+    fn read_to_string(&self, path: &Path) -> Option<String> {
+        self.read(path).and_then(|bytes| String::from_utf8(bytes).ok())
+    }
+
+    /// Number of recent commits that touched each file; empty without git history
+    fn churn(&self) -> BTreeMap<PathBuf, usize> {
+        BTreeMap::new()
+    }
+}
+
+/// Opens the source at `path`: a directory, a `.tar`/`.tar.gz`/`.tgz`/`.zip`
+/// archive, or a git repository read at `rev` (bare repositories default to HEAD).
+pub fn open_source(path: &Path, rev: Option<&str>) -> Result<Box<dyn FileSource>> {
+    if !path.exists() {
+        return Err(anyhow!("Path not found: {}", path.display()));
+    }
+
+    if path.is_dir() {
+        let bare = git2::Repository::open_bare(path).is_ok();
+        if rev.is_some() || bare {
+            return Ok(Box::new(MemorySource::from_git(path, rev.unwrap_or("HEAD"))?));
+        }
+        return Ok(Box::new(DirSource::new(path)?));
+    }
+
+    if rev.is_some() {
+        return Err(anyhow!("--rev can only be used with a git repository"));
+    }
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+    if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+        let file = fs::File::open(path)?;
+        MemorySource::from_tar(archive_stem(&file_name), flate2::read::GzDecoder::new(file))
+            .map(|s| Box::new(s) as Box<dyn FileSource>)
+    } else if file_name.ends_with(".tar") {
+        let file = fs::File::open(path)?;
+        MemorySource::from_tar(archive_stem(&file_name), file)
+            .map(|s| Box::new(s) as Box<dyn FileSource>)
+    } else if file_name.ends_with(".zip") {
+        MemorySource::from_zip(archive_stem(&file_name), path)
+            .map(|s| Box::new(s) as Box<dyn FileSource>)
+    } else {
+        Err(anyhow!("Unsupported source: {} (expected a directory, .tar.gz, .tgz, .tar, .zip or git repository)", path.display()))
+    }
+}
+
+fn archive_stem(file_name: &str) -> String {
+    [".tar.gz", ".tgz", ".tar", ".zip"]
+        .iter()
+        .find_map(|ext| file_name.strip_suffix(ext))
+        .unwrap_or(file_name)
+        .to_string()
+}
+
+// ----- Directory Source -----
+
+pub struct DirSource {
+    root: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(root: &Path) -> Result<Self> {
+        Ok(DirSource {
+            root: root.canonicalize()?,
+        })
+    }
+}
+
+impl FileSource for DirSource {
+    fn name(&self) -> String {
+        self.root
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        WalkDir::new(&self.root)
+            .into_iter()
+            .filter_entry(|e| e.file_name() != ".git")
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| e.path().strip_prefix(&self.root).ok().map(Path::to_path_buf))
+            .collect()
+    }
+
+    fn read(&self, path: &Path) -> Option<Vec<u8>> {
+        let full = self.root.join(path);
+        if !fs::metadata(&full).ok()?.is_file() {
+            return None;
+        }
+        fs::read(full).ok()
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.root.join(path).exists()
+    }
+
+    // The directory may be a subdirectory of the checkout
+    fn churn(&self) -> BTreeMap<PathBuf, usize> {
+        let Ok(repo) = git2::Repository::discover(&self.root) else { return BTreeMap::new() };
+        let Some(workdir) = repo.workdir().and_then(|w| w.canonicalize().ok()) else { return BTreeMap::new() };
+        let prefix = self.root.strip_prefix(&workdir).unwrap_or(Path::new(""));
+        git_churn(&repo, "HEAD", prefix)
+    }
+}
+
+// Commits walked for churn: enough to show which files are active without slowing down long histories
+const CHURN_COMMITS: usize = 1000;
+
+fn git_churn(repo: &git2::Repository, rev: &str, prefix: &Path) -> BTreeMap<PathBuf, usize> {
+    let mut churn = BTreeMap::new();
+    let Ok(start) = repo.revparse_single(rev).and_then(|o| o.peel_to_commit()) else { return churn };
+    let Ok(mut walk) = repo.revwalk() else { return churn };
+    if walk.push(start.id()).is_err() {
+        return churn;
+    }
+
+    for oid in walk.filter_map(|o| o.ok()).take(CHURN_COMMITS) {
+        let Ok(commit) = repo.find_commit(oid) else { continue };
+        // Merges repeat changes their parents already made
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let tree = commit.tree().ok();
+        let parent = commit.parent(0).ok().and_then(|p| p.tree().ok());
+        let Ok(diff) = repo.diff_tree_to_tree(parent.as_ref(), tree.as_ref(), None) else { continue };
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path().and_then(|p| p.strip_prefix(prefix).ok()) {
+                *churn.entry(path.to_path_buf()).or_insert(0) += 1;
+            }
+        }
+    }
+    churn
+}
+
+// ----- In-Memory Source (archives and git) -----
+
+pub struct MemorySource {
+    name: String,
+    files: BTreeMap<PathBuf, Vec<u8>>,
+    /// Repository and revision the files were read from, for history
+    git: Option<(PathBuf, String)>,
+}
+
+impl MemorySource {
+    fn from_tar<R: Read>(name: String, reader: R) -> Result<Self> {
+        let mut archive = tar::Archive::new(reader);
+        let mut files = BTreeMap::new();
+
+        for entry in archive.entries().context("Failed to read tar archive")? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let Some(path) = sanitize(&entry.path()?) else { continue };
+            // The header's size isn't trusted for preallocation; a hostile archive could claim gigabytes
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            files.insert(path, content);
+        }
+
+        Ok(Self::with_common_root_stripped(name, files))
+    }
+
+    fn from_zip(name: String, path: &Path) -> Result<Self> {
+        let file = fs::File::open(path)?;
+        let mut archive = zip::ZipArchive::new(file).context("Failed to read zip archive")?;
+        let mut files = BTreeMap::new();
+
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if !entry.is_file() {
+                continue;
+            }
+            let Some(path) = entry.enclosed_name().and_then(|p| sanitize(&p)) else { continue };
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            files.insert(path, content);
+        }
+
+        Ok(Self::with_common_root_stripped(name, files))
+    }
+
+    fn from_git(repo_path: &Path, rev: &str) -> Result<Self> {
+        let repo = git2::Repository::open(repo_path)
+            .with_context(|| format!("Not a git repository: {}", repo_path.display()))?;
+        let tree = repo
+            .revparse_single(rev)
+            .and_then(|obj| obj.peel_to_tree())
+            .with_context(|| format!("Unknown revision: {}", rev))?;
+
+        let mut files = BTreeMap::new();
+        let mut walk_error = None;
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            // Skip symlinks (0o120000) and submodules, which have no blob content to analyze
+            if entry.kind() != Some(git2::ObjectType::Blob) || entry.filemode() == 0o120000 {
+                return git2::TreeWalkResult::Ok;
+            }
+            match repo.find_blob(entry.id()) {
+                Ok(blob) => {
+                    let path = PathBuf::from(dir).join(entry.name().unwrap_or_default());
+                    files.insert(path, blob.content().to_vec());
+                }
+                Err(e) => {
+                    walk_error = Some(e);
+                    return git2::TreeWalkResult::Abort;
+                }
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+        if let Some(e) = walk_error {
+            return Err(e.into());
+        }
+
+        let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+        let name = workdir
+            .canonicalize()
+            .unwrap_or_else(|_| workdir.to_path_buf())
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .trim_end_matches(".git")
+            .to_string();
+
+        Ok(MemorySource { name, files, git: Some((repo_path.to_path_buf(), rev.to_string())) })
+    }
+
+    // Release archives usually wrap everything in a single `project-1.2.3/` directory;
+    // strip it so root-level files like Cargo.toml are found where the analyzers expect them.
+    fn with_common_root_stripped(name: String, files: BTreeMap<PathBuf, Vec<u8>>) -> Self {
+        let mut roots = files.keys().map(|p| p.components().next());
+        let common = match roots.next() {
+            Some(Some(first)) if roots.all(|c| c == Some(first)) => first.as_os_str().to_os_string(),
+            _ => return MemorySource { name, files, git: None },
+        };
+        if files.keys().any(|p| p.as_os_str() == common) {
+            return MemorySource { name, files, git: None };
+        }
+
+        let files = files
+            .into_iter()
+            .filter_map(|(p, content)| p.strip_prefix(&common).ok().map(|p| (p.to_path_buf(), content)))
+            .collect();
+        MemorySource {
+            name: common.to_string_lossy().to_string(),
+            files,
+            git: None,
+        }
+    }
+}
+
+impl FileSource for MemorySource {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.files.keys().cloned().collect()
+    }
+
+    fn read(&self, path: &Path) -> Option<Vec<u8>> {
+        self.files.get(path).cloned()
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(path) || self.files.keys().any(|p| p.starts_with(path))
+    }
+
+    fn churn(&self) -> BTreeMap<PathBuf, usize> {
+        let Some((repo_path, rev)) = &self.git else { return BTreeMap::new() };
+        match git2::Repository::open(repo_path) {
+            Ok(repo) => git_churn(&repo, rev, Path::new("")),
+            Err(_) => BTreeMap::new(),
+        }
+    }
+}
+
+// Rejects absolute paths and `..` so archive entries can't escape the source root
+fn sanitize(path: &Path) -> Option<PathBuf> {
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if clean.as_os_str().is_empty() {
+        None
+    } else {
+        Some(clean)
+    }
+}
@@ -0,0 +1,236 @@
+// stack.rs - Technology detection from declared dependencies
+// Maps well-known packages to the frameworks, databases and tools they stand for
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::manifests::{normalize_python, Ecosystem, Manifest};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Framework,
+    Database,
+    Tool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Technology {
+    pub name: String,
+    pub category: Category,
+    /// The package that revealed it
+    pub package: String,
+    pub version: Option<String>,
+    pub manifest: String,
+}
+
+use Category::{Database as D, Framework as F, Tool as T};
+use Ecosystem::*;
+
+// (ecosystem, package, technology, category); a trailing `*` matches a name prefix
+const KNOWN_PACKAGES: &[(Ecosystem, &str, &str, Category)] = &[
+    // Rust
+    (Cargo, "axum", "Axum", F),
+    (Cargo, "actix-web", "Actix Web", F),
+    (Cargo, "rocket", "Rocket", F),
+    (Cargo, "warp", "Warp", F),
+    (Cargo, "poem", "Poem", F),
+    (Cargo, "tokio", "Tokio", F),
+    (Cargo, "async-std", "async-std", F),
+    (Cargo, "hyper", "Hyper", F),
+    (Cargo, "tonic", "gRPC (tonic)", F),
+    (Cargo, "tauri", "Tauri", F),
+    (Cargo, "leptos", "Leptos", F),
+    (Cargo, "yew", "Yew", F),
+    (Cargo, "dioxus", "Dioxus", F),
+    (Cargo, "bevy", "Bevy", F),
+    (Cargo, "ratatui", "Ratatui", F),
+    (Cargo, "sqlx", "SQLx", D),
+    (Cargo, "diesel", "Diesel", D),
+    (Cargo, "sea-orm", "SeaORM", D),
+    (Cargo, "rusqlite", "SQLite", D),
+    (Cargo, "tokio-postgres", "PostgreSQL", D),
+    (Cargo, "postgres", "PostgreSQL", D),
+    (Cargo, "redis", "Redis", D),
+    (Cargo, "mongodb", "MongoDB", D),
+    (Cargo, "sled", "sled", D),
+    (Cargo, "clap", "Clap", T),
+    (Cargo, "serde", "Serde", T),
+    (Cargo, "tracing", "tracing", T),
+    (Cargo, "reqwest", "reqwest", T),
+    (Cargo, "criterion", "Criterion", T),
+    // JavaScript / TypeScript
+    (Npm, "react", "React", F),
+    (Npm, "next", "Next.js", F),
+    (Npm, "vue", "Vue", F),
+    (Npm, "nuxt", "Nuxt", F),
+    (Npm, "@angular/core", "Angular", F),
+    (Npm, "svelte", "Svelte", F),
+    (Npm, "@sveltejs/kit", "SvelteKit", F),
+    (Npm, "@remix-run/*", "Remix", F),
+    (Npm, "solid-js", "Solid", F),
+    (Npm, "express", "Express", F),
+    (Npm, "fastify", "Fastify", F),
+    (Npm, "koa", "Koa", F),
+    (Npm, "@nestjs/core", "NestJS", F),
+    (Npm, "hono", "Hono", F),
+    (Npm, "electron", "Electron", F),
+    (Npm, "react-native", "React Native", F),
+    (Npm, "mongoose", "MongoDB (Mongoose)", D),
+    (Npm, "mongodb", "MongoDB", D),
+    (Npm, "pg", "PostgreSQL", D),
+    (Npm, "mysql", "MySQL", D),
+    (Npm, "mysql2", "MySQL", D),
+    (Npm, "sqlite3", "SQLite", D),
+    (Npm, "better-sqlite3", "SQLite", D),
+    (Npm, "redis", "Redis", D),
+    (Npm, "ioredis", "Redis", D),
+    (Npm, "prisma", "Prisma", D),
+    (Npm, "@prisma/client", "Prisma", D),
+    (Npm, "typeorm", "TypeORM", D),
+    (Npm, "sequelize", "Sequelize", D),
+    (Npm, "drizzle-orm", "Drizzle", D),
+    (Npm, "knex", "Knex", D),
+    (Npm, "typescript", "TypeScript", T),
+    (Npm, "vite", "Vite", T),
+    (Npm, "webpack", "webpack", T),
+    (Npm, "@babel/core", "Babel", T),
+    (Npm, "jest", "Jest", T),
+    (Npm, "vitest", "Vitest", T),
+    (Npm, "mocha", "Mocha", T),
+    (Npm, "cypress", "Cypress", T),
+    (Npm, "@playwright/test", "Playwright", T),
+    (Npm, "eslint", "ESLint", T),
+    (Npm, "prettier", "Prettier", T),
+    (Npm, "tailwindcss", "Tailwind CSS", T),
+    (Npm, "storybook", "Storybook", T),
+    // Python
+    (PyPI, "django", "Django", F),
+    (PyPI, "flask", "Flask", F),
+    (PyPI, "fastapi", "FastAPI", F),
+    (PyPI, "starlette", "Starlette", F),
+    (PyPI, "tornado", "Tornado", F),
+    (PyPI, "aiohttp", "aiohttp", F),
+    (PyPI, "pyramid", "Pyramid", F),
+    (PyPI, "streamlit", "Streamlit", F),
+    (PyPI, "celery", "Celery", F),
+    (PyPI, "torch", "PyTorch", F),
+    (PyPI, "tensorflow", "TensorFlow", F),
+    (PyPI, "psycopg", "PostgreSQL", D),
+    (PyPI, "psycopg2", "PostgreSQL", D),
+    (PyPI, "psycopg2-binary", "PostgreSQL", D),
+    (PyPI, "asyncpg", "PostgreSQL", D),
+    (PyPI, "pymongo", "MongoDB", D),
+    (PyPI, "motor", "MongoDB", D),
+    (PyPI, "redis", "Redis", D),
+    (PyPI, "sqlalchemy", "SQLAlchemy", D),
+    (PyPI, "mysqlclient", "MySQL", D),
+    (PyPI, "pymysql", "MySQL", D),
+    (PyPI, "pytest", "pytest", T),
+    (PyPI, "black", "Black", T),
+    (PyPI, "ruff", "Ruff", T),
+    (PyPI, "mypy", "mypy", T),
+    (PyPI, "pandas", "pandas", T),
+    (PyPI, "numpy", "NumPy", T),
+    // Go
+    (Go, "github.com/gin-gonic/gin", "Gin", F),
+    (Go, "github.com/labstack/echo*", "Echo", F),
+    (Go, "github.com/gofiber/fiber*", "Fiber", F),
+    (Go, "github.com/gorilla/mux", "Gorilla Mux", F),
+    (Go, "github.com/go-chi/chi*", "chi", F),
+    (Go, "google.golang.org/grpc", "gRPC", F),
+    (Go, "gorm.io/gorm", "GORM", D),
+    (Go, "github.com/jackc/pgx*", "PostgreSQL", D),
+    (Go, "github.com/lib/pq", "PostgreSQL", D),
+    (Go, "github.com/go-redis/redis*", "Redis", D),
+    (Go, "github.com/redis/go-redis*", "Redis", D),
+    (Go, "go.mongodb.org/mongo-driver*", "MongoDB", D),
+    (Go, "github.com/go-sql-driver/mysql", "MySQL", D),
+    (Go, "github.com/mattn/go-sqlite3", "SQLite", D),
+    (Go, "github.com/spf13/cobra", "Cobra", T),
+    (Go, "github.com/stretchr/testify", "Testify", T),
+    // Java / Kotlin
+    (Maven, "org.springframework.boot*", "Spring Boot", F),
+    (Maven, "org.springframework:*", "Spring", F),
+    (Maven, "io.quarkus*", "Quarkus", F),
+    (Maven, "io.micronaut*", "Micronaut", F),
+    (Maven, "io.ktor*", "Ktor", F),
+    (Maven, "org.postgresql:postgresql", "PostgreSQL", D),
+    (Maven, "mysql:mysql-connector-java", "MySQL", D),
+    (Maven, "com.mysql:mysql-connector-j", "MySQL", D),
+    (Maven, "org.hibernate*", "Hibernate", D),
+    (Maven, "org.mongodb:*", "MongoDB", D),
+    (Maven, "redis.clients:jedis", "Redis", D),
+    (Maven, "junit:junit", "JUnit", T),
+    (Maven, "org.junit.jupiter:*", "JUnit", T),
+    (Maven, "org.projectlombok:lombok", "Lombok", T),
+    // PHP
+    (Packagist, "laravel/framework", "Laravel", F),
+    (Packagist, "symfony/framework-bundle", "Symfony", F),
+    (Packagist, "slim/slim", "Slim", F),
+    (Packagist, "doctrine/orm", "Doctrine", D),
+    (Packagist, "predis/predis", "Redis", D),
+    (Packagist, "phpunit/phpunit", "PHPUnit", T),
+    // Ruby
+    (RubyGems, "rails", "Rails", F),
+    (RubyGems, "sinatra", "Sinatra", F),
+    (RubyGems, "sidekiq", "Sidekiq", F),
+    (RubyGems, "pg", "PostgreSQL", D),
+    (RubyGems, "mysql2", "MySQL", D),
+    (RubyGems, "sqlite3", "SQLite", D),
+    (RubyGems, "redis", "Redis", D),
+    (RubyGems, "mongoid", "MongoDB", D),
+    (RubyGems, "rspec", "RSpec", T),
+    (RubyGems, "rspec-rails", "RSpec", T),
+    (RubyGems, "rubocop", "RuboCop", T),
+    // .NET
+    (NuGet, "Microsoft.AspNetCore*", "ASP.NET Core", F),
+    (NuGet, "Microsoft.EntityFrameworkCore*", "Entity Framework Core", D),
+    (NuGet, "Npgsql*", "PostgreSQL", D),
+    (NuGet, "StackExchange.Redis", "Redis", D),
+    (NuGet, "MongoDB.Driver", "MongoDB", D),
+    (NuGet, "xunit", "xUnit", T),
+    (NuGet, "NUnit", "NUnit", T),
+];
+
+/// Every known technology declared in `manifests`, once per manifest
+pub fn detect(manifests: &[Manifest]) -> Vec<Technology> {
+    let mut seen = BTreeSet::new();
+    let mut found = Vec::new();
+
+    for manifest in manifests {
+        for dependency in &manifest.dependencies {
+            let name = normalize(manifest.ecosystem, &dependency.name);
+            let Some(&(_, _, technology, category)) = KNOWN_PACKAGES
+                .iter()
+                .find(|(ecosystem, package, ..)| *ecosystem == manifest.ecosystem && matches(package, &name))
+            else {
+                continue;
+            };
+            if seen.insert((technology, manifest.path.as_str())) {
+                found.push(Technology {
+                    name: technology.to_string(),
+                    category,
+                    package: dependency.name.clone(),
+                    version: dependency.requirement.clone(),
+                    manifest: manifest.path.clone(),
+                });
+            }
+        }
+    }
+    found
+}
+
+// PyPI names are case-insensitive with `-`, `_` and `.` interchangeable; NuGet ids are case-insensitive
+fn normalize(ecosystem: Ecosystem, name: &str) -> String {
+    match ecosystem {
+        PyPI => normalize_python(name),
+        _ => name.to_string(),
+    }
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+        None => pattern.eq_ignore_ascii_case(name),
+    }
+}
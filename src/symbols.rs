@@ -0,0 +1,121 @@
+// symbols.rs - Project-wide symbol index
+// Records where every function, type, trait, class and constant is defined, for `codemap find`
+
+use anyhow::{anyhow, Result};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::parser::{ParsedFile, SymbolKind};
+
+pub const INDEX_PATH: &str = ".codemap/symbols.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexEntry {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub container: Option<String>,
+    pub path: String,
+    pub line: usize,
+    pub end_line: usize,
+}
+
+impl IndexEntry {
+    /// `Container::name` for methods, plain name otherwise
+    pub fn qualified_name(&self) -> String {
+        match &self.container {
+            Some(container) => format!("{}::{}", container, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SymbolIndex {
+    pub entries: Vec<IndexEntry>,
+}
+
+impl SymbolIndex {
+    pub fn build(parsed_files: &[ParsedFile]) -> Self {
+        let mut entries: Vec<IndexEntry> = parsed_files
+            .iter()
+            .flat_map(|file| {
+                file.symbols.iter().map(move |symbol| IndexEntry {
+                    name: symbol.name.clone(),
+                    kind: symbol.kind,
+                    container: symbol.container.clone(),
+                    path: file.path.to_string_lossy().to_string(),
+                    line: symbol.start_line,
+                    end_line: symbol.end_line,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+
+        SymbolIndex { entries }
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Path::new(INDEX_PATH);
+        if !path.exists() {
+            return Err(anyhow!("No symbol index found. Run 'codemap init' or 'codemap analyze' first."));
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::write(INDEX_PATH, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Fuzzy-matches `query` against symbol names and `Container::name`, best matches first.
+    /// Exact (case-insensitive) name matches always rank above fuzzy ones.
+    pub fn search(&self, query: &str, kinds: &[SymbolKind]) -> Vec<&IndexEntry> {
+        let matcher = SkimMatcherV2::default();
+        let query_lower = query.to_lowercase();
+
+        let mut matches: Vec<(bool, i64, &IndexEntry)> = self
+            .entries
+            .iter()
+            .filter(|entry| kinds.is_empty() || kinds.contains(&entry.kind))
+            .filter_map(|entry| {
+                let score = matcher
+                    .fuzzy_match(&entry.name, query)
+                    .max(matcher.fuzzy_match(&entry.qualified_name(), query))?;
+                let exact = entry.name.to_lowercase() == query_lower
+                    || entry.qualified_name().to_lowercase() == query_lower;
+                Some((exact, score, entry))
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then(b.1.cmp(&a.1))
+                .then(a.2.name.len().cmp(&b.2.name.len()))
+                .then(a.2.path.cmp(&b.2.path))
+        });
+        matches.into_iter().map(|(_, _, entry)| entry).collect()
+    }
+}
+
+/// Parses a `--kind` filter value such as `fn`, `struct` or `const`
+pub fn parse_kind(kind: &str) -> Result<SymbolKind> {
+    match kind.to_lowercase().as_str() {
+        "fn" | "func" | "function" | "functions" => Ok(SymbolKind::Function),
+        "method" | "methods" => Ok(SymbolKind::Method),
+        "class" | "classes" => Ok(SymbolKind::Class),
+        "struct" | "structs" => Ok(SymbolKind::Struct),
+        "enum" | "enums" => Ok(SymbolKind::Enum),
+        "trait" | "traits" => Ok(SymbolKind::Trait),
+        "interface" | "interfaces" => Ok(SymbolKind::Interface),
+        "mod" | "module" | "modules" | "namespace" | "package" => Ok(SymbolKind::Module),
+        "const" | "constant" | "constants" | "static" => Ok(SymbolKind::Constant),
+        "type" | "alias" | "typealias" => Ok(SymbolKind::TypeAlias),
+        other => Err(anyhow!(
+            "Unknown symbol kind: {} (expected function, method, class, struct, enum, trait, interface, module, const or type)",
+            other
+        )),
+    }
+}
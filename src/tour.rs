@@ -0,0 +1,361 @@
+// tour.rs - Interactive codebase tour
+// Walks the onboarding reading path in a full-screen terminal UI: a highlighted preview of each file
+// focused on its key lines, what to look for, and progress saved so a tour can be resumed
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use crate::graph::DependencyGraph;
+use crate::parser::SymbolKind;
+use crate::reading::{Stage, Step};
+use crate::symbols::{IndexEntry, SymbolIndex};
+
+pub const PROGRESS_PATH: &str = ".codemap/tour-progress.json";
+
+/// One file of the tour, with the lines worth reading first
+pub struct Stop {
+    pub path: String,
+    /// 1-based inclusive line range the preview opens on
+    pub focus: Option<(usize, usize)>,
+    pub explanation: Vec<String>,
+    pub minutes: u32,
+}
+
+// ----- Building the tour -----
+
+/// A stop per reading-path step, focused on the symbol that step is about
+pub fn build(steps: &[Step], index: &SymbolIndex, graph: &DependencyGraph) -> Vec<Stop> {
+    steps
+        .iter()
+        .map(|step| {
+            let symbols: Vec<&IndexEntry> = index
+                .entries
+                .iter()
+                .filter(|e| e.path.replace('\\', "/") == step.path && e.container.is_none())
+                .collect();
+            let focus = focus_symbol(step.stage, &symbols);
+
+            let mut explanation = vec![
+                match step.stage {
+                    Stage::EntryPoint => "Where execution begins: read it for the shape of the program.",
+                    Stage::DomainTypes => "Core domain types: the data the rest of the code passes around.",
+                    Stage::Core => "A key file, placed after the files it depends on.",
+                }
+                .to_string(),
+                String::new(),
+                step.reason.clone(),
+                String::new(),
+                format!("~{} min read, {} lines of code, {} complexity", step.minutes, step.code, step.complexity),
+            ];
+            if let Some(symbol) = focus {
+                explanation.push(format!(
+                    "Start at {:?} `{}` (lines {}-{})",
+                    symbol.kind,
+                    symbol.name,
+                    symbol.line,
+                    symbol.end_line
+                ));
+            }
+            let defined: Vec<&str> =
+                symbols.iter().filter(|s| s.kind != SymbolKind::Module).map(|s| s.name.as_str()).collect();
+            if !defined.is_empty() {
+                explanation.push(String::new());
+                explanation.push(format!("Defines: {}", listing(&defined)));
+            }
+            let imports: Vec<&str> =
+                graph.edges.iter().filter(|e| e.from == step.path).map(|e| e.to.as_str()).collect::<BTreeSet<_>>().into_iter().collect();
+            if !imports.is_empty() {
+                explanation.push(format!("Imports: {}", listing(&imports)));
+            }
+            let users: Vec<&str> =
+                graph.edges.iter().filter(|e| e.to == step.path).map(|e| e.from.as_str()).collect::<BTreeSet<_>>().into_iter().collect();
+            if !users.is_empty() {
+                explanation.push(format!("Used by: {}", listing(&users)));
+            }
+
+            Stop {
+                path: step.path.clone(),
+                focus: focus.map(|s| (s.line, s.end_line)),
+                explanation,
+                minutes: step.minutes,
+            }
+        })
+        .collect()
+}
+
+// `main` for entry points, the first type for domain files, else the first top-level definition
+fn focus_symbol<'a>(stage: Stage, symbols: &[&'a IndexEntry]) -> Option<&'a IndexEntry> {
+    let is_type = |kind: SymbolKind| {
+        matches!(kind, SymbolKind::Class | SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Trait | SymbolKind::Interface)
+    };
+    let preferred = match stage {
+        Stage::EntryPoint => symbols.iter().find(|s| s.kind.is_callable() && ["main", "run", "app"].contains(&s.name.as_str())),
+        Stage::DomainTypes => symbols.iter().find(|s| is_type(s.kind)),
+        Stage::Core => None,
+    };
+    preferred
+        .or_else(|| symbols.iter().find(|s| !matches!(s.kind, SymbolKind::Module | SymbolKind::Constant)))
+        .or_else(|| symbols.first())
+        .copied()
+}
+
+fn listing(items: &[&str]) -> String {
+    let mut listed = items.iter().take(8).copied().collect::<Vec<_>>().join(", ");
+    if items.len() > 8 {
+        listed.push_str(&format!(" and {} more", items.len() - 8));
+    }
+    listed
+}
+
+/// The tour as plain text, for when stdout isn't a terminal
+pub fn print(stops: &[Stop]) {
+    for (i, stop) in stops.iter().enumerate() {
+        let focus = stop.focus.map(|(start, end)| format!(":{}-{}", start, end)).unwrap_or_default();
+        println!("\n{}. {}{} (~{} min)", i + 1, stop.path, focus, stop.minutes);
+        for line in stop.explanation.iter().filter(|l| !l.is_empty()) {
+            println!("   {}", line);
+        }
+    }
+}
+
+// ----- Progress -----
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Progress {
+    /// Paths rather than positions, so progress survives a re-analysis that reorders the tour
+    current: Option<String>,
+    visited: BTreeSet<String>,
+    #[serde(default)]
+    updated: String,
+}
+
+impl Progress {
+    // A missing or unreadable file just starts the tour over
+    fn load() -> Self {
+        fs::read_to_string(PROGRESS_PATH).ok().and_then(|text| serde_json::from_str(&text).ok()).unwrap_or_default()
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::create_dir_all(".codemap")?;
+        fs::write(PROGRESS_PATH, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+// ----- Terminal UI -----
+
+// Lines of context shown above the focused range
+const CONTEXT_LINES: usize = 3;
+
+struct Tour {
+    stops: Vec<Stop>,
+    current: usize,
+    visited: BTreeSet<String>,
+    /// First preview line on screen, 0-based
+    scroll: usize,
+    /// Digits typed so far for a jump
+    jump: String,
+    syntaxes: SyntaxSet,
+    theme: Theme,
+    preview: Vec<Line<'static>>,
+}
+
+/// Runs the tour until the user quits, resuming where the last session stopped
+pub fn run(stops: Vec<Stop>) -> Result<()> {
+    let progress = Progress::load();
+    let current = progress.current.as_ref().and_then(|path| stops.iter().position(|s| &s.path == path)).unwrap_or(0);
+    let mut themes = ThemeSet::load_defaults();
+    let mut tour = Tour {
+        stops,
+        current,
+        visited: progress.visited,
+        scroll: 0,
+        jump: String::new(),
+        syntaxes: SyntaxSet::load_defaults_newlines(),
+        theme: themes.themes.remove("base16-ocean.dark").unwrap_or_default(),
+        preview: Vec::new(),
+    };
+    tour.go_to(current);
+
+    let mut terminal = ratatui::try_init()?;
+    let result = tour.event_loop(&mut terminal);
+    ratatui::restore();
+
+    Progress {
+        current: tour.stops.get(tour.current).map(|s| s.path.clone()),
+        visited: tour.visited,
+        updated: chrono::Utc::now().to_rfc3339(),
+    }
+    .save()?;
+    result
+}
+
+impl Tour {
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let last = self.stops.len() - 1;
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Right | KeyCode::Char('n') | KeyCode::Char('l') | KeyCode::Char(' ') => {
+                    self.go_to((self.current + 1).min(last))
+                }
+                KeyCode::Left | KeyCode::Char('p') | KeyCode::Char('h') => self.go_to(self.current.saturating_sub(1)),
+                KeyCode::Home | KeyCode::Char('g') => self.go_to(0),
+                KeyCode::End | KeyCode::Char('G') => self.go_to(last),
+                KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+                KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+                KeyCode::PageDown => self.scroll_by(20),
+                KeyCode::PageUp => self.scroll_by(-20),
+                KeyCode::Char(digit) if digit.is_ascii_digit() => self.jump.push(digit),
+                KeyCode::Backspace => {
+                    self.jump.pop();
+                }
+                KeyCode::Enter => {
+                    if let Ok(stop) = self.jump.parse::<usize>() {
+                        self.go_to(stop.clamp(1, last + 1) - 1);
+                    }
+                    self.jump.clear();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn go_to(&mut self, stop: usize) {
+        self.current = stop;
+        let path = self.stops[stop].path.clone();
+        self.visited.insert(path.clone());
+        self.preview = match fs::read_to_string(&path) {
+            Ok(content) => self.highlight(&path, &content),
+            Err(_) => vec![Line::styled(format!("{} is not in the working directory", path), Style::default().fg(Color::DarkGray))],
+        };
+        self.scroll = self.stops[stop].focus.map_or(0, |(start, _)| start.saturating_sub(1 + CONTEXT_LINES));
+    }
+
+    fn scroll_by(&mut self, lines: isize) {
+        self.scroll = self.scroll.saturating_add_signed(lines).min(self.preview.len().saturating_sub(1));
+    }
+
+    fn highlight(&self, path: &str, content: &str) -> Vec<Line<'static>> {
+        let extension = Path::new(path).extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        // The bundled syntaxes have no TypeScript; JavaScript's is close enough to read by
+        let extension = match extension.as_str() {
+            "ts" | "tsx" | "mts" | "cts" => "js".to_string(),
+            _ => extension,
+        };
+        let syntax = self
+            .syntaxes
+            .find_syntax_by_extension(&extension)
+            .unwrap_or_else(|| self.syntaxes.find_syntax_plain_text());
+        let mut highlighter = HighlightLines::new(syntax, &self.theme);
+
+        LinesWithEndings::from(content)
+            .map(|line| match highlighter.highlight_line(line, &self.syntaxes) {
+                Ok(regions) => Line::from(
+                    regions
+                        .into_iter()
+                        .map(|(style, text)| {
+                            let color = Color::Rgb(style.foreground.r, style.foreground.g, style.foreground.b);
+                            Span::styled(text.trim_end_matches(['\n', '\r']).replace('\t', "    "), Style::default().fg(color))
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                Err(_) => Line::from(line.trim_end_matches(['\n', '\r']).replace('\t', "    ")),
+            })
+            .collect()
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let stop = &self.stops[self.current];
+        let [header, body, progress, help] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1), Constraint::Length(1)])
+                .areas(frame.area());
+        let [code, notes] = Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(body);
+
+        frame.render_widget(
+            Line::from(vec![
+                Span::styled(" codemap tour ", Style::default().fg(Color::Black).bg(Color::Blue).add_modifier(Modifier::BOLD)),
+                Span::raw(format!(" Stop {} of {}: ", self.current + 1, self.stops.len())),
+                Span::styled(stop.path.clone(), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            ]),
+            header,
+        );
+
+        // Line numbers, with a marker down the focused range
+        let visible = code.height.saturating_sub(2) as usize;
+        let gutter = self.preview.len().max(1).to_string().len();
+        let lines: Vec<Line> = self
+            .preview
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(visible)
+            .map(|(i, line)| {
+                let number = i + 1;
+                let focused = stop.focus.is_some_and(|(start, end)| (start..=end).contains(&number));
+                let marker = if focused {
+                    Span::styled("▌", Style::default().fg(Color::Yellow))
+                } else {
+                    Span::raw(" ")
+                };
+                let number_style = if focused {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                let mut spans = vec![Span::styled(format!("{:>gutter$} ", number), number_style), marker, Span::raw(" ")];
+                spans.extend(line.spans.iter().cloned());
+                Line::from(spans)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(stop.path.as_str())), code);
+
+        let explanation: Vec<Line> = stop.explanation.iter().map(|l| Line::from(l.as_str())).collect();
+        frame.render_widget(
+            Paragraph::new(explanation).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title("What to look for")),
+            notes,
+        );
+
+        let remaining: u32 = self.stops[self.current..].iter().map(|s| s.minutes).sum();
+        frame.render_widget(
+            Gauge::default()
+                .gauge_style(Style::default().fg(Color::Blue))
+                .ratio((self.current + 1) as f64 / self.stops.len() as f64)
+                .label(format!(
+                    "{}/{} · {} visited · ~{} min left",
+                    self.current + 1,
+                    self.stops.len(),
+                    self.stops.iter().filter(|s| self.visited.contains(&s.path)).count(),
+                    remaining
+                )),
+            progress,
+        );
+
+        let keys = if self.jump.is_empty() {
+            " ←/p prev  →/n next  ↑↓/jk scroll  PgUp/PgDn page  g/G first/last  number+⏎ jump  q quit".to_string()
+        } else {
+            format!(" Jump to stop {}_  (⏎ to go, Backspace to edit)", self.jump)
+        };
+        frame.render_widget(Line::from(keys).style(Style::default().fg(Color::DarkGray)), help);
+    }
+}